@@ -0,0 +1,27 @@
+use anchor_lang::{prelude::Pubkey, solana_program::hash::Hash};
+use solana_transaction::Transaction;
+
+/// Owner, balance and data of an account read from a backend.
+#[derive(Clone, Debug)]
+pub struct AccountData {
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+/// Where commands read accounts from and send transactions to.
+pub trait Backend {
+    fn account(&self, address: &Pubkey) -> anyhow::Result<Option<AccountData>>;
+
+    /// Accounts owned by `program_id` whose data holds `bytes` at `offset` for every filter.
+    fn program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[(usize, Vec<u8>)],
+    ) -> anyhow::Result<Vec<(Pubkey, AccountData)>>;
+
+    fn latest_blockhash(&self) -> anyhow::Result<Hash>;
+
+    /// Sends a signed transaction and waits for it to land.
+    fn send_transaction(&mut self, transaction: &Transaction) -> anyhow::Result<()>;
+}
@@ -0,0 +1,322 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anchor_escrow::state::{Config, Escrow};
+use anchor_lang::{
+    prelude::{Clock, Pubkey},
+    solana_program::{instruction::Instruction, sysvar},
+    AccountDeserialize, Discriminator,
+};
+use anyhow::{anyhow, bail, Context};
+use clap::{Parser, Subcommand};
+use escrow_client::{
+    ata, config_address, EscrowView, MakeBuilder, RawAccount, RefundBuilder, TakeBuilder,
+    PROGRAM_ID,
+};
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::{AccountData, Backend};
+
+const ONE_WEEK: i64 = 7 * 24 * 60 * 60;
+
+/// Offset of `Escrow::maker`, after the discriminator and the seed.
+const MAKER_OFFSET: usize = 8 + 8;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "escrow-cli",
+    version,
+    about = "Make, take, refund and inspect escrows"
+)]
+pub struct Cli {
+    /// JSON RPC endpoint of the cluster.
+    #[arg(long, short, global = true, default_value = "http://127.0.0.1:8899")]
+    pub url: String,
+
+    /// Keypair file that signs and pays for transactions.
+    #[arg(long, short, global = true, default_value = "~/.config/solana/id.json")]
+    pub keypair: PathBuf,
+
+    /// Print escrows as JSON instead of a table.
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Deposit mint A into a new escrow asking for mint B.
+    Make {
+        #[arg(long)]
+        seed: u64,
+        #[arg(long)]
+        mint_a: Pubkey,
+        #[arg(long)]
+        mint_b: Pubkey,
+        /// Amount of mint A to deposit.
+        #[arg(long)]
+        deposit: u64,
+        /// Amount of mint B asked for the whole deposit.
+        #[arg(long)]
+        receive: u64,
+        /// Seconds until the escrow can no longer be taken.
+        #[arg(long, default_value_t = ONE_WEEK)]
+        expires_in: i64,
+        /// Seconds before the deposit can be refunded.
+        #[arg(long, default_value_t = 0)]
+        lock_period: i64,
+        /// Only this taker may fill the escrow.
+        #[arg(long)]
+        allowed_taker: Option<Pubkey>,
+        /// Lamports paid to whoever closes the escrow after it expires.
+        #[arg(long, default_value_t = 0)]
+        bounty: u64,
+    },
+    /// Fill an escrow, or only `--amount-b` of it.
+    Take {
+        escrow: Pubkey,
+        #[arg(long)]
+        amount_b: Option<u64>,
+    },
+    /// Return the deposit of an escrow to its maker.
+    Refund { escrow: Pubkey },
+    /// Print the decoded state of an escrow.
+    Show { escrow: Pubkey },
+    /// Print every open escrow of a maker.
+    List {
+        #[arg(long)]
+        maker: Pubkey,
+    },
+}
+
+/// Runs the command in `cli` against `backend`, writing what it prints to `out`.
+pub fn run(cli: &Cli, backend: &mut impl Backend, out: &mut impl Write) -> anyhow::Result<()> {
+    match &cli.command {
+        Command::Make {
+            seed,
+            mint_a,
+            mint_b,
+            deposit,
+            receive,
+            expires_in,
+            lock_period,
+            allowed_taker,
+            bounty,
+        } => {
+            let maker = read_keypair(&cli.keypair)?;
+            let mint_a_account = fetch(backend, mint_a, "mint")?;
+            let mint_b_account = fetch(backend, mint_b, "mint")?;
+            let make = MakeBuilder::new(
+                maker.pubkey(),
+                *seed,
+                raw(mint_a, &mint_a_account),
+                raw(mint_b, &mint_b_account),
+            )
+            .map_err(|err| anyhow!("building make: {err}"))?
+            .deposit(*deposit)
+            .receive(*receive)
+            .expiry(now(backend)? + expires_in)
+            .lock_period(*lock_period)
+            .allowed_taker(*allowed_taker)
+            .bounty(*bounty);
+
+            send(backend, &maker, make.instruction())?;
+
+            print_escrows(cli, out, &[view(backend, &make.escrow())?])
+        }
+        Command::Take { escrow, amount_b } => {
+            let taker = read_keypair(&cli.keypair)?;
+            let state = fetch_escrow(backend, escrow)?;
+            let mint_a = fetch_mint(backend, &state.mint_a)?;
+            let mint_b = fetch_mint(backend, &state.mint_b)?;
+            let mut take = TakeBuilder::new(
+                taker.pubkey(),
+                state.maker,
+                state.seed,
+                mint_a.as_ref().map(|mint| raw(&state.mint_a, mint)),
+                mint_b.as_ref().map(|mint| raw(&state.mint_b, mint)),
+                fetch_config(backend)?.fee_recipient,
+            )
+            .map_err(|err| anyhow!("building take: {err}"))?;
+            // A native SOL escrow is only taken by amount, so a whole fill is the amount still owed
+            let amount_b = match amount_b {
+                None if is_native(&state) => Some(state.remaining_b),
+                amount_b => *amount_b,
+            };
+            if let Some(amount_b) = amount_b {
+                take = take
+                    .amount_b(amount_b)
+                    .fills_escrow(amount_b == state.remaining_b);
+            }
+
+            send(backend, &taker, take.instruction())?;
+
+            match backend.account(escrow)? {
+                Some(_) => print_escrows(cli, out, &[view(backend, escrow)?]),
+                None => Ok(writeln!(out, "Escrow {escrow} filled and closed")?),
+            }
+        }
+        Command::Refund { escrow } => {
+            let maker = read_keypair(&cli.keypair)?;
+            let state = fetch_escrow(backend, escrow)?;
+            if state.maker != maker.pubkey() {
+                bail!("only the maker {} can refund escrow {escrow}", state.maker);
+            }
+
+            let mint_a = fetch_mint(backend, &state.mint_a)?;
+            let refund = RefundBuilder::new(
+                state.maker,
+                state.seed,
+                mint_a.as_ref().map(|mint| raw(&state.mint_a, mint)),
+            )
+            .map_err(|err| anyhow!("building refund: {err}"))?;
+
+            send(backend, &maker, refund.instruction())?;
+
+            Ok(writeln!(out, "Escrow {escrow} refunded and closed")?)
+        }
+        Command::Show { escrow } => print_escrows(cli, out, &[view(backend, escrow)?]),
+        Command::List { maker } => {
+            let filters = [
+                (0, Escrow::DISCRIMINATOR.to_vec()),
+                (MAKER_OFFSET, maker.to_bytes().to_vec()),
+            ];
+            let mut escrows = backend.program_accounts(&PROGRAM_ID, &filters)?;
+            escrows.sort_by_key(|(address, _)| *address);
+
+            if escrows.is_empty() && !cli.json {
+                return Ok(writeln!(out, "No escrows for maker {maker}")?);
+            }
+
+            // One escrow that cannot be decoded is reported without hiding the rest
+            let views = escrows
+                .iter()
+                .filter_map(|(address, _)| match view(backend, address) {
+                    Ok(view) => Some(view),
+                    Err(err) => {
+                        eprintln!("skipping escrow {address}: {err:#}");
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            print_escrows(cli, out, &views)
+        }
+    }
+}
+
+// A single escrow prints as a JSON object, a list as an array
+fn print_escrows(cli: &Cli, out: &mut impl Write, views: &[EscrowView]) -> anyhow::Result<()> {
+    match (cli.json, &cli.command, views) {
+        (true, Command::List { .. }, _) => {
+            writeln!(out, "{}", serde_json::to_string_pretty(views)?)?
+        }
+        (true, _, [view]) => writeln!(out, "{}", view.to_json())?,
+        _ => views.iter().try_for_each(|view| write!(out, "{view}"))?,
+    }
+
+    Ok(())
+}
+
+fn read_keypair(path: &Path) -> anyhow::Result<Keypair> {
+    let path = match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    };
+
+    read_keypair_file(&path).map_err(|err| anyhow!("reading keypair {}: {err}", path.display()))
+}
+
+fn fetch_escrow(backend: &impl Backend, address: &Pubkey) -> anyhow::Result<Escrow> {
+    decode_escrow(address, &fetch(backend, address, "escrow")?)
+}
+
+fn decode_escrow(address: &Pubkey, account: &AccountData) -> anyhow::Result<Escrow> {
+    Escrow::try_deserialize(&mut account.data.as_slice())
+        .with_context(|| format!("{address} is not an escrow"))
+}
+
+// Decode an escrow together with its vault and mints
+// A native SOL leg has no mint account, and native SOL is held by the escrow without a vault
+fn view(backend: &impl Backend, address: &Pubkey) -> anyhow::Result<EscrowView> {
+    let escrow = fetch(backend, address, "escrow")?;
+    let state = decode_escrow(address, &escrow)?;
+    let mint_a = fetch_mint(backend, &state.mint_a)?;
+    let mint_b = fetch_mint(backend, &state.mint_b)?;
+    let vault = match &mint_a {
+        Some(mint_a) => {
+            let vault_address = ata(address, &state.mint_a, &mint_a.owner);
+            Some((vault_address, fetch(backend, &vault_address, "vault")?))
+        }
+        None => None,
+    };
+
+    EscrowView::decode(
+        raw(address, &escrow),
+        vault
+            .as_ref()
+            .map(|(vault_address, vault)| raw(vault_address, vault)),
+        mint_a.as_ref().map(|mint| raw(&state.mint_a, mint)),
+        mint_b.as_ref().map(|mint| raw(&state.mint_b, mint)),
+        now(backend)?,
+    )
+    .map_err(|err| anyhow!("decoding escrow {address}: {err}"))
+}
+
+fn raw<'a>(address: &Pubkey, account: &'a AccountData) -> RawAccount<'a> {
+    RawAccount {
+        address: *address,
+        owner: account.owner,
+        lamports: account.lamports,
+        data: &account.data,
+    }
+}
+
+fn fetch(backend: &impl Backend, address: &Pubkey, kind: &str) -> anyhow::Result<AccountData> {
+    backend
+        .account(address)?
+        .with_context(|| format!("{kind} {address} not found"))
+}
+
+fn fetch_config(backend: &impl Backend) -> anyhow::Result<Config> {
+    let account = backend
+        .account(&config_address())?
+        .context("protocol config not initialized")?;
+
+    Ok(Config::try_deserialize(&mut account.data.as_slice())?)
+}
+
+// Native SOL has no mint account to fetch
+fn fetch_mint(backend: &impl Backend, mint: &Pubkey) -> anyhow::Result<Option<AccountData>> {
+    if *mint == Escrow::NATIVE_SOL {
+        return Ok(None);
+    }
+
+    fetch(backend, mint, "mint").map(Some)
+}
+
+fn is_native(escrow: &Escrow) -> bool {
+    escrow.mint_a == Escrow::NATIVE_SOL || escrow.mint_b == Escrow::NATIVE_SOL
+}
+
+fn now(backend: &impl Backend) -> anyhow::Result<i64> {
+    let account = backend
+        .account(&sysvar::clock::ID)?
+        .context("clock sysvar not found")?;
+
+    Ok(bincode::deserialize::<Clock>(&account.data)?.unix_timestamp)
+}
+
+fn send(backend: &mut impl Backend, signer: &Keypair, ix: Instruction) -> anyhow::Result<()> {
+    let message = Message::new(&[ix], Some(&signer.pubkey()));
+    let transaction = Transaction::new(&[signer], message, backend.latest_blockhash()?);
+
+    backend.send_transaction(&transaction)
+}
@@ -0,0 +1,10 @@
+//! Command-line access to the escrow program.
+//!
+//! Commands run against a [`Backend`]: an RPC endpoint in the binary, or an in-process
+//! LiteSVM instance in the integration tests.
+
+mod backend;
+mod commands;
+mod rpc;
+
+pub use {backend::*, commands::*, rpc::RpcBackend};
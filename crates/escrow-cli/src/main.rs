@@ -0,0 +1,9 @@
+use clap::Parser;
+use escrow_cli::{run, Cli, RpcBackend};
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let mut backend = RpcBackend::new(&cli.url);
+
+    run(&cli, &mut backend, &mut std::io::stdout().lock())
+}
@@ -0,0 +1,102 @@
+use anchor_lang::{prelude::Pubkey, solana_program::hash::Hash};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_address::Address;
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    filter::{Memcmp, RpcFilterType},
+};
+use solana_transaction::Transaction;
+
+use crate::{AccountData, Backend};
+
+/// Backend talking to a cluster over JSON RPC.
+pub struct RpcBackend {
+    client: RpcClient,
+}
+
+impl RpcBackend {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed()),
+        }
+    }
+}
+
+impl Backend for RpcBackend {
+    fn account(&self, address: &Pubkey) -> anyhow::Result<Option<AccountData>> {
+        let account = self
+            .client
+            .get_account_with_commitment(&to_address(address), self.client.commitment())?
+            .value;
+
+        Ok(account.map(|account| AccountData {
+            owner: to_pubkey(&account.owner),
+            lamports: account.lamports,
+            data: account.data,
+        }))
+    }
+
+    fn program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[(usize, Vec<u8>)],
+    ) -> anyhow::Result<Vec<(Pubkey, AccountData)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(
+                filters
+                    .iter()
+                    .map(|(offset, bytes)| {
+                        RpcFilterType::Memcmp(Memcmp::new_raw_bytes(*offset, bytes.clone()))
+                    })
+                    .collect(),
+            ),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.client.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+
+        let accounts = self
+            .client
+            .get_program_accounts_with_config(&to_address(program_id), config)?;
+
+        Ok(accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let account = AccountData {
+                    owner: to_pubkey(&account.owner),
+                    lamports: account.lamports,
+                    data: account.data,
+                };
+                (to_pubkey(&address), account)
+            })
+            .collect())
+    }
+
+    fn latest_blockhash(&self) -> anyhow::Result<Hash> {
+        Ok(Hash::new_from_array(
+            self.client.get_latest_blockhash()?.to_bytes(),
+        ))
+    }
+
+    fn send_transaction(&mut self, transaction: &Transaction) -> anyhow::Result<()> {
+        // Both SDKs share the wire format, so the transaction round-trips through bincode
+        let transaction: solana_transaction_v3::Transaction =
+            bincode::deserialize(&bincode::serialize(transaction)?)?;
+        self.client.send_and_confirm_transaction(&transaction)?;
+
+        Ok(())
+    }
+}
+
+fn to_address(pubkey: &Pubkey) -> Address {
+    Address::new_from_array(pubkey.to_bytes())
+}
+
+fn to_pubkey(address: &Address) -> Pubkey {
+    Pubkey::new_from_array(address.to_bytes())
+}
@@ -0,0 +1,552 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        hash::Hash,
+        instruction::Instruction,
+        system_program,
+    },
+    InstructionData, ToAccountMetas,
+};
+use anyhow::anyhow;
+use clap::Parser;
+use escrow_cli::{run, AccountData, Backend, Cli};
+use escrow_client::{
+    ata, config_address, escrow_address, event_authority_address, program_data_address, PROGRAM_ID,
+};
+use litesvm::LiteSVM;
+use litesvm_token::{
+    get_spl_account, spl_token, CreateAssociatedTokenAccount, CreateMint, MintTo, TOKEN_ID,
+};
+use solana_account::Account;
+use solana_keypair::{write_keypair_file, Keypair};
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+// Runs transactions in-process and remembers every account they touch,
+// since LiteSVM cannot enumerate the accounts of a program
+struct SvmBackend {
+    svm: LiteSVM,
+    touched: BTreeSet<Pubkey>,
+}
+
+impl Backend for SvmBackend {
+    fn account(&self, address: &Pubkey) -> anyhow::Result<Option<AccountData>> {
+        Ok(self
+            .svm
+            .get_account(address)
+            .filter(|account| account.lamports > 0)
+            .map(|account| AccountData {
+                owner: account.owner,
+                lamports: account.lamports,
+                data: account.data,
+            }))
+    }
+
+    fn program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[(usize, Vec<u8>)],
+    ) -> anyhow::Result<Vec<(Pubkey, AccountData)>> {
+        let mut accounts = vec![];
+
+        for address in &self.touched {
+            let Some(account) = self.account(address)? else {
+                continue;
+            };
+            let matches = filters.iter().all(|(offset, bytes)| {
+                account.data.get(*offset..offset + bytes.len()) == Some(bytes.as_slice())
+            });
+
+            if account.owner == *program_id && matches {
+                accounts.push((*address, account));
+            }
+        }
+
+        Ok(accounts)
+    }
+
+    fn latest_blockhash(&self) -> anyhow::Result<Hash> {
+        Ok(self.svm.latest_blockhash())
+    }
+
+    fn send_transaction(&mut self, transaction: &Transaction) -> anyhow::Result<()> {
+        self.touched
+            .extend(transaction.message.account_keys.iter().copied());
+        self.svm
+            .send_transaction(transaction.clone())
+            .map_err(|failed| anyhow!("transaction failed: {}", failed.err))?;
+
+        Ok(())
+    }
+}
+
+struct TestEnv {
+    backend: SvmBackend,
+    maker: Keypair,
+    taker: Keypair,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+}
+
+impl TestEnv {
+    // Run the CLI with `args`, signing with `signer`'s keypair file, and return what it printed
+    fn cli(&mut self, signer: &Keypair, args: &[&str]) -> anyhow::Result<String> {
+        let keypair = keypair_file(signer);
+        let cli = Cli::try_parse_from(
+            ["escrow-cli", "--keypair", keypair.to_str().unwrap()]
+                .iter()
+                .chain(args),
+        )?;
+
+        let mut out = vec![];
+        run(&cli, &mut self.backend, &mut out)?;
+
+        Ok(String::from_utf8(out)?)
+    }
+
+    fn make(&mut self, seed: u64, deposit: u64, receive: u64) -> anyhow::Result<String> {
+        let maker = self.maker.insecure_clone();
+        let (mint_a, mint_b) = (self.mint_a.to_string(), self.mint_b.to_string());
+
+        self.cli(
+            &maker,
+            &[
+                "make",
+                "--seed",
+                &seed.to_string(),
+                "--mint-a",
+                &mint_a,
+                "--mint-b",
+                &mint_b,
+                "--deposit",
+                &deposit.to_string(),
+                "--receive",
+                &receive.to_string(),
+            ],
+        )
+    }
+
+    // Send `ix` signed by the maker, outside the CLI, which cannot make native SOL escrows
+    fn send_as_maker(&mut self, ix: Instruction) {
+        let message = Message::new(&[ix], Some(&self.maker.pubkey()));
+        let blockhash = self.backend.latest_blockhash().unwrap();
+        let transaction = Transaction::new(&[&self.maker], message, blockhash);
+
+        self.backend.send_transaction(&transaction).unwrap();
+    }
+
+    // Escrow of `deposit` lamports asking for `receive` of mint B
+    fn make_native(&mut self, seed: u64, deposit: u64, receive: u64) {
+        let escrow = escrow_address(&self.maker.pubkey(), seed);
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: anchor_escrow::accounts::MakeNative {
+                maker: self.maker.pubkey(),
+                mint_b: self.mint_b,
+                config: config_address(),
+                escrow,
+                system_program: system_program::ID,
+                event_authority: event_authority_address(),
+                program: PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: anchor_escrow::instruction::MakeNative {
+                seed,
+                deposit,
+                receive,
+                expiry: i64::MAX,
+                lock_period: 0,
+                allowed_taker: None,
+                bounty: 0,
+            }
+            .data(),
+        };
+
+        self.send_as_maker(ix);
+    }
+
+    // Escrow of `deposit` of mint A asking for `receive` lamports
+    fn make_for_native(&mut self, seed: u64, deposit: u64, receive: u64) {
+        let escrow = escrow_address(&self.maker.pubkey(), seed);
+        let ix = Instruction {
+            program_id: PROGRAM_ID,
+            accounts: anchor_escrow::accounts::MakeForNative {
+                maker: self.maker.pubkey(),
+                mint_a: self.mint_a,
+                maker_ata_a: ata(&self.maker.pubkey(), &self.mint_a, &TOKEN_ID),
+                config: config_address(),
+                escrow,
+                vault: ata(&escrow, &self.mint_a, &TOKEN_ID),
+                associated_token_program: spl_associated_token_account_client::program::ID,
+                token_program: TOKEN_ID,
+                system_program: system_program::ID,
+                event_authority: event_authority_address(),
+                program: PROGRAM_ID,
+            }
+            .to_account_metas(None),
+            data: anchor_escrow::instruction::MakeForNative {
+                seed,
+                deposit,
+                receive,
+                expiry: i64::MAX,
+                lock_period: 0,
+                allowed_taker: None,
+                bounty: 0,
+            }
+            .data(),
+        };
+
+        self.send_as_maker(ix);
+    }
+
+    fn lamports(&self, address: &Pubkey) -> u64 {
+        self.backend.svm.get_balance(address).unwrap_or_default()
+    }
+
+    fn balance(&self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        get_spl_account::<spl_token::state::Account>(
+            &self.backend.svm,
+            &ata(owner, mint, &TOKEN_ID),
+        )
+        .unwrap()
+        .amount
+    }
+}
+
+fn keypair_file(keypair: &Keypair) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("escrow-cli-{}.json", keypair.pubkey()));
+    write_keypair_file(keypair, &path).unwrap();
+
+    path
+}
+
+// Maker holds 1,000 Mint A and taker holds 1,000 Mint B, with a fee-free protocol config
+fn setup() -> TestEnv {
+    let mut svm = LiteSVM::new();
+
+    let so_path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/anchor_escrow.so");
+    let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
+    svm.add_program(PROGRAM_ID, &program_data);
+
+    let maker = Keypair::new();
+    let taker = Keypair::new();
+    svm.airdrop(&maker.pubkey(), 10_000_000_000).unwrap();
+    svm.airdrop(&taker.pubkey(), 10_000_000_000).unwrap();
+
+    // Only the upgrade authority can initialize the config
+    let upgrade_authority = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(maker.pubkey()),
+    })
+    .unwrap();
+    svm.set_account(
+        program_data_address(),
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(upgrade_authority.len()),
+            data: upgrade_authority,
+            owner: bpf_loader_upgradeable::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+
+    let initialize_config_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: anchor_escrow::accounts::InitializeConfig {
+            admin: maker.pubkey(),
+            program_data: program_data_address(),
+            config: config_address(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_escrow::instruction::InitializeConfig {
+            fee_bps: 0,
+            fee_recipient: Pubkey::new_unique(),
+            oracle_program: Pubkey::new_unique(),
+        }
+        .data(),
+    };
+    let message = Message::new(&[initialize_config_ix], Some(&maker.pubkey()));
+    let transaction = Transaction::new(&[&maker], message, svm.latest_blockhash());
+    svm.send_transaction(transaction).unwrap();
+
+    let mint_a = CreateMint::new(&mut svm, &maker)
+        .decimals(6)
+        .authority(&maker.pubkey())
+        .send()
+        .unwrap();
+    let mint_b = CreateMint::new(&mut svm, &maker)
+        .decimals(6)
+        .authority(&maker.pubkey())
+        .send()
+        .unwrap();
+
+    let maker_ata_a = CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint_a)
+        .send()
+        .unwrap();
+    MintTo::new(&mut svm, &maker, &mint_a, &maker_ata_a, 1_000_000_000)
+        .send()
+        .unwrap();
+
+    let taker_ata_b = CreateAssociatedTokenAccount::new(&mut svm, &taker, &mint_b)
+        .send()
+        .unwrap();
+    MintTo::new(&mut svm, &maker, &mint_b, &taker_ata_b, 1_000_000_000)
+        .send()
+        .unwrap();
+
+    TestEnv {
+        backend: SvmBackend {
+            svm,
+            touched: BTreeSet::new(),
+        },
+        maker,
+        taker,
+        mint_a,
+        mint_b,
+    }
+}
+
+#[test]
+fn test_make_and_show() {
+    let mut env = setup();
+
+    let out = env.make(1, 100, 250).unwrap();
+
+    let escrow = escrow_address(&env.maker.pubkey(), 1);
+    assert!(out.contains(&format!("Escrow {escrow}")));
+    assert!(out.contains("deposit:          0.0001 (100)"));
+    assert!(out.contains("receive:          0.00025 (250)"));
+    assert!(out.contains("price:            2.5 B per A"));
+    assert!(out.contains("allowed_taker:    anyone"));
+    assert!(out.contains("vault_balance:    0.0001 (100)"));
+    assert!(out.contains("pda_valid:        yes"));
+    assert!(out.contains("vault_consistent: yes"));
+    assert_eq!(env.balance(&escrow, &env.mint_a), 100);
+
+    let maker = env.maker.insecure_clone();
+    let shown = env.cli(&maker, &["show", &escrow.to_string()]).unwrap();
+    assert_eq!(shown, out);
+}
+
+#[test]
+fn test_show_json() {
+    let mut env = setup();
+    env.make(1, 1_500_000, 3_000_000).unwrap();
+
+    let escrow = escrow_address(&env.maker.pubkey(), 1);
+    let maker = env.maker.insecure_clone();
+    let out = env
+        .cli(&maker, &["show", &escrow.to_string(), "--json"])
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+    assert_eq!(json["address"], escrow.to_string());
+    assert_eq!(json["maker"], maker.pubkey().to_string());
+    assert_eq!(json["deposit"]["amount"], 1_500_000);
+    assert_eq!(json["deposit"]["ui_amount"], "1.5");
+    assert_eq!(json["receive"]["ui_amount"], "3");
+    assert_eq!(json["price"], 2.0);
+    assert_eq!(json["allowed_taker"], serde_json::Value::Null);
+    assert_eq!(json["vault_balance"]["ui_amount"], "1.5");
+    assert_eq!(json["pda_valid"], true);
+    assert_eq!(json["vault_consistent"], true);
+
+    // A list prints an array, empty when the maker has no escrows
+    let out = env
+        .cli(
+            &maker,
+            &["list", "--maker", &maker.pubkey().to_string(), "--json"],
+        )
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+
+    let taker = env.taker.pubkey().to_string();
+    let out = env
+        .cli(&maker, &["list", "--maker", &taker, "--json"])
+        .unwrap();
+    assert_eq!(out.trim(), "[]");
+}
+
+#[test]
+fn test_take() {
+    let mut env = setup();
+    env.make(1, 100, 250).unwrap();
+
+    let escrow = escrow_address(&env.maker.pubkey(), 1).to_string();
+    let taker = env.taker.insecure_clone();
+    let out = env.cli(&taker, &["take", &escrow]).unwrap();
+
+    assert!(out.contains("filled and closed"));
+    assert_eq!(env.balance(&taker.pubkey(), &env.mint_a), 100);
+    assert_eq!(env.balance(&env.maker.pubkey(), &env.mint_b), 250);
+}
+
+#[test]
+fn test_take_partial() {
+    let mut env = setup();
+    env.make(1, 100, 250).unwrap();
+
+    let escrow = escrow_address(&env.maker.pubkey(), 1).to_string();
+    let taker = env.taker.insecure_clone();
+    let out = env
+        .cli(&taker, &["take", &escrow, "--amount-b", "50"])
+        .unwrap();
+
+    assert!(out.contains("remaining_a:      0.00008 (80)"));
+    assert!(out.contains("remaining_b:      0.0002 (200)"));
+    assert!(out.contains("vault_balance:    0.00008 (80)"));
+    assert_eq!(env.balance(&taker.pubkey(), &env.mint_a), 20);
+}
+
+#[test]
+fn test_refund() {
+    let mut env = setup();
+    env.make(1, 100, 250).unwrap();
+
+    let escrow = escrow_address(&env.maker.pubkey(), 1);
+
+    // Only the maker's keypair can refund
+    let taker = env.taker.insecure_clone();
+    let err = env
+        .cli(&taker, &["refund", &escrow.to_string()])
+        .unwrap_err();
+    assert!(err.to_string().contains("only the maker"));
+
+    let maker = env.maker.insecure_clone();
+    let out = env.cli(&maker, &["refund", &escrow.to_string()]).unwrap();
+
+    assert!(out.contains("refunded and closed"));
+    assert_eq!(env.balance(&maker.pubkey(), &env.mint_a), 1_000_000_000);
+    assert!(env.backend.account(&escrow).unwrap().is_none());
+}
+
+#[test]
+fn test_list_by_maker() {
+    let mut env = setup();
+    env.make(1, 100, 250).unwrap();
+    env.make(2, 300, 600).unwrap();
+
+    let maker = env.maker.insecure_clone();
+    let out = env
+        .cli(&maker, &["list", "--maker", &maker.pubkey().to_string()])
+        .unwrap();
+
+    assert_eq!(out.matches("Escrow ").count(), 2);
+    assert!(out.contains(&format!("Escrow {}", escrow_address(&maker.pubkey(), 1))));
+    assert!(out.contains(&format!("Escrow {}", escrow_address(&maker.pubkey(), 2))));
+
+    let taker = env.taker.insecure_clone();
+    let out = env
+        .cli(&maker, &["list", "--maker", &taker.pubkey().to_string()])
+        .unwrap();
+    assert!(out.contains("No escrows"));
+}
+
+#[test]
+fn test_show_missing_escrow_fails() {
+    let mut env = setup();
+
+    let maker = env.maker.insecure_clone();
+    let missing = Pubkey::new_unique();
+    let err = env
+        .cli(&maker, &["show", &missing.to_string()])
+        .unwrap_err();
+
+    assert!(err.to_string().contains("not found"));
+}
+
+#[test]
+fn test_native_escrow_show_and_take() {
+    let mut env = setup();
+    env.make_native(1, 1_000_000_000, 250);
+
+    // The lamports held by the escrow stand in for the vault
+    let escrow = escrow_address(&env.maker.pubkey(), 1);
+    let maker = env.maker.insecure_clone();
+    let out = env.cli(&maker, &["show", &escrow.to_string()]).unwrap();
+    assert!(out.contains("deposit:          1 (1000000000)"));
+    assert!(out.contains(&format!("vault:            {escrow}")));
+    assert!(out.contains("vault_balance:    1 (1000000000)"));
+    assert!(out.contains("pda_valid:        yes"));
+    assert!(out.contains("vault_consistent: yes"));
+
+    // Without `--amount-b` the whole escrow is taken
+    let taker = env.taker.insecure_clone();
+    let before = env.lamports(&taker.pubkey());
+    let out = env.cli(&taker, &["take", &escrow.to_string()]).unwrap();
+
+    assert!(out.contains("filled and closed"));
+    assert!(env.lamports(&taker.pubkey()) > before + 990_000_000);
+    assert_eq!(env.balance(&maker.pubkey(), &env.mint_b), 250);
+}
+
+#[test]
+fn test_take_for_native() {
+    let mut env = setup();
+    env.make_for_native(1, 100, 1_000_000);
+
+    let escrow = escrow_address(&env.maker.pubkey(), 1);
+    let maker = env.maker.insecure_clone();
+    let out = env.cli(&maker, &["show", &escrow.to_string()]).unwrap();
+    assert!(out.contains("receive:          0.001 (1000000)"));
+    assert!(out.contains("vault_consistent: yes"));
+
+    let taker = env.taker.insecure_clone();
+    let before = env.lamports(&maker.pubkey());
+    let out = env
+        .cli(
+            &taker,
+            &["take", &escrow.to_string(), "--amount-b", "400000"],
+        )
+        .unwrap();
+    assert!(out.contains("remaining_b:      0.0006 (600000)"));
+    assert_eq!(env.balance(&taker.pubkey(), &env.mint_a), 40);
+    assert_eq!(env.lamports(&maker.pubkey()), before + 400_000);
+}
+
+#[test]
+fn test_native_refund() {
+    let mut env = setup();
+    env.make_native(1, 1_000_000_000, 250);
+
+    let escrow = escrow_address(&env.maker.pubkey(), 1);
+    let maker = env.maker.insecure_clone();
+    let before = env.lamports(&maker.pubkey());
+    let out = env.cli(&maker, &["refund", &escrow.to_string()]).unwrap();
+
+    assert!(out.contains("refunded and closed"));
+    assert!(env.lamports(&maker.pubkey()) > before + 1_000_000_000);
+    assert!(env.backend.account(&escrow).unwrap().is_none());
+}
+
+#[test]
+fn test_list_skips_undecodable_escrow() {
+    let mut env = setup();
+    env.make(1, 100, 250).unwrap();
+    env.make_native(2, 1_000_000_000, 250);
+    env.make(3, 300, 600).unwrap();
+
+    // Cut the third escrow short, past the maker, so it still matches the listing filters
+    let broken = escrow_address(&env.maker.pubkey(), 3);
+    let mut account = env.backend.svm.get_account(&broken).unwrap();
+    account.data.truncate(8 + 8 + 32);
+    env.backend.svm.set_account(broken, account).unwrap();
+
+    let maker = env.maker.insecure_clone();
+    let out = env
+        .cli(&maker, &["list", "--maker", &maker.pubkey().to_string()])
+        .unwrap();
+
+    assert_eq!(out.matches("Escrow ").count(), 2);
+    assert!(out.contains(&format!("Escrow {}", escrow_address(&maker.pubkey(), 1))));
+    assert!(out.contains(&format!("Escrow {}", escrow_address(&maker.pubkey(), 2))));
+    assert!(!out.contains(&broken.to_string()));
+}
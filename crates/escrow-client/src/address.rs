@@ -0,0 +1,74 @@
+use anchor_escrow::state::Escrow;
+use anchor_lang::{
+    error, error::ErrorCode, prelude::Pubkey, require, require_keys_eq,
+    solana_program::bpf_loader_upgradeable, Result,
+};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id, token, token_2022,
+};
+
+use crate::{RawAccount, PROGRAM_ID};
+
+/// Escrow PDA of `maker` for `seed`.
+pub fn escrow_address(maker: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"escrow", maker.as_ref(), &seed.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+/// Protocol config PDA.
+pub fn config_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &PROGRAM_ID).0
+}
+
+/// ProgramData account of the escrow program, which records its upgrade authority.
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+/// PDA that signs the self-CPI used to emit events.
+pub fn event_authority_address() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID).0
+}
+
+/// Associated token account of `owner` for `mint` under `token_program`.
+pub fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+/// Token program owning the fetched `mint` account, which must be SPL Token or Token-2022.
+pub fn mint_token_program(mint: &RawAccount) -> Result<Pubkey> {
+    require!(
+        mint.owner == token::ID || mint.owner == token_2022::ID,
+        ErrorCode::ConstraintMintTokenProgram
+    );
+
+    Ok(mint.owner)
+}
+
+/// Mint addresses of both legs and the token program owning those that are not native SOL.
+/// A native SOL leg has no mint account: pass `None` for it.
+pub(crate) fn legs(
+    mint_a: Option<RawAccount>,
+    mint_b: Option<RawAccount>,
+) -> Result<(Pubkey, Pubkey, Pubkey)> {
+    let token_program = match (mint_a, mint_b) {
+        (Some(mint_a), Some(mint_b)) => {
+            let token_program = mint_token_program(&mint_a)?;
+            require_keys_eq!(
+                mint_token_program(&mint_b)?,
+                token_program,
+                ErrorCode::ConstraintMintTokenProgram
+            );
+            token_program
+        }
+        (Some(mint), None) | (None, Some(mint)) => mint_token_program(&mint)?,
+        (None, None) => return Err(ErrorCode::ConstraintTokenMint.into()),
+    };
+
+    let address = |mint: Option<RawAccount>| mint.map_or(Escrow::NATIVE_SOL, |mint| mint.address);
+
+    Ok((address(mint_a), address(mint_b), token_program))
+}
@@ -0,0 +1,15 @@
+//! Instruction builders and account decoders for the escrow program.
+//!
+//! Each builder derives the escrow PDA, its vault and every associated token account
+//! from the maker, the seed and the mints, so callers only supply the terms. Builders take
+//! the fetched mint accounts and use whichever token program owns them.
+
+mod address;
+mod make;
+mod refund;
+mod take;
+mod view;
+
+pub use {address::*, make::*, refund::*, take::*, view::*};
+
+pub use anchor_escrow::{state::DutchAuction, ID as PROGRAM_ID};
@@ -0,0 +1,144 @@
+use anchor_escrow::state::DutchAuction;
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program,
+    },
+    InstructionData, Result, ToAccountMetas,
+};
+use anchor_spl::associated_token;
+
+use crate::{
+    ata, config_address, escrow_address, event_authority_address, legs, RawAccount, PROGRAM_ID,
+};
+
+/// Builds the "Make" instruction, which deposits `deposit` of mint A for `receive` of mint B.
+#[derive(Clone, Debug)]
+pub struct MakeBuilder {
+    maker: Pubkey,
+    seed: u64,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    deposit: u64,
+    receive: u64,
+    expiry: i64,
+    lock_period: i64,
+    allowed_taker: Option<Pubkey>,
+    auction: Option<DutchAuction>,
+    bounty: u64,
+    token_program: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl MakeBuilder {
+    /// `mint_a` and `mint_b` are the fetched mint accounts, both owned by the same token
+    /// program, which the instruction is built for.
+    pub fn new(maker: Pubkey, seed: u64, mint_a: RawAccount, mint_b: RawAccount) -> Result<Self> {
+        let (mint_a, mint_b, token_program) = legs(Some(mint_a), Some(mint_b))?;
+
+        Ok(Self {
+            maker,
+            seed,
+            mint_a,
+            mint_b,
+            deposit: 0,
+            receive: 0,
+            expiry: 0,
+            lock_period: 0,
+            allowed_taker: None,
+            auction: None,
+            bounty: 0,
+            token_program,
+            remaining_accounts: vec![],
+        })
+    }
+
+    pub fn deposit(mut self, deposit: u64) -> Self {
+        self.deposit = deposit;
+        self
+    }
+
+    pub fn receive(mut self, receive: u64) -> Self {
+        self.receive = receive;
+        self
+    }
+
+    /// Unix timestamp after which the escrow can no longer be taken.
+    pub fn expiry(mut self, expiry: i64) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// Seconds from now before the maker can refund. Defaults to 0.
+    pub fn lock_period(mut self, lock_period: i64) -> Self {
+        self.lock_period = lock_period;
+        self
+    }
+
+    pub fn allowed_taker(mut self, allowed_taker: Option<Pubkey>) -> Self {
+        self.allowed_taker = allowed_taker;
+        self
+    }
+
+    pub fn auction(mut self, auction: Option<DutchAuction>) -> Self {
+        self.auction = auction;
+        self
+    }
+
+    /// Lamports paid to whoever cranks the escrow once it has expired, returned to the maker
+    /// on take or refund. Defaults to 0.
+    pub fn bounty(mut self, bounty: u64) -> Self {
+        self.bounty = bounty;
+        self
+    }
+
+    /// Extra accounts appended after the instruction's own, such as transfer hook accounts.
+    pub fn remaining_accounts(mut self, remaining_accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = remaining_accounts;
+        self
+    }
+
+    pub fn escrow(&self) -> Pubkey {
+        escrow_address(&self.maker, self.seed)
+    }
+
+    pub fn vault(&self) -> Pubkey {
+        ata(&self.escrow(), &self.mint_a, &self.token_program)
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let mut accounts = anchor_escrow::accounts::Make {
+            maker: self.maker,
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            maker_ata_a: ata(&self.maker, &self.mint_a, &self.token_program),
+            config: config_address(),
+            escrow: self.escrow(),
+            vault: self.vault(),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+            event_authority: event_authority_address(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(self.remaining_accounts.iter().cloned());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: anchor_escrow::instruction::Make {
+                seed: self.seed,
+                deposit: self.deposit,
+                receive: self.receive,
+                expiry: self.expiry,
+                lock_period: self.lock_period,
+                allowed_taker: self.allowed_taker,
+                auction: self.auction,
+                bounty: self.bounty,
+            }
+            .data(),
+        }
+    }
+}
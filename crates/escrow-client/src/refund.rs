@@ -0,0 +1,120 @@
+use anchor_escrow::state::Escrow;
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program,
+    },
+    InstructionData, Result, ToAccountMetas,
+};
+
+use crate::{
+    ata, escrow_address, event_authority_address, mint_token_program, RawAccount, PROGRAM_ID,
+};
+
+/// Builds the "Refund" instruction, which returns the deposit to the maker.
+/// An escrow holding native SOL is refunded with "RefundNative" instead.
+#[derive(Clone, Debug)]
+pub struct RefundBuilder {
+    maker: Pubkey,
+    seed: u64,
+    mint_a: Pubkey,
+    escrow: Option<Pubkey>,
+    vault: Option<Pubkey>,
+    token_program: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl RefundBuilder {
+    /// `mint_a` is the fetched mint account, or `None` for an escrow holding native SOL,
+    /// and the instruction is built for the token program owning it.
+    pub fn new(maker: Pubkey, seed: u64, mint_a: Option<RawAccount>) -> Result<Self> {
+        // Native SOL is returned by closing the escrow, with no token program involved
+        let (mint_a, token_program) = match mint_a {
+            Some(mint_a) => (mint_a.address, mint_token_program(&mint_a)?),
+            None => (Escrow::NATIVE_SOL, Pubkey::default()),
+        };
+
+        Ok(Self {
+            maker,
+            seed,
+            mint_a,
+            escrow: None,
+            vault: None,
+            token_program,
+            remaining_accounts: vec![],
+        })
+    }
+
+    /// Extra accounts appended after the instruction's own, such as transfer hook accounts.
+    pub fn remaining_accounts(mut self, remaining_accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = remaining_accounts;
+        self
+    }
+
+    /// Address an escrow other than the one derived from the maker and seed.
+    pub fn with_escrow(mut self, escrow: Pubkey) -> Self {
+        self.escrow = Some(escrow);
+        self
+    }
+
+    /// Address a vault other than the one derived from the escrow and mint A.
+    pub fn with_vault(mut self, vault: Pubkey) -> Self {
+        self.vault = Some(vault);
+        self
+    }
+
+    pub fn escrow(&self) -> Pubkey {
+        self.escrow
+            .unwrap_or_else(|| escrow_address(&self.maker, self.seed))
+    }
+
+    pub fn vault(&self) -> Pubkey {
+        self.vault
+            .unwrap_or_else(|| ata(&self.escrow(), &self.mint_a, &self.token_program))
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        if self.mint_a == Escrow::NATIVE_SOL {
+            return self.refund_native();
+        }
+
+        let mut accounts = anchor_escrow::accounts::Refund {
+            maker: self.maker,
+            mint_a: self.mint_a,
+            maker_ata_a: ata(&self.maker, &self.mint_a, &self.token_program),
+            escrow: self.escrow(),
+            vault: self.vault(),
+            token_program: self.token_program,
+            system_program: system_program::ID,
+            event_authority: event_authority_address(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(self.remaining_accounts.iter().cloned());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: anchor_escrow::instruction::Refund {}.data(),
+        }
+    }
+
+    // The deposit is the lamports of the escrow itself, returned when it closes
+    fn refund_native(&self) -> Instruction {
+        let accounts = anchor_escrow::accounts::RefundNative {
+            maker: self.maker,
+            escrow: self.escrow(),
+            system_program: system_program::ID,
+            event_authority: event_authority_address(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data: anchor_escrow::instruction::RefundNative {}.data(),
+        }
+    }
+}
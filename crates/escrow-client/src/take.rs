@@ -0,0 +1,203 @@
+use anchor_escrow::state::Escrow;
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_program,
+    },
+    InstructionData, Result, ToAccountMetas,
+};
+use anchor_spl::associated_token;
+
+use crate::{
+    ata, config_address, escrow_address, event_authority_address, legs, RawAccount, PROGRAM_ID,
+};
+
+/// Builds the "Take" instruction, or "TakePartial" once `amount_b` is set.
+/// An escrow with a native SOL leg is taken with "TakeNative" or "TakeForNative" instead.
+#[derive(Clone, Debug)]
+pub struct TakeBuilder {
+    taker: Pubkey,
+    maker: Pubkey,
+    seed: u64,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    fee_recipient: Pubkey,
+    amount_b: Option<u64>,
+    fills_escrow: bool,
+    token_program: Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl TakeBuilder {
+    /// `mint_a` and `mint_b` are the fetched mint accounts, or `None` for a native SOL leg,
+    /// and the instruction is built for the token program owning them.
+    /// `fee_recipient` must match the one recorded in the protocol config.
+    pub fn new(
+        taker: Pubkey,
+        maker: Pubkey,
+        seed: u64,
+        mint_a: Option<RawAccount>,
+        mint_b: Option<RawAccount>,
+        fee_recipient: Pubkey,
+    ) -> Result<Self> {
+        let (mint_a, mint_b, token_program) = legs(mint_a, mint_b)?;
+
+        Ok(Self {
+            taker,
+            maker,
+            seed,
+            mint_a,
+            mint_b,
+            fee_recipient,
+            amount_b: None,
+            fills_escrow: false,
+            token_program,
+            remaining_accounts: vec![],
+        })
+    }
+
+    /// Fill only `amount_b` of mint B instead of the whole escrow.
+    /// Required for an escrow with a native SOL leg, which has no whole-fill instruction.
+    pub fn amount_b(mut self, amount_b: u64) -> Self {
+        self.amount_b = Some(amount_b);
+        self
+    }
+
+    /// Whether the `amount_b` fill completes the escrow. Only that fill needs the maker's
+    /// mint A account, to return rounding dust, so other partial takers do not pay its rent.
+    /// A whole take always completes the escrow.
+    pub fn fills_escrow(mut self, fills_escrow: bool) -> Self {
+        self.fills_escrow = fills_escrow;
+        self
+    }
+
+    /// Extra accounts appended after the instruction's own, such as transfer hook accounts.
+    pub fn remaining_accounts(mut self, remaining_accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts = remaining_accounts;
+        self
+    }
+
+    pub fn escrow(&self) -> Pubkey {
+        escrow_address(&self.maker, self.seed)
+    }
+
+    pub fn vault(&self) -> Pubkey {
+        ata(&self.escrow(), &self.mint_a, &self.token_program)
+    }
+
+    /// # Panics
+    ///
+    /// If the escrow has a native SOL leg and `amount_b` is not set.
+    pub fn instruction(&self) -> Instruction {
+        let (mut accounts, data) = if self.mint_a == Escrow::NATIVE_SOL {
+            self.take_native()
+        } else if self.mint_b == Escrow::NATIVE_SOL {
+            self.take_for_native()
+        } else {
+            self.take()
+        };
+        accounts.extend(self.remaining_accounts.iter().cloned());
+
+        Instruction {
+            program_id: PROGRAM_ID,
+            accounts,
+            data,
+        }
+    }
+
+    fn take(&self) -> (Vec<AccountMeta>, Vec<u8>) {
+        let accounts = anchor_escrow::accounts::Take {
+            taker: self.taker,
+            maker: self.maker,
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            taker_ata_a: ata(&self.taker, &self.mint_a, &self.token_program),
+            taker_ata_b: ata(&self.taker, &self.mint_b, &self.token_program),
+            maker_ata_a: (self.amount_b.is_none() || self.fills_escrow)
+                .then(|| ata(&self.maker, &self.mint_a, &self.token_program)),
+            maker_ata_b: ata(&self.maker, &self.mint_b, &self.token_program),
+            config: config_address(),
+            fee_recipient: self.fee_recipient,
+            fee_recipient_ata_b: ata(&self.fee_recipient, &self.mint_b, &self.token_program),
+            escrow: self.escrow(),
+            vault: self.vault(),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+            event_authority: event_authority_address(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None);
+
+        let data = match self.amount_b {
+            Some(amount_b) => anchor_escrow::instruction::TakePartial { amount_b }.data(),
+            None => anchor_escrow::instruction::Take {}.data(),
+        };
+
+        (accounts, data)
+    }
+
+    // The escrow holds native SOL, so there is no mint A or vault
+    fn take_native(&self) -> (Vec<AccountMeta>, Vec<u8>) {
+        let accounts = anchor_escrow::accounts::TakeNative {
+            taker: self.taker,
+            maker: self.maker,
+            mint_b: self.mint_b,
+            taker_ata_b: ata(&self.taker, &self.mint_b, &self.token_program),
+            maker_ata_b: ata(&self.maker, &self.mint_b, &self.token_program),
+            config: config_address(),
+            fee_recipient: self.fee_recipient,
+            fee_recipient_ata_b: ata(&self.fee_recipient, &self.mint_b, &self.token_program),
+            escrow: self.escrow(),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+            event_authority: event_authority_address(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None);
+
+        let data = anchor_escrow::instruction::TakeNative {
+            amount_b: self.native_amount_b(),
+        }
+        .data();
+
+        (accounts, data)
+    }
+
+    // The escrow asks for native SOL, paid in lamports to the maker and the fee recipient
+    fn take_for_native(&self) -> (Vec<AccountMeta>, Vec<u8>) {
+        let accounts = anchor_escrow::accounts::TakeForNative {
+            taker: self.taker,
+            maker: self.maker,
+            mint_a: self.mint_a,
+            taker_ata_a: ata(&self.taker, &self.mint_a, &self.token_program),
+            maker_ata_a: self
+                .fills_escrow
+                .then(|| ata(&self.maker, &self.mint_a, &self.token_program)),
+            config: config_address(),
+            fee_recipient: self.fee_recipient,
+            escrow: self.escrow(),
+            vault: self.vault(),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+            event_authority: event_authority_address(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None);
+
+        let data = anchor_escrow::instruction::TakeForNative {
+            amount_b: self.native_amount_b(),
+        }
+        .data();
+
+        (accounts, data)
+    }
+
+    fn native_amount_b(&self) -> u64 {
+        self.amount_b
+            .expect("amount_b is required to take an escrow with a native SOL leg")
+    }
+}
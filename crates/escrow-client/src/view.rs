@@ -0,0 +1,296 @@
+use std::fmt;
+
+use anchor_escrow::state::{DutchAuction, Escrow};
+use anchor_lang::{
+    error,
+    error::ErrorCode,
+    prelude::{Pubkey, Rent},
+    require_keys_eq, AccountDeserialize, Result,
+};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account, Mint},
+};
+use serde::{Serialize, Serializer};
+
+use crate::{ata, escrow_address, PROGRAM_ID};
+
+/// Address, owner, balance and raw data of an account, as fetched from a cluster or a test SVM.
+#[derive(Clone, Copy, Debug)]
+pub struct RawAccount<'a> {
+    pub address: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: &'a [u8],
+}
+
+/// Token amount in base units alongside its decimal representation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TokenAmount {
+    pub amount: u64,
+    pub decimals: u8,
+    pub ui_amount: String,
+}
+
+impl TokenAmount {
+    pub fn new(amount: u64, decimals: u8) -> Self {
+        Self {
+            amount,
+            decimals,
+            ui_amount: ui_amount(amount, decimals),
+        }
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.ui_amount, self.amount)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AuctionView {
+    pub floor: TokenAmount,
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Decoded token-for-token escrow with amounts formatted by their mint decimals.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct EscrowView {
+    #[serde(serialize_with = "base58")]
+    pub address: Pubkey,
+    pub seed: u64,
+    #[serde(serialize_with = "base58")]
+    pub maker: Pubkey,
+    #[serde(serialize_with = "base58")]
+    pub mint_a: Pubkey,
+    #[serde(serialize_with = "base58")]
+    pub mint_b: Pubkey,
+    #[serde(serialize_with = "base58")]
+    pub token_program: Pubkey,
+    pub deposit: TokenAmount,
+    pub receive: TokenAmount,
+    pub remaining_a: TokenAmount,
+    pub remaining_b: TokenAmount,
+    /// Mint B asked per whole unit of mint A when decoded, following the auction if there is one.
+    pub price: f64,
+    /// Mint B asked per whole unit of mint A before any auction decay.
+    pub list_price: f64,
+    pub expiry: i64,
+    pub unlock_at: i64,
+    #[serde(serialize_with = "base58_option")]
+    pub allowed_taker: Option<Pubkey>,
+    pub auction: Option<AuctionView>,
+    /// The escrow itself when it holds native SOL.
+    #[serde(serialize_with = "base58")]
+    pub vault: Pubkey,
+    pub vault_balance: TokenAmount,
+    /// The escrow is the PDA of its maker and seed, and the vault is its associated token account.
+    pub pda_valid: bool,
+    /// The vault holds mint A for the escrow and exactly the amount still owed to takers.
+    pub vault_consistent: bool,
+}
+
+impl EscrowView {
+    /// Decimals of a native SOL leg, counted in lamports.
+    pub const NATIVE_DECIMALS: u8 = 9;
+
+    /// Decode an escrow from its own account, its vault and both of its mints.
+    /// A native SOL leg has no mint, and an escrow holding native SOL has no vault:
+    /// pass `None` for those and the deposit is read from the lamports of the escrow.
+    /// `now` is the unix timestamp the price of a Dutch auction is quoted at.
+    pub fn decode(
+        escrow: RawAccount,
+        vault: Option<RawAccount>,
+        mint_a: Option<RawAccount>,
+        mint_b: Option<RawAccount>,
+        now: i64,
+    ) -> Result<Self> {
+        let state = Escrow::try_deserialize(&mut &escrow.data[..])?;
+        let decimals_a = decimals(mint_a, &state.mint_a)?;
+        let decimals_b = decimals(mint_b, &state.mint_b)?;
+
+        // Whichever leg is not native SOL names the token program
+        let token_program = mint_a
+            .or(mint_b)
+            .ok_or(ErrorCode::ConstraintTokenMint)?
+            .owner;
+        let seed = state.seed.to_le_bytes();
+        let signer_seeds: &[&[u8]] = &[b"escrow", state.maker.as_ref(), &seed, &[state.bump]];
+        let pda_valid = escrow.address == escrow_address(&state.maker, state.seed)
+            && Pubkey::create_program_address(signer_seeds, &PROGRAM_ID) == Ok(escrow.address)
+            && vault.is_none_or(|vault| {
+                vault.address == ata(&escrow.address, &state.mint_a, &token_program)
+            });
+
+        let (vault_address, vault_amount, vault_consistent) = match vault {
+            Some(vault) => {
+                let vault_state = StateWithExtensions::<Account>::unpack(vault.data)?.base;
+                let vault_consistent = vault.owner == token_program
+                    && vault_state.mint == state.mint_a
+                    && vault_state.owner == escrow.address
+                    && vault_state.amount == state.remaining_a;
+                (vault.address, vault_state.amount, vault_consistent)
+            }
+            None => {
+                require_keys_eq!(
+                    state.mint_a,
+                    Escrow::NATIVE_SOL,
+                    ErrorCode::ConstraintTokenMint
+                );
+                // Everything above the rent-exempt minimum and the crank bounty is the deposit
+                // still owed to takers
+                let amount = escrow
+                    .lamports
+                    .saturating_sub(Rent::default().minimum_balance(escrow.data.len()))
+                    .saturating_sub(state.bounty);
+                (escrow.address, amount, amount == state.remaining_a)
+            }
+        };
+
+        let price_per_a = |receive: u64| {
+            (receive as f64 / 10f64.powi(decimals_b.into()))
+                / (state.deposit as f64 / 10f64.powi(decimals_a.into()))
+        };
+        let receive_now = state.auction.map_or(state.receive, |auction| {
+            auction.price_at(state.receive, now)
+        });
+
+        Ok(Self {
+            address: escrow.address,
+            seed: state.seed,
+            maker: state.maker,
+            mint_a: state.mint_a,
+            mint_b: state.mint_b,
+            token_program,
+            deposit: TokenAmount::new(state.deposit, decimals_a),
+            receive: TokenAmount::new(state.receive, decimals_b),
+            remaining_a: TokenAmount::new(state.remaining_a, decimals_a),
+            remaining_b: TokenAmount::new(state.remaining_b, decimals_b),
+            price: price_per_a(receive_now),
+            list_price: price_per_a(state.receive),
+            expiry: state.expiry,
+            unlock_at: state.unlock_at,
+            allowed_taker: state.allowed_taker,
+            auction: state
+                .auction
+                .map(|DutchAuction { floor, start, end }| AuctionView {
+                    floor: TokenAmount::new(floor, decimals_b),
+                    start,
+                    end,
+                }),
+            vault: vault_address,
+            vault_balance: TokenAmount::new(vault_amount, decimals_a),
+            pda_valid,
+            vault_consistent,
+        })
+    }
+
+    /// Pretty-printed JSON of the view.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("EscrowView always serialises")
+    }
+}
+
+/// Prints the view as a two-column table.
+impl fmt::Display for EscrowView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let yes_no = |ok: bool| if ok { "yes" } else { "NO" };
+        let rows: [(&str, String); 18] = [
+            ("seed", self.seed.to_string()),
+            ("maker", self.maker.to_string()),
+            ("mint_a", self.mint_a.to_string()),
+            ("mint_b", self.mint_b.to_string()),
+            ("token_program", self.token_program.to_string()),
+            ("deposit", self.deposit.to_string()),
+            ("receive", self.receive.to_string()),
+            ("remaining_a", self.remaining_a.to_string()),
+            ("remaining_b", self.remaining_b.to_string()),
+            (
+                "price",
+                match self.auction {
+                    Some(_) => format!("{} B per A (list {})", self.price, self.list_price),
+                    None => format!("{} B per A", self.price),
+                },
+            ),
+            ("expiry", self.expiry.to_string()),
+            ("unlock_at", self.unlock_at.to_string()),
+            (
+                "allowed_taker",
+                self.allowed_taker
+                    .map_or("anyone".to_string(), |taker| taker.to_string()),
+            ),
+            (
+                "auction",
+                self.auction.as_ref().map_or("none".to_string(), |auction| {
+                    format!(
+                        "floor {} from {} to {}",
+                        auction.floor, auction.start, auction.end
+                    )
+                }),
+            ),
+            ("vault", self.vault.to_string()),
+            ("vault_balance", self.vault_balance.to_string()),
+            ("pda_valid", yes_no(self.pda_valid).to_string()),
+            (
+                "vault_consistent",
+                yes_no(self.vault_consistent).to_string(),
+            ),
+        ];
+
+        writeln!(f, "Escrow {}", self.address)?;
+        for (field, value) in rows {
+            writeln!(f, "  {:<18}{value}", format!("{field}:"))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Decimals of the leg in `address`, which has no mint account when it is native SOL
+fn decimals(mint: Option<RawAccount>, address: &Pubkey) -> Result<u8> {
+    match mint {
+        Some(mint) => {
+            require_keys_eq!(mint.address, *address, ErrorCode::ConstraintTokenMint);
+            Ok(StateWithExtensions::<Mint>::unpack(mint.data)?
+                .base
+                .decimals)
+        }
+        None => {
+            require_keys_eq!(*address, Escrow::NATIVE_SOL, ErrorCode::ConstraintTokenMint);
+            Ok(EscrowView::NATIVE_DECIMALS)
+        }
+    }
+}
+
+/// Format `amount` base units with `decimals`, dropping trailing zeros: 1_500_000 at 6 is "1.5".
+pub fn ui_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let digits = format!("{amount:0>width$}", width = decimals as usize + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+fn base58<S: Serializer>(key: &Pubkey, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(key)
+}
+
+fn base58_option<S: Serializer>(
+    key: &Option<Pubkey>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match key {
+        Some(key) => serializer.collect_str(key),
+        None => serializer.serialize_none(),
+    }
+}
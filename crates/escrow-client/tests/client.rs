@@ -0,0 +1,413 @@
+use std::path::PathBuf;
+
+use anchor_escrow::state::DutchAuction;
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::Clock,
+        instruction::Instruction,
+        system_program,
+    },
+    InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    token::ID as TOKEN_PROGRAM_ID,
+    token_2022::{
+        spl_token_2022::{
+            self, instruction::initialize_mint2, solana_program::program_pack::Pack, state::Mint,
+        },
+        ID as TOKEN_2022_PROGRAM_ID,
+    },
+};
+use escrow_client::{
+    ata, config_address, program_data_address, ui_amount, EscrowView, MakeBuilder, RawAccount,
+    RefundBuilder, TakeBuilder, PROGRAM_ID,
+};
+use litesvm::LiteSVM;
+use litesvm_token::CreateAssociatedTokenAccount;
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_message::Message;
+use solana_signer::Signer;
+use solana_system_interface::instruction::create_account;
+use solana_transaction::Transaction;
+
+struct TestEnv {
+    svm: LiteSVM,
+    maker: Keypair,
+    taker: Keypair,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    fee_recipient: Pubkey,
+}
+
+impl TestEnv {
+    fn send(&mut self, signer: &Keypair, ix: Instruction) {
+        let message = Message::new(&[ix], Some(&signer.pubkey()));
+        let transaction = Transaction::new(&[signer], message, self.svm.latest_blockhash());
+
+        self.svm.send_transaction(transaction).unwrap();
+    }
+
+    fn mint(&self, mint: &Pubkey) -> Account {
+        self.svm.get_account(mint).unwrap()
+    }
+
+    fn make_builder(&self, seed: u64, deposit: u64, receive: u64) -> MakeBuilder {
+        let (mint_a, mint_b) = (self.mint(&self.mint_a), self.mint(&self.mint_b));
+
+        MakeBuilder::new(
+            self.maker.pubkey(),
+            seed,
+            raw(&self.mint_a, &mint_a),
+            raw(&self.mint_b, &mint_b),
+        )
+        .unwrap()
+        .deposit(deposit)
+        .receive(receive)
+        .expiry(i64::MAX)
+    }
+
+    fn take_builder(&self, seed: u64) -> TakeBuilder {
+        let (mint_a, mint_b) = (self.mint(&self.mint_a), self.mint(&self.mint_b));
+
+        TakeBuilder::new(
+            self.taker.pubkey(),
+            self.maker.pubkey(),
+            seed,
+            Some(raw(&self.mint_a, &mint_a)),
+            Some(raw(&self.mint_b, &mint_b)),
+            self.fee_recipient,
+        )
+        .unwrap()
+    }
+
+    fn make(&mut self, seed: u64, deposit: u64, receive: u64) -> MakeBuilder {
+        let builder = self.make_builder(seed, deposit, receive);
+        let maker = self.maker.insecure_clone();
+        self.send(&maker, builder.instruction());
+
+        builder
+    }
+
+    // Decode the escrow at `address` as if its vault were the one of `builder`
+    fn view_at(&self, builder: &MakeBuilder, address: &Pubkey) -> EscrowView {
+        let escrow = self.svm.get_account(address).unwrap();
+        let vault = self.svm.get_account(&builder.vault()).unwrap();
+        let (mint_a, mint_b) = (self.mint(&self.mint_a), self.mint(&self.mint_b));
+
+        EscrowView::decode(
+            raw(address, &escrow),
+            Some(raw(&builder.vault(), &vault)),
+            Some(raw(&self.mint_a, &mint_a)),
+            Some(raw(&self.mint_b, &mint_b)),
+            self.svm.get_sysvar::<Clock>().unix_timestamp,
+        )
+        .unwrap()
+    }
+
+    fn view(&self, builder: &MakeBuilder) -> EscrowView {
+        self.view_at(builder, &builder.escrow())
+    }
+}
+
+fn raw<'a>(address: &Pubkey, account: &'a Account) -> RawAccount<'a> {
+    RawAccount {
+        address: *address,
+        owner: account.owner,
+        lamports: account.lamports,
+        data: &account.data,
+    }
+}
+
+// A mint with 6 decimals and `authority` as mint authority, owned by `token_program`.
+// litesvm-token only builds SPL Token instructions, so Token-2022 mints are made here
+fn create_mint(svm: &mut LiteSVM, authority: &Keypair, token_program: &Pubkey) -> Pubkey {
+    let mint = Keypair::new();
+    let instructions = [
+        create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            svm.minimum_balance_for_rent_exemption(Mint::LEN),
+            Mint::LEN as u64,
+            token_program,
+        ),
+        initialize_mint2(token_program, &mint.pubkey(), &authority.pubkey(), None, 6).unwrap(),
+    ];
+    let message = Message::new(&instructions, Some(&authority.pubkey()));
+    let transaction = Transaction::new(&[authority, &mint], message, svm.latest_blockhash());
+    svm.send_transaction(transaction).unwrap();
+
+    mint.pubkey()
+}
+
+// Mint `amount` of `mint` into `account`, with the mint's owner as token program
+fn mint_to(svm: &mut LiteSVM, authority: &Keypair, mint: &Pubkey, account: &Pubkey, amount: u64) {
+    let token_program = svm.get_account(mint).unwrap().owner;
+    let ix = spl_token_2022::instruction::mint_to(
+        &token_program,
+        mint,
+        account,
+        &authority.pubkey(),
+        &[],
+        amount,
+    )
+    .unwrap();
+    let message = Message::new(&[ix], Some(&authority.pubkey()));
+    let transaction = Transaction::new(&[authority], message, svm.latest_blockhash());
+    svm.send_transaction(transaction).unwrap();
+}
+
+// Maker holds 1,000 Mint A and taker holds 1,000 Mint B, both owned by `token_program`,
+// with a fee-free protocol config
+fn setup(token_program: Pubkey) -> TestEnv {
+    let mut svm = LiteSVM::new();
+
+    let so_path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/anchor_escrow.so");
+    let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
+    svm.add_program(PROGRAM_ID, &program_data);
+
+    let maker = Keypair::new();
+    let taker = Keypair::new();
+    svm.airdrop(&maker.pubkey(), 10_000_000_000).unwrap();
+    svm.airdrop(&taker.pubkey(), 10_000_000_000).unwrap();
+
+    // Only the upgrade authority can initialize the config
+    let upgrade_authority = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(maker.pubkey()),
+    })
+    .unwrap();
+    svm.set_account(
+        program_data_address(),
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(upgrade_authority.len()),
+            data: upgrade_authority,
+            owner: bpf_loader_upgradeable::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+
+    let fee_recipient = Pubkey::new_unique();
+    let initialize_config_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: anchor_escrow::accounts::InitializeConfig {
+            admin: maker.pubkey(),
+            program_data: program_data_address(),
+            config: config_address(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_escrow::instruction::InitializeConfig {
+            fee_bps: 0,
+            fee_recipient,
+            oracle_program: Pubkey::new_unique(),
+        }
+        .data(),
+    };
+    let message = Message::new(&[initialize_config_ix], Some(&maker.pubkey()));
+    let transaction = Transaction::new(&[&maker], message, svm.latest_blockhash());
+    svm.send_transaction(transaction).unwrap();
+
+    let mint_a = create_mint(&mut svm, &maker, &token_program);
+    let mint_b = create_mint(&mut svm, &maker, &token_program);
+
+    let maker_ata_a = CreateAssociatedTokenAccount::new(&mut svm, &maker, &mint_a)
+        .token_program_id(&token_program)
+        .send()
+        .unwrap();
+    mint_to(&mut svm, &maker, &mint_a, &maker_ata_a, 1_000_000_000);
+
+    let taker_ata_b = CreateAssociatedTokenAccount::new(&mut svm, &taker, &mint_b)
+        .token_program_id(&token_program)
+        .send()
+        .unwrap();
+    mint_to(&mut svm, &maker, &mint_b, &taker_ata_b, 1_000_000_000);
+
+    TestEnv {
+        svm,
+        maker,
+        taker,
+        mint_a,
+        mint_b,
+        fee_recipient,
+    }
+}
+
+#[test]
+fn test_ui_amount() {
+    assert_eq!(ui_amount(1_500_000, 6), "1.5");
+    assert_eq!(ui_amount(3_000_000, 6), "3");
+    assert_eq!(ui_amount(250, 6), "0.00025");
+    assert_eq!(ui_amount(0, 6), "0");
+    assert_eq!(ui_amount(42, 0), "42");
+    assert_eq!(ui_amount(u64::MAX, 9), "18446744073.709551615");
+}
+
+#[test]
+fn test_escrow_view() {
+    let mut env = setup(TOKEN_PROGRAM_ID);
+
+    // 1.5 of Mint A for 3 of Mint B
+    let make = env.make(1, 1_500_000, 3_000_000);
+
+    let view = env.view(&make);
+    assert_eq!(view.address, make.escrow());
+    assert_eq!(view.maker, env.maker.pubkey());
+    assert_eq!(view.token_program, TOKEN_PROGRAM_ID);
+    assert_eq!(view.deposit.ui_amount, "1.5");
+    assert_eq!(view.receive.ui_amount, "3");
+    assert_eq!(view.price, 2.0);
+    assert_eq!(view.vault, make.vault());
+    assert_eq!(view.vault_balance.amount, 1_500_000);
+    assert!(view.pda_valid);
+    assert!(view.vault_consistent);
+
+    // Amounts follow a partial take
+    let take = env.take_builder(1).amount_b(1_000_000);
+    let taker = env.taker.insecure_clone();
+    env.send(&taker, take.instruction());
+
+    let view = env.view(&make);
+    assert_eq!(view.remaining_a.ui_amount, "1");
+    assert_eq!(view.remaining_b.ui_amount, "2");
+    assert_eq!(view.vault_balance.ui_amount, "1");
+    assert!(view.vault_consistent);
+
+    let table = view.to_string();
+    assert!(table.starts_with(&format!("Escrow {}\n", make.escrow())));
+    assert!(table.contains("  remaining_a:      1 (1000000)\n"));
+    assert!(table.contains("  price:            2 B per A\n"));
+    assert!(table.contains("  allowed_taker:    anyone\n"));
+    assert!(table.contains("  vault_consistent: yes\n"));
+
+    let json = view.to_json();
+    assert!(json.contains(&format!("\"address\": \"{}\"", make.escrow())));
+    assert!(json.contains("\"ui_amount\": \"1\""));
+    assert!(json.contains("\"allowed_taker\": null"));
+    assert!(json.contains("\"auction\": null"));
+}
+
+#[test]
+fn test_escrow_view_auction_price() {
+    let mut env = setup(TOKEN_PROGRAM_ID);
+
+    // 1 of Mint A for 4 of Mint B, decaying to 2 over 100 seconds
+    let start = env.svm.get_sysvar::<Clock>().unix_timestamp;
+    let make = env
+        .make_builder(1, 1_000_000, 4_000_000)
+        .auction(Some(DutchAuction {
+            floor: 2_000_000,
+            start,
+            end: start + 100,
+        }));
+    let maker = env.maker.insecure_clone();
+    env.send(&maker, make.instruction());
+
+    let view = env.view(&make);
+    assert_eq!(view.price, 4.0);
+    assert_eq!(view.list_price, 4.0);
+
+    // Halfway through, the price shown is the one a taker pays now
+    let mut clock = env.svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start + 50;
+    env.svm.set_sysvar(&clock);
+
+    let view = env.view(&make);
+    assert_eq!(view.price, 3.0);
+    assert_eq!(view.list_price, 4.0);
+    assert!(view
+        .to_string()
+        .contains("  price:            3 B per A (list 4)\n"));
+}
+
+#[test]
+fn test_escrow_view_flags_inconsistent_vault() {
+    let mut env = setup(TOKEN_PROGRAM_ID);
+    let make = env.make(1, 100, 250);
+
+    // Tokens sent straight to the vault are not owed to any taker
+    let maker = env.maker.insecure_clone();
+    mint_to(&mut env.svm, &maker, &env.mint_a, &make.vault(), 1);
+
+    let view = env.view(&make);
+    assert!(view.pda_valid);
+    assert!(!view.vault_consistent);
+    assert!(view.to_string().contains("  vault_consistent: NO\n"));
+}
+
+#[test]
+fn test_escrow_view_flags_invalid_pda() {
+    let mut env = setup(TOKEN_PROGRAM_ID);
+    let make = env.make(1, 100, 250);
+
+    // A copy of the escrow at another address is not the maker's PDA
+    let copy = Pubkey::new_unique();
+    let account = env.svm.get_account(&make.escrow()).unwrap();
+    env.svm.set_account(copy, account).unwrap();
+
+    let view = env.view_at(&make, &copy);
+    assert!(!view.pda_valid);
+    assert!(!view.vault_consistent);
+}
+
+#[test]
+fn test_token_2022() {
+    let mut env = setup(TOKEN_2022_PROGRAM_ID);
+
+    // The builders derive the vault and ATAs under the token program owning the mints
+    let make = env.make(1, 10_000, 5_000);
+    assert_eq!(
+        make.vault(),
+        ata(&make.escrow(), &env.mint_a, &TOKEN_2022_PROGRAM_ID)
+    );
+
+    let view = env.view(&make);
+    assert_eq!(view.token_program, TOKEN_2022_PROGRAM_ID);
+    assert_eq!(view.vault_balance.ui_amount, "0.01");
+    assert_eq!(view.price, 0.5);
+    assert!(view.pda_valid);
+    assert!(view.vault_consistent);
+
+    let take = env.take_builder(1).amount_b(1_000);
+    let taker = env.taker.insecure_clone();
+    env.send(&taker, take.instruction());
+    assert_eq!(env.view(&make).remaining_a.amount, 8_000);
+
+    let mint_a = env.mint(&env.mint_a);
+    let refund =
+        RefundBuilder::new(env.maker.pubkey(), 1, Some(raw(&env.mint_a, &mint_a))).unwrap();
+    let maker = env.maker.insecure_clone();
+    env.send(&maker, refund.instruction());
+    assert!(env
+        .svm
+        .get_account(&make.escrow())
+        .is_none_or(|account| account.lamports == 0));
+}
+
+#[test]
+fn test_builders_reject_accounts_that_are_not_mints() {
+    let env = setup(TOKEN_PROGRAM_ID);
+    let mint_a = env.mint(&env.mint_a);
+    let maker = env.svm.get_account(&env.maker.pubkey()).unwrap();
+
+    // The maker's wallet is owned by the system program, not a token program
+    let wallet = raw(&env.maker.pubkey(), &maker);
+    assert!(MakeBuilder::new(env.maker.pubkey(), 1, raw(&env.mint_a, &mint_a), wallet).is_err());
+    assert!(RefundBuilder::new(env.maker.pubkey(), 1, Some(wallet)).is_err());
+
+    // Both legs cannot be native SOL
+    assert!(TakeBuilder::new(
+        env.taker.pubkey(),
+        env.maker.pubkey(),
+        1,
+        None,
+        None,
+        env.fee_recipient
+    )
+    .is_err());
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Lock period must not be negative or outlast the expiry")]
+    InvalidLockPeriod,
+    #[msg("Escrow has expired and can no longer be taken")]
+    EscrowExpired,
+    #[msg("Escrow is still within its lock period and cannot be refunded")]
+    RefundLocked,
+    #[msg("Fill amount must be greater than zero and at most the remaining amount")]
+    InvalidFillAmount,
+    #[msg("Fill amount is too small to pay out any of the deposit")]
+    FillTooSmall,
+    #[msg("Deposit amount must be greater than zero")]
+    ZeroDeposit,
+    #[msg("Receive amount must be greater than zero")]
+    ZeroReceive,
+    #[msg("Mint A and mint B must be different")]
+    SameMint,
+    #[msg("Maker does not hold enough of mint A to cover the deposit")]
+    InsufficientMakerBalance,
+    #[msg("Taker does not hold enough of mint B to cover the fill")]
+    InsufficientTakerBalance,
+    #[msg("Mint does not match the escrow")]
+    InvalidMint,
+    #[msg("Maker does not match the escrow")]
+    InvalidMaker,
+    #[msg("Receive amount would overflow the maker's token account")]
+    ReceiveOverflow,
+    #[msg("Taker is not allowed to fill this escrow")]
+    UnauthorizedTaker,
+    #[msg("Fee must not exceed 1,000 basis points")]
+    InvalidFee,
+    #[msg("Signer is not the config admin")]
+    InvalidAdmin,
+    #[msg("Fee recipient does not match the config")]
+    InvalidFeeRecipient,
+    #[msg("Payment would leave the recipient below the rent-exempt minimum")]
+    RecipientNotRentExempt,
+    #[msg("Mint has a Token-2022 extension the escrow does not support")]
+    UnsupportedMintExtension,
+    #[msg("Amount plus transfer fee overflows")]
+    TransferFeeOverflow,
+    #[msg("Arbiter does not match the escrow")]
+    InvalidArbiter,
+    #[msg("Arbiter must not be the maker or the taker")]
+    ArbiterIsParty,
+    #[msg("Taker does not match the escrow")]
+    InvalidTaker,
+    #[msg("Only the maker or the taker can raise a dispute")]
+    UnauthorizedDisputant,
+    #[msg("Escrow is under dispute")]
+    EscrowDisputed,
+    #[msg("Escrow is not under dispute")]
+    NotDisputed,
+    #[msg("Milestone escrow needs between 1 and 8 tranches")]
+    InvalidMilestoneCount,
+    #[msg("Total deposit overflows")]
+    DepositOverflow,
+    #[msg("Milestone does not exist")]
+    InvalidMilestone,
+    #[msg("Milestone has already been released")]
+    MilestoneReleased,
+    #[msg("Milestone can only be released by the maker before its unlock time")]
+    MilestoneLocked,
+    #[msg("Vesting schedule must satisfy start <= cliff <= end with start < end")]
+    InvalidVestingSchedule,
+    #[msg("Beneficiary does not match the escrow")]
+    InvalidBeneficiary,
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+    #[msg("Basket needs between 1 and 4 legs on each side")]
+    InvalidBasketSize,
+    #[msg("Remaining accounts do not match the basket legs")]
+    InvalidBasketAccounts,
+    #[msg("Each mint can appear only once in a basket")]
+    DuplicateBasketMint,
+    #[msg("Mint is not an NFT: it must have 0 decimals and a supply of 1")]
+    NotAnNft,
+    #[msg("An NFT price must be exactly 1")]
+    InvalidNftPrice,
+    #[msg("Token account holding the NFT is frozen")]
+    NftFrozen,
+    #[msg("Metadata account does not match the NFT")]
+    InvalidNftMetadata,
+    #[msg("Creator accounts do not match the escrow royalty terms")]
+    InvalidCreator,
+    #[msg("Royalty and protocol fee exceed the price")]
+    RoyaltyExceedsPrice,
+    #[msg("Escrow has not expired yet")]
+    EscrowNotExpired,
+    #[msg("Slippage must be at most 10000 basis points")]
+    InvalidSlippage,
+    #[msg("Staleness bound cannot be negative")]
+    InvalidStaleness,
+    #[msg("Price feed account is not the escrow's oracle or is malformed")]
+    InvalidOracle,
+    #[msg("Oracle price is not positive")]
+    InvalidOraclePrice,
+    #[msg("Oracle price is older than the staleness bound")]
+    StaleOraclePrice,
+    #[msg("Payment is below the oracle price less the maximum slippage")]
+    PaymentBelowOraclePrice,
+    #[msg("Auction floor must be 1 to the starting price, ending after start and by expiry")]
+    InvalidAuction,
+    #[msg("Bidder does not match the bid")]
+    InvalidBidder,
+    #[msg("Escrows do not cross at their current prices")]
+    EscrowsDoNotCross,
+    #[msg("Maker must refund its own escrow rather than crank it")]
+    MakerCannotCrank,
+    #[msg("Every leg of a basket must be a mint of the same token program")]
+    MixedTokenPrograms,
+    #[msg("Maker's mint A token account is required for the fill that completes the escrow")]
+    MakerAtaRequired,
+    #[msg("Escrows cross, but not by enough to pay the transfer fees of the match")]
+    SpreadBelowTransferFee,
+}
@@ -0,0 +1,849 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    instructions::Settlement,
+    state::{
+        ArbitratedEscrow, BasketEscrow, BasketLeg, Bid, DutchAuction, Escrow, MilestoneEscrow,
+        NftEscrow, OracleEscrow, VestingEscrow,
+    },
+};
+
+#[event]
+pub struct EscrowCreated {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub deposit: u64,
+    pub receive: u64,
+    pub expiry: i64,
+    pub unlock_at: i64,
+    pub allowed_taker: Option<Pubkey>,
+    pub auction: Option<DutchAuction>,
+    /// Lamports paid to whoever cranks the escrow once it has expired.
+    pub bounty: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowTaken {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Amount of mint A paid out to the taker.
+    pub amount_a: u64,
+    /// Amount of mint B paid by the taker, including the fee.
+    pub amount_b: u64,
+    /// Part of `amount_b` paid to the protocol fee recipient.
+    pub fee: u64,
+    pub remaining_a: u64,
+    pub remaining_b: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowRefunded {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Amount of mint A returned to the maker.
+    pub amount_a: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowUpdated {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Amount of mint A now on offer.
+    pub remaining_a: u64,
+    /// Amount of mint B now asked for `remaining_a`.
+    pub remaining_b: u64,
+    pub expiry: i64,
+    pub allowed_taker: Option<Pubkey>,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ArbitratedEscrowCreated {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub arbiter: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub expiry: i64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowDisputed {
+    pub seed: u64,
+    pub maker: Pubkey,
+    /// Maker or taker who raised the dispute.
+    pub raised_by: Pubkey,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowResolved {
+    pub seed: u64,
+    pub maker: Pubkey,
+    /// Owner of the token account the vault was paid out to.
+    pub recipient: Pubkey,
+    /// Maker on release or on a refund after expiry, arbiter after a dispute.
+    pub resolved_by: Pubkey,
+    pub amount: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneEscrowCreated {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub mint: Pubkey,
+    /// Amount of `mint` deposited across all tranches.
+    pub amount: u64,
+    pub milestones: u8,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneReleased {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub index: u8,
+    /// Amount of `mint` paid out to the taker.
+    pub amount: u64,
+    pub released_by: Pubkey,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestonesRefunded {
+    pub seed: u64,
+    pub maker: Pubkey,
+    /// Amount of `mint` returned to the maker for the tranches still locked.
+    pub amount: u64,
+    /// Amount of `mint` paid to the taker for tranches that had unlocked but were not released.
+    pub released: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestingEscrowCreated {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VestedClaimed {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Amount of `mint` paid out by this claim.
+    pub amount: u64,
+    /// Amount of `mint` paid out so far, including this claim.
+    pub claimed: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BasketCreated {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub deposit: Vec<BasketLeg>,
+    pub receive: Vec<BasketLeg>,
+    pub expiry: i64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BasketTaken {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    /// Protocol fee paid to the fee recipient on each receive leg.
+    pub fees: Vec<BasketLeg>,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BasketRefunded {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftEscrowCreated {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive: u64,
+    pub royalty_bps: u16,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftEscrowTaken {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Amount of mint B paid by the taker, including the fee and royalty.
+    pub amount_b: u64,
+    /// Part of `amount_b` paid to the protocol fee recipient.
+    pub fee: u64,
+    /// Part of `amount_b` paid to the creators.
+    pub royalty: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct NftEscrowRefunded {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowCranked {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub cranker: Pubkey,
+    pub mint_a: Pubkey,
+    /// Amount of mint A returned to the maker.
+    pub amount_a: u64,
+    /// Lamports the maker posted as a bounty, paid to the cranker.
+    pub bounty: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleEscrowCreated {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub deposit: u64,
+    pub oracle: Pubkey,
+    pub oracle_program: Pubkey,
+    pub max_slippage_bps: u16,
+    pub max_staleness: i64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleEscrowTaken {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    /// Oracle price the take was checked against, scaled by `10^expo`.
+    pub price: u64,
+    pub expo: i32,
+    /// Amount of mint A released to the taker.
+    pub amount_a: u64,
+    /// Amount of mint B paid by the taker, including the fee.
+    pub amount_b: u64,
+    /// Part of `amount_b` paid to the protocol fee recipient.
+    pub fee: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OracleEscrowRefunded {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    /// Amount of mint A returned to the maker.
+    pub amount_a: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BidCreated {
+    pub seed: u64,
+    pub bidder: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub expiry: i64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BidFilled {
+    pub seed: u64,
+    pub bidder: Pubkey,
+    pub filler: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Amount of mint A paid by the filler, including the fee.
+    pub amount_a: u64,
+    /// Amount of mint B paid out of the vault to the filler.
+    pub amount_b: u64,
+    /// Part of `amount_a` paid to the protocol fee recipient.
+    pub fee: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BidCancelled {
+    pub seed: u64,
+    pub bidder: Pubkey,
+    pub mint_b: Pubkey,
+    /// Amount of mint B returned to the bidder.
+    pub amount_b: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowsMatched {
+    pub matcher: Pubkey,
+    pub seed_x: u64,
+    pub maker_x: Pubkey,
+    pub seed_y: u64,
+    pub maker_y: Pubkey,
+    /// Mint deposited by maker X and requested by maker Y.
+    pub mint_a: Pubkey,
+    /// Mint deposited by maker Y and requested by maker X.
+    pub mint_b: Pubkey,
+    /// Amount of mint A released from X's vault.
+    pub amount_a: u64,
+    /// Amount of mint B released from Y's vault.
+    pub amount_b: u64,
+    /// Part of `amount_a` kept by the matcher.
+    pub surplus_a: u64,
+    /// Part of `amount_b` kept by the matcher.
+    pub surplus_b: u64,
+    /// Protocol fee taken from maker Y's mint A proceeds.
+    pub fee_a: u64,
+    /// Protocol fee taken from maker X's mint B proceeds.
+    pub fee_b: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+impl EscrowCreated {
+    pub fn new(escrow: &Escrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            deposit: escrow.deposit,
+            receive: escrow.receive,
+            expiry: escrow.expiry,
+            unlock_at: escrow.unlock_at,
+            allowed_taker: escrow.allowed_taker,
+            auction: escrow.auction,
+            bounty: escrow.bounty,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl EscrowTaken {
+    pub fn new(
+        escrow: &Escrow,
+        taker: Pubkey,
+        amount_a: u64,
+        amount_b: u64,
+        fee: u64,
+    ) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            taker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            amount_a,
+            amount_b,
+            fee,
+            remaining_a: escrow.remaining_a,
+            remaining_b: escrow.remaining_b,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl EscrowRefunded {
+    pub fn new(escrow: &Escrow, amount_a: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            amount_a,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl EscrowUpdated {
+    pub fn new(escrow: &Escrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            remaining_a: escrow.remaining_a,
+            remaining_b: escrow.remaining_b,
+            expiry: escrow.expiry,
+            allowed_taker: escrow.allowed_taker,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl ArbitratedEscrowCreated {
+    pub fn new(escrow: &ArbitratedEscrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            taker: escrow.taker,
+            arbiter: escrow.arbiter,
+            mint: escrow.mint,
+            amount: escrow.amount,
+            expiry: escrow.expiry,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl EscrowDisputed {
+    pub fn new(escrow: &ArbitratedEscrow, raised_by: Pubkey) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            raised_by,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl EscrowResolved {
+    pub fn new(
+        escrow: &ArbitratedEscrow,
+        recipient: Pubkey,
+        resolved_by: Pubkey,
+        amount: u64,
+    ) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            recipient,
+            resolved_by,
+            amount,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl MilestoneEscrowCreated {
+    pub fn new(escrow: &MilestoneEscrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            taker: escrow.taker,
+            mint: escrow.mint,
+            amount: escrow.unreleased(),
+            milestones: escrow.milestones.len() as u8,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl MilestoneReleased {
+    pub fn new(
+        escrow: &MilestoneEscrow,
+        index: u8,
+        amount: u64,
+        released_by: Pubkey,
+    ) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            taker: escrow.taker,
+            index,
+            amount,
+            released_by,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl MilestonesRefunded {
+    pub fn new(escrow: &MilestoneEscrow, amount: u64, released: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            amount,
+            released,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl VestingEscrowCreated {
+    pub fn new(escrow: &VestingEscrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            beneficiary: escrow.beneficiary,
+            mint: escrow.mint,
+            amount: escrow.amount,
+            start: escrow.start,
+            cliff: escrow.cliff,
+            end: escrow.end,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl VestedClaimed {
+    pub fn new(escrow: &VestingEscrow, amount: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            beneficiary: escrow.beneficiary,
+            amount,
+            claimed: escrow.claimed,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl BasketCreated {
+    pub fn new(escrow: &BasketEscrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            deposit: escrow.deposit.clone(),
+            receive: escrow.receive.clone(),
+            expiry: escrow.expiry,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl BasketTaken {
+    pub fn new(escrow: &BasketEscrow, taker: Pubkey, fees: Vec<BasketLeg>) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            taker,
+            fees,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl BasketRefunded {
+    pub fn new(escrow: &BasketEscrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl NftEscrowCreated {
+    pub fn new(escrow: &NftEscrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            receive: escrow.receive,
+            royalty_bps: escrow.royalty_bps,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl NftEscrowTaken {
+    pub fn new(escrow: &NftEscrow, taker: Pubkey, fee: u64, royalty: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            taker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            amount_b: escrow.receive,
+            fee,
+            royalty,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl NftEscrowRefunded {
+    pub fn new(escrow: &NftEscrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl EscrowCranked {
+    pub fn new(escrow: &Escrow, cranker: Pubkey, amount_a: u64, bounty: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            cranker,
+            mint_a: escrow.mint_a,
+            amount_a,
+            bounty,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl OracleEscrowCreated {
+    pub fn new(escrow: &OracleEscrow) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            deposit: escrow.deposit,
+            oracle: escrow.oracle,
+            oracle_program: escrow.oracle_program,
+            max_slippage_bps: escrow.max_slippage_bps,
+            max_staleness: escrow.max_staleness,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl OracleEscrowTaken {
+    pub fn new(
+        escrow: &OracleEscrow,
+        taker: Pubkey,
+        price: u64,
+        expo: i32,
+        amount_a: u64,
+        amount_b: u64,
+        fee: u64,
+    ) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            taker,
+            price,
+            expo,
+            amount_a,
+            amount_b,
+            fee,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl OracleEscrowRefunded {
+    pub fn new(escrow: &OracleEscrow, amount_a: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            amount_a,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl BidCreated {
+    pub fn new(bid: &Bid) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: bid.seed,
+            bidder: bid.bidder,
+            mint_a: bid.mint_a,
+            mint_b: bid.mint_b,
+            amount_a: bid.amount_a,
+            amount_b: bid.amount_b,
+            expiry: bid.expiry,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl BidFilled {
+    pub fn new(bid: &Bid, filler: Pubkey, fee: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: bid.seed,
+            bidder: bid.bidder,
+            filler,
+            mint_a: bid.mint_a,
+            mint_b: bid.mint_b,
+            amount_a: bid.amount_a,
+            amount_b: bid.amount_b,
+            fee,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl BidCancelled {
+    pub fn new(bid: &Bid, amount_b: u64) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            seed: bid.seed,
+            bidder: bid.bidder,
+            mint_b: bid.mint_b,
+            amount_b,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
+
+impl EscrowsMatched {
+    pub fn new(
+        escrow_x: &Escrow,
+        escrow_y: &Escrow,
+        matcher: Pubkey,
+        settlement: &Settlement,
+    ) -> Result<Self> {
+        let clock = Clock::get()?;
+
+        Ok(Self {
+            matcher,
+            seed_x: escrow_x.seed,
+            maker_x: escrow_x.maker,
+            seed_y: escrow_y.seed,
+            maker_y: escrow_y.maker,
+            mint_a: escrow_x.mint_a,
+            mint_b: escrow_x.mint_b,
+            amount_a: settlement.amount_a,
+            amount_b: settlement.amount_b,
+            surplus_a: settlement.surplus_a,
+            surplus_b: settlement.surplus_b,
+            fee_a: settlement.fee_a,
+            fee_b: settlement.fee_b,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+        })
+    }
+}
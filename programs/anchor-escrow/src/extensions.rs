@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+            StateWithExtensions,
+        },
+    },
+    token_interface::{
+        harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint, Mint, TokenAccount,
+        TokenInterface, TransferChecked,
+    },
+};
+
+use crate::error::EscrowError;
+
+/// Token-2022 mint extensions that make a mint unusable as an escrow leg.
+const UNSUPPORTED_EXTENSIONS: [ExtensionType; 1] = [ExtensionType::NonTransferable];
+
+fn transfer_fee_config(mint: &InterfaceAccount<Mint>) -> Result<Option<TransferFeeConfig>> {
+    let info = mint.to_account_info();
+
+    if *info.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    Ok(state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Rejects mints whose extensions the escrow cannot honour.
+pub fn check_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let info = mint.to_account_info();
+
+    if *info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    for extension in state.get_extension_types()? {
+        require!(
+            !UNSUPPORTED_EXTENSIONS.contains(&extension),
+            EscrowError::UnsupportedMintExtension
+        );
+    }
+
+    Ok(())
+}
+
+/// Amount to transfer so the recipient nets exactly `amount` after any transfer fee.
+pub fn gross_up(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let Some(config) = transfer_fee_config(mint)? else {
+        return Ok(amount);
+    };
+
+    let epoch = Clock::get()?.epoch;
+
+    config
+        .get_epoch_fee(epoch)
+        .calculate_pre_fee_amount(amount)
+        .ok_or(EscrowError::TransferFeeOverflow.into())
+}
+
+/// Moves transfer fees withheld in `account` to the mint, so the account can be closed.
+pub fn harvest_withheld<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    account: &InterfaceAccount<'info, TokenAccount>,
+) -> Result<()> {
+    if transfer_fee_config(mint)?.is_none() {
+        return Ok(());
+    }
+
+    let cpi_accounts = HarvestWithheldTokensToMint {
+        token_program_id: token_program.to_account_info(),
+        mint: mint.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+
+    harvest_withheld_tokens_to_mint(cpi_ctx, vec![account.to_account_info()])
+}
+
+/// `transfer_checked` that also forwards the extra accounts required by a Token-2022 transfer
+/// hook, resolved from `ctx.remaining_accounts`. Behaves like a plain transfer for other mints.
+pub fn transfer_checked<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    spl_token_2022::onchain::invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{error::EscrowError, extensions, state::Bid};
+
+// Bidder takes mint B back and closes the bid
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(mut)]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = bidder,
+        has_one = mint_b @ EscrowError::InvalidMint,
+        has_one = bidder @ EscrowError::InvalidBidder,
+        seeds = [b"bid", bidder.key().as_ref(), bid.seed.to_le_bytes().as_ref()],
+        bump = bid.bump,
+    )]
+    pub bid: Account<'info, Bid>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelBid<'info> {
+    pub fn refund_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"bid",
+            self.bidder.key.as_ref(),
+            &self.bid.seed.to_le_bytes()[..],
+            &[self.bid.bump],
+        ]];
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: self.bidder_ata_b.to_account_info(),
+            mint: self.mint_b.to_account_info(),
+            authority: self.bid.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        let amount_b = self.vault.amount;
+
+        extensions::transfer_checked(cpi_context, amount_b, self.mint_b.decimals)?;
+
+        extensions::harvest_withheld(&self.token_program, &self.mint_b, &self.vault)?;
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.bidder.to_account_info(),
+            authority: self.bid.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
+
+        close_account(cpi_context)?;
+
+        Ok(amount_b)
+    }
+}
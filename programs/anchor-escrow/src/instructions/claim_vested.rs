@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError, extensions, instructions::refund::pay_out_and_close_vault,
+    state::VestingEscrow,
+};
+
+// Beneficiary claims whatever has vested since the last claim
+// Closes the escrow once the whole amount has been claimed
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub beneficiary_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = beneficiary @ EscrowError::InvalidBeneficiary,
+        has_one = mint @ EscrowError::InvalidMint,
+        seeds = [b"vesting", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, VestingEscrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimVested<'info> {
+    pub fn claim(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let now = Clock::get()?.unix_timestamp;
+        let amount = self.escrow.record_claim(now)?;
+
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"vesting",
+            self.maker.key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: self.beneficiary_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_context, amount, self.mint.decimals)?;
+
+        if !self.escrow.is_drained() {
+            return Ok(amount);
+        }
+
+        // Fully claimed: anything sent to the vault on top of the schedule goes to the beneficiary
+        self.vault.reload()?;
+
+        pay_out_and_close_vault(
+            &self.escrow.to_account_info(),
+            &signer_seeds,
+            &self.vault,
+            &self.mint,
+            &self.beneficiary_ata.to_account_info(),
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )?;
+
+        self.escrow.close(self.maker.to_account_info())?;
+
+        Ok(amount)
+    }
+}
@@ -0,0 +1,153 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{error::EscrowError, instructions::refund::return_and_close_vault, state::Escrow};
+
+// Anyone but the maker can close an expired escrow, returning the tokens and rent to the maker
+// The cranker is paid the bounty the maker posted for the trouble
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CrankExpired<'info> {
+    #[account(
+        mut,
+        constraint = cranker.key() != maker.key() @ EscrowError::MakerCannotCrank,
+    )]
+    pub cranker: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = mint_a @ EscrowError::InvalidMint,
+        has_one = maker @ EscrowError::InvalidMaker,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CrankExpired<'info> {
+    pub fn return_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.escrow.expiry,
+            EscrowError::EscrowNotExpired
+        );
+
+        return_and_close_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint_a,
+            &self.maker_ata_a,
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )
+    }
+
+    pub fn pay_bounty(&mut self) -> Result<u64> {
+        pay_bounty(&self.escrow, &self.cranker)
+    }
+}
+
+// Native SOL counterpart of `CrankExpired`: the deposit is held as lamports on the escrow itself,
+// so closing it returns the deposit and rent to the maker in one go
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CrankExpiredNative<'info> {
+    #[account(
+        mut,
+        constraint = cranker.key() != maker.key() @ EscrowError::MakerCannotCrank,
+    )]
+    pub cranker: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ EscrowError::InvalidMaker,
+        constraint = escrow.mint_a == Escrow::NATIVE_SOL @ EscrowError::InvalidMint,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CrankExpiredNative<'info> {
+    pub fn crank(&mut self) -> Result<u64> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.escrow.expiry,
+            EscrowError::EscrowNotExpired
+        );
+
+        Ok(self.escrow.remaining_a)
+    }
+
+    pub fn pay_bounty(&mut self) -> Result<u64> {
+        pay_bounty(&self.escrow, &self.cranker)
+    }
+}
+
+/// Move the crank bounty from `maker` onto the newly made `escrow`, where it stays until it is
+/// paid to a cranker or returned to the maker with the rent when the escrow closes.
+pub fn post_bounty<'info>(
+    maker: &Signer<'info>,
+    escrow: &Account<'info, Escrow>,
+    system_program: &Program<'info, System>,
+    bounty: u64,
+) -> Result<()> {
+    if bounty == 0 {
+        return Ok(());
+    }
+
+    let cpi_accounts = Transfer {
+        from: maker.to_account_info(),
+        to: escrow.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+
+    transfer(cpi_ctx, bounty)
+}
+
+/// Pays `cranker` the bounty the maker posted on `escrow` and returns it.
+/// Whatever the escrow holds besides goes back to the maker when it is closed.
+pub fn pay_bounty<'info>(escrow: &Account<'info, Escrow>, cranker: &Signer<'info>) -> Result<u64> {
+    let bounty = escrow.bounty;
+
+    if bounty > 0 {
+        escrow.sub_lamports(bounty)?;
+        cranker.add_lamports(bounty)?;
+    }
+
+    Ok(bounty)
+}
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::EscrowError,
+    instructions::{refund::pay_out_and_close_vault, take::pay_maker_less_fee},
+    state::{Bid, Config},
+};
+
+// A holder of mint A sells it to the bidder for the vault
+// As on an ask, the protocol fee comes out of the proceeds of the escrow's maker, here the bidder
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FillBid<'info> {
+    #[account(mut)]
+    pub filler: Signer<'info>,
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = filler,
+        associated_token::token_program = token_program,
+    )]
+    pub filler_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = filler,
+        associated_token::mint = mint_b,
+        associated_token::authority = filler,
+        associated_token::token_program = token_program,
+    )]
+    pub filler_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = filler,
+        associated_token::mint = mint_a,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        has_one = fee_recipient @ EscrowError::InvalidFeeRecipient,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: only used as the authority of `fee_recipient_ata_a`, checked against `config`
+    pub fee_recipient: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = filler,
+        associated_token::mint = mint_a,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_recipient_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = bidder,
+        has_one = bidder @ EscrowError::InvalidBidder,
+        has_one = mint_a @ EscrowError::InvalidMint,
+        has_one = mint_b @ EscrowError::InvalidMint,
+        seeds = [b"bid", bidder.key().as_ref(), bid.seed.to_le_bytes().as_ref()],
+        bump = bid.bump,
+    )]
+    pub bid: Account<'info, Bid>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FillBid<'info> {
+    /// Pays the bid's amount of mint A to the bidder and the protocol, and returns the fee.
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        require!(
+            Clock::get()?.unix_timestamp < self.bid.expiry,
+            EscrowError::EscrowExpired
+        );
+
+        pay_maker_less_fee(
+            &self.filler,
+            &self.filler_ata_a,
+            &self.bidder_ata_a,
+            &self.fee_recipient_ata_a,
+            &self.mint_a,
+            &self.token_program,
+            self.bid.fee_bps,
+            self.bid.amount_a,
+            remaining_accounts,
+        )
+    }
+
+    /// Pays out the whole vault to the filler.
+    pub fn withdraw_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"bid",
+            self.bidder.key.as_ref(),
+            &self.bid.seed.to_le_bytes()[..],
+            &[self.bid.bump],
+        ]];
+
+        pay_out_and_close_vault(
+            &self.bid.to_account_info(),
+            &signer_seeds,
+            &self.vault,
+            &self.mint_b,
+            &self.filler_ata_b.to_account_info(),
+            &self.bidder.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )
+    }
+}
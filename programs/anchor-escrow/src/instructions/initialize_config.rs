@@ -0,0 +1,49 @@
+use anchor_lang::{prelude::*, solana_program::bpf_loader_upgradeable};
+
+use crate::{error::EscrowError, state::Config};
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    // Only the program's upgrade authority may take the config, so it cannot be front-run
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ EscrowError::InvalidAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(
+        init,
+        payer = admin,
+        seeds = [b"config"],
+        bump,
+        space = 8 + Config::INIT_SPACE,
+    )]
+    pub config: Account<'info, Config>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn init_config(
+        &mut self,
+        fee_bps: u16,
+        fee_recipient: Pubkey,
+        oracle_program: Pubkey,
+        bumps: &InitializeConfigBumps,
+    ) -> Result<()> {
+        require!(fee_bps <= Config::MAX_FEE_BPS, EscrowError::InvalidFee);
+
+        self.config.set_inner(Config {
+            admin: self.admin.key(),
+            fee_recipient,
+            fee_bps,
+            oracle_program,
+            bump: bumps.config,
+        });
+
+        Ok(())
+    }
+}
@@ -1,11 +1,17 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::state::Escrow;
+use crate::{
+    error::EscrowError,
+    extensions,
+    instructions::crank_expired::post_bounty,
+    state::{Config, DutchAuction, Escrow},
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(seed: u64)]
 pub struct Make<'info> {
@@ -17,8 +23,11 @@ pub struct Make<'info> {
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = maker,
+        associated_token::token_program = token_program,
     )]
     pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
     #[account(
         init,
         payer = maker,
@@ -32,6 +41,7 @@ pub struct Make<'info> {
         payer = maker,
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
+        associated_token::token_program = token_program,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -40,20 +50,61 @@ pub struct Make<'info> {
 }
 
 impl<'info> Make<'info> {
-    pub fn init_escrow(&mut self, seed: u64, receive: u64, bumps: &MakeBumps) -> Result<()> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+        expiry: i64,
+        lock_period: i64,
+        allowed_taker: Option<Pubkey>,
+        auction: Option<DutchAuction>,
+        bounty: u64,
+        bumps: &MakeBumps,
+    ) -> Result<()> {
+        let unlock_at = Escrow::validate_terms(deposit, receive, expiry, lock_period)?;
+        if let Some(auction) = &auction {
+            auction.validate(receive, expiry)?;
+        }
+        require_keys_neq!(self.mint_a.key(), self.mint_b.key(), EscrowError::SameMint);
+        extensions::check_mint(&self.mint_a)?;
+        extensions::check_mint(&self.mint_b)?;
+
         self.escrow.set_inner(Escrow {
             seed,
             maker: self.maker.key(),
             mint_a: self.mint_a.key(),
             mint_b: self.mint_b.key(),
+            deposit,
             receive,
+            remaining_a: deposit,
+            remaining_b: receive,
+            expiry,
+            unlock_at,
+            bounty,
+            allowed_taker,
+            auction,
+            fee_bps: self.config.fee_bps,
             bump: bumps.escrow,
         });
 
-        Ok(())
+        post_bounty(&self.maker, &self.escrow, &self.system_program, bounty)
     }
 
-    pub fn deposit(&mut self, deposit: u64) -> Result<()> {
+    // The vault must end up holding exactly `deposit`, so any transfer fee is paid on top
+    pub fn deposit(
+        &mut self,
+        deposit: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let gross = extensions::gross_up(&self.mint_a, deposit)?;
+
+        require!(
+            self.maker_ata_a.amount >= gross,
+            EscrowError::InsufficientMakerBalance
+        );
+
         let cpi_program = self.token_program.to_account_info();
 
         let cpi_accounts = TransferChecked {
@@ -63,9 +114,10 @@ impl<'info> Make<'info> {
             mint: self.mint_a.to_account_info(),
         };
 
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
 
-        transfer_checked(cpi_ctx, deposit, self.mint_a.decimals)?;
+        extensions::transfer_checked(cpi_ctx, gross, self.mint_a.decimals)?;
 
         Ok(())
     }
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    state::{ArbitratedEscrow, ArbitrationStatus},
+};
+
+// Maker deposits funds for a taker, releasable by the maker or by an arbiter after a dispute,
+// and refundable to the maker once the escrow expires
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeArbitrated<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"arbitrated", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + ArbitratedEscrow::INIT_SPACE,
+    )]
+    pub escrow: Account<'info, ArbitratedEscrow>,
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeArbitrated<'info> {
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        amount: u64,
+        taker: Pubkey,
+        arbiter: Pubkey,
+        expiry: i64,
+        bumps: &MakeArbitratedBumps,
+    ) -> Result<()> {
+        require!(amount > 0, EscrowError::ZeroDeposit);
+        require!(
+            expiry > Clock::get()?.unix_timestamp,
+            EscrowError::InvalidExpiry
+        );
+        require!(
+            arbiter != self.maker.key() && arbiter != taker,
+            EscrowError::ArbiterIsParty
+        );
+        extensions::check_mint(&self.mint)?;
+
+        self.escrow.set_inner(ArbitratedEscrow {
+            seed,
+            maker: self.maker.key(),
+            taker,
+            arbiter,
+            mint: self.mint.key(),
+            amount,
+            expiry,
+            status: ArbitrationStatus::Active,
+            bump: bumps.escrow,
+        });
+
+        Ok(())
+    }
+
+    // The vault must end up holding exactly `amount`, so any transfer fee is paid on top
+    pub fn deposit(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let gross = extensions::gross_up(&self.mint, amount)?;
+
+        require!(
+            self.maker_ata.amount >= gross,
+            EscrowError::InsufficientMakerBalance
+        );
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, gross, self.mint.decimals)
+    }
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    instructions::take_basket::{
+        create_ata_if_missing, load_mint, require_ata, split_legs, BASKET_LEG_ACCOUNTS,
+    },
+    state::{BasketEscrow, BasketLeg, Config},
+};
+
+// Maker offers a basket of tokens for another basket, as a single all-or-nothing order
+//
+// remaining_accounts:
+// - `[mint, maker_ata, vault]` for each deposit leg, in the order of `deposit`
+// - then any accounts required by Token-2022 transfer hooks
+//
+// Every leg must be a mint of `token_program`, since the basket settles through that one program
+// Receive mints are only checked on take, as passing them here would not fit a 4+4 basket
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeBasket<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"basket", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + BasketEscrow::INIT_SPACE,
+    )]
+    pub escrow: Account<'info, BasketEscrow>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeBasket<'info> {
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        deposit: Vec<BasketLeg>,
+        receive: Vec<BasketLeg>,
+        expiry: i64,
+        bumps: &MakeBasketBumps,
+    ) -> Result<()> {
+        BasketEscrow::validate_legs(&deposit, &receive)?;
+        require!(
+            expiry > Clock::get()?.unix_timestamp,
+            EscrowError::InvalidExpiry
+        );
+
+        self.escrow.set_inner(BasketEscrow {
+            seed,
+            maker: self.maker.key(),
+            deposit,
+            receive,
+            expiry,
+            fee_bps: self.config.fee_bps,
+            bump: bumps.escrow,
+        });
+
+        Ok(())
+    }
+
+    // Each vault must end up holding exactly its leg, so any transfer fee is paid on top
+    pub fn deposit(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        let deposit = self.escrow.deposit.clone();
+        let (deposit_accounts, hook_accounts) =
+            split_legs(remaining_accounts, deposit.len(), BASKET_LEG_ACCOUNTS)?;
+
+        for (leg, accounts) in deposit
+            .iter()
+            .zip(deposit_accounts.chunks(BASKET_LEG_ACCOUNTS))
+        {
+            let [mint, maker_ata, vault] = accounts else {
+                return err!(EscrowError::InvalidBasketAccounts);
+            };
+            let mint = load_mint(mint, leg, &self.token_program)?;
+            extensions::check_mint(&mint)?;
+
+            require_ata(vault, &self.escrow.key(), &mint, &self.token_program)?;
+            create_ata_if_missing(
+                &self.maker,
+                vault,
+                &self.escrow,
+                &mint,
+                &self.associated_token_program,
+                &self.token_program,
+                &self.system_program,
+            )?;
+
+            let gross = extensions::gross_up(&mint, leg.amount)?;
+            let maker_balance = InterfaceAccount::<TokenAccount>::try_from(maker_ata)?.amount;
+
+            require!(
+                maker_balance >= gross,
+                EscrowError::InsufficientMakerBalance
+            );
+
+            let cpi_program = self.token_program.to_account_info();
+
+            let cpi_accounts = TransferChecked {
+                from: maker_ata.clone(),
+                to: vault.clone(),
+                authority: self.maker.to_account_info(),
+                mint: mint.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+                .with_remaining_accounts(hook_accounts.to_vec());
+
+            extensions::transfer_checked(cpi_ctx, gross, mint.decimals)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    state::{Bid, Config, Escrow},
+};
+
+// Bidder locks mint B in a vault and waits for a holder of mint A to fill it
+// The mirror image of `Make`, with the same PDA and vault layout under its own seed prefix
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = bidder,
+        associated_token::token_program = token_program,
+    )]
+    pub bidder_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = bidder,
+        seeds = [b"bid", bidder.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Bid::INIT_SPACE,
+    )]
+    pub bid: Account<'info, Bid>,
+    #[account(
+        init,
+        payer = bidder,
+        associated_token::mint = mint_b,
+        associated_token::authority = bid,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeBid<'info> {
+    pub fn init_bid(
+        &mut self,
+        seed: u64,
+        amount_b: u64,
+        amount_a: u64,
+        expiry: i64,
+        bumps: &MakeBidBumps,
+    ) -> Result<()> {
+        // Same terms as an escrow with the legs swapped, and nothing to unlock
+        Escrow::validate_terms(amount_b, amount_a, expiry, 0)?;
+        require_keys_neq!(self.mint_a.key(), self.mint_b.key(), EscrowError::SameMint);
+        extensions::check_mint(&self.mint_a)?;
+        extensions::check_mint(&self.mint_b)?;
+
+        self.bid.set_inner(Bid {
+            seed,
+            bidder: self.bidder.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            amount_a,
+            amount_b,
+            expiry,
+            fee_bps: self.config.fee_bps,
+            bump: bumps.bid,
+        });
+
+        Ok(())
+    }
+
+    // The vault must end up holding exactly `amount_b`, so any transfer fee is paid on top
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let gross = extensions::gross_up(&self.mint_b, self.bid.amount_b)?;
+
+        require!(
+            self.bidder_ata_b.amount >= gross,
+            EscrowError::InsufficientMakerBalance
+        );
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.bidder_ata_b.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.bidder.to_account_info(),
+            mint: self.mint_b.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, gross, self.mint_b.decimals)
+    }
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    instructions::crank_expired::post_bounty,
+    state::{Config, Escrow},
+};
+
+// Maker offers mint A from the vault in exchange for native SOL
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeForNative<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"escrow", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Escrow::INIT_SPACE,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeForNative<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+        expiry: i64,
+        lock_period: i64,
+        allowed_taker: Option<Pubkey>,
+        bounty: u64,
+        bumps: &MakeForNativeBumps,
+    ) -> Result<()> {
+        let unlock_at = Escrow::validate_terms(deposit, receive, expiry, lock_period)?;
+        extensions::check_mint(&self.mint_a)?;
+
+        self.escrow.set_inner(Escrow {
+            seed,
+            maker: self.maker.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: Escrow::NATIVE_SOL,
+            deposit,
+            receive,
+            remaining_a: deposit,
+            remaining_b: receive,
+            expiry,
+            unlock_at,
+            bounty,
+            allowed_taker,
+            auction: None,
+            fee_bps: self.config.fee_bps,
+            bump: bumps.escrow,
+        });
+
+        post_bounty(&self.maker, &self.escrow, &self.system_program, bounty)
+    }
+
+    // The vault must end up holding exactly `deposit`, so any transfer fee is paid on top
+    pub fn deposit(
+        &mut self,
+        deposit: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let gross = extensions::gross_up(&self.mint_a, deposit)?;
+
+        require!(
+            self.maker_ata_a.amount >= gross,
+            EscrowError::InsufficientMakerBalance
+        );
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata_a.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, gross, self.mint_a.decimals)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    instructions::crank_expired::post_bounty,
+    state::{Config, Escrow},
+};
+
+// Maker offers native SOL, held directly by the escrow PDA, in exchange for mint B
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeNative<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"escrow", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + Escrow::INIT_SPACE,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeNative<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+        expiry: i64,
+        lock_period: i64,
+        allowed_taker: Option<Pubkey>,
+        bounty: u64,
+        bumps: &MakeNativeBumps,
+    ) -> Result<()> {
+        let unlock_at = Escrow::validate_terms(deposit, receive, expiry, lock_period)?;
+        extensions::check_mint(&self.mint_b)?;
+
+        self.escrow.set_inner(Escrow {
+            seed,
+            maker: self.maker.key(),
+            mint_a: Escrow::NATIVE_SOL,
+            mint_b: self.mint_b.key(),
+            deposit,
+            receive,
+            remaining_a: deposit,
+            remaining_b: receive,
+            expiry,
+            unlock_at,
+            bounty,
+            allowed_taker,
+            auction: None,
+            fee_bps: self.config.fee_bps,
+            bump: bumps.escrow,
+        });
+
+        post_bounty(&self.maker, &self.escrow, &self.system_program, bounty)
+    }
+
+    // Lamports are held on top of the escrow's own rent-exempt balance
+    pub fn deposit(&mut self, deposit: u64) -> Result<()> {
+        require!(
+            self.maker.lamports() >= deposit,
+            EscrowError::InsufficientMakerBalance
+        );
+
+        let cpi_program = self.system_program.to_account_info();
+
+        let cpi_accounts = Transfer {
+            from: self.maker.to_account_info(),
+            to: self.escrow.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        transfer(cpi_ctx, deposit)
+    }
+}
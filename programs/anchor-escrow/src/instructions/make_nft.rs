@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions, metadata,
+    state::{Config, NftEscrow},
+};
+
+// Maker offers an NFT for an amount of mint B, which can itself be an NFT
+// Creators are paid the royalty set in the Metaplex metadata account of the NFT, which is always
+// passed: an NFT without metadata has an empty account at that address and pays no royalty
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeNft<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: checked against the Metaplex metadata PDA of `mint_a` in `metadata::royalty`
+    pub metadata: UncheckedAccount<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"nft", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + NftEscrow::INIT_SPACE,
+    )]
+    pub escrow: Account<'info, NftEscrow>,
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeNft<'info> {
+    pub fn init_escrow(&mut self, seed: u64, receive: u64, bumps: &MakeNftBumps) -> Result<()> {
+        require_keys_neq!(self.mint_a.key(), self.mint_b.key(), EscrowError::SameMint);
+        NftEscrow::validate_terms(&self.mint_a, &self.mint_b, receive)?;
+        extensions::check_mint(&self.mint_a)?;
+        extensions::check_mint(&self.mint_b)?;
+
+        let (royalty_bps, creators) = metadata::royalty(&self.metadata, &self.mint_a.key())?;
+
+        self.escrow.set_inner(NftEscrow {
+            seed,
+            maker: self.maker.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            receive,
+            royalty_bps,
+            creators,
+            fee_bps: self.config.fee_bps,
+            bump: bumps.escrow,
+        });
+
+        Ok(())
+    }
+
+    // A frozen account (e.g. a programmable NFT locked by its delegate) cannot be deposited
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        require!(!self.maker_ata_a.is_frozen(), EscrowError::NftFrozen);
+        require!(
+            self.maker_ata_a.amount == 1,
+            EscrowError::InsufficientMakerBalance
+        );
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata_a.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, 1, self.mint_a.decimals)
+    }
+}
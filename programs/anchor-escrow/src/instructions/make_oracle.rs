@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    oracle::PriceFeed,
+    state::{Config, OracleEscrow},
+};
+
+// Maker deposits mint A to be sold at the price `oracle` publishes when the escrow is taken
+// The feed and the oracle program owning it are pinned in the escrow
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeOracle<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = token_program)]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: owner and layout checked by `PriceFeed::load`, then pinned in the escrow
+    pub oracle: UncheckedAccount<'info>,
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"oracle", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + OracleEscrow::INIT_SPACE,
+    )]
+    pub escrow: Account<'info, OracleEscrow>,
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeOracle<'info> {
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        deposit: u64,
+        max_slippage_bps: u16,
+        max_staleness: i64,
+        bumps: &MakeOracleBumps,
+    ) -> Result<()> {
+        require_keys_neq!(self.mint_a.key(), self.mint_b.key(), EscrowError::SameMint);
+        OracleEscrow::validate_terms(deposit, max_slippage_bps, max_staleness)?;
+        extensions::check_mint(&self.mint_a)?;
+        extensions::check_mint(&self.mint_b)?;
+
+        let feed = PriceFeed::load(&self.oracle, &self.config.oracle_program)?;
+        let price = feed.fresh_price(max_staleness)?;
+
+        self.escrow.set_inner(OracleEscrow {
+            seed,
+            maker: self.maker.key(),
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            deposit,
+            oracle: self.oracle.key(),
+            oracle_program: self.config.oracle_program,
+            max_slippage_bps,
+            max_staleness,
+            fee_bps: self.config.fee_bps,
+            bump: bumps.escrow,
+        });
+
+        // The deposit must be worth something at the current price
+        self.escrow
+            .receive_at(price, feed.expo, self.mint_a.decimals, self.mint_b.decimals)?;
+
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // Transfer fees are paid by the maker, so the vault holds the full deposit
+        let gross = extensions::gross_up(&self.mint_a, self.escrow.deposit)?;
+
+        require!(
+            self.maker_ata_a.amount >= gross,
+            EscrowError::InsufficientMakerBalance
+        );
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata_a.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, gross, self.mint_a.decimals)
+    }
+}
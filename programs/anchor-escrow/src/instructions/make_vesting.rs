@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{error::EscrowError, extensions, state::VestingEscrow};
+
+// Maker locks mint A for a beneficiary who claims it linearly between `start` and `end`
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeVesting<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = maker,
+        seeds = [b"vesting", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + VestingEscrow::INIT_SPACE,
+    )]
+    pub escrow: Account<'info, VestingEscrow>,
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeVesting<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_escrow(
+        &mut self,
+        seed: u64,
+        beneficiary: Pubkey,
+        amount: u64,
+        start: i64,
+        cliff: i64,
+        end: i64,
+        bumps: &MakeVestingBumps,
+    ) -> Result<()> {
+        VestingEscrow::validate_schedule(amount, start, cliff, end)?;
+        extensions::check_mint(&self.mint)?;
+
+        self.escrow.set_inner(VestingEscrow {
+            seed,
+            maker: self.maker.key(),
+            beneficiary,
+            mint: self.mint.key(),
+            amount,
+            claimed: 0,
+            start,
+            cliff,
+            end,
+            bump: bumps.escrow,
+        });
+
+        Ok(())
+    }
+
+    // The vault must end up holding exactly `amount`, so any transfer fee is paid on top
+    pub fn deposit(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let gross = extensions::gross_up(&self.mint, amount)?;
+
+        require!(
+            self.maker_ata.amount >= gross,
+            EscrowError::InsufficientMakerBalance
+        );
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, gross, self.mint.decimals)
+    }
+}
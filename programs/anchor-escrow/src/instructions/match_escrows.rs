@@ -0,0 +1,325 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    instructions::refund::return_and_close_vault,
+    state::{Config, Escrow},
+};
+
+// Anyone can settle two crossing escrows against each other, vault to vault
+// Escrow X offers mint A for mint B and escrow Y offers mint B for mint A
+// Each maker gets their asking price less the protocol fee, and the matcher keeps the rest
+// Transfer fees come out of that surplus, so escrows of transfer-fee mints must cross by more
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MatchEscrows<'info> {
+    #[account(mut)]
+    pub matcher: Signer<'info>,
+    #[account(mut)]
+    pub maker_x: SystemAccount<'info>,
+    #[account(mut)]
+    pub maker_y: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        init_if_needed,
+        payer = matcher,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker_x,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_x_ata_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = matcher,
+        associated_token::mint = mint_b,
+        associated_token::authority = maker_x,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_x_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = matcher,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker_y,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_y_ata_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = matcher,
+        associated_token::mint = mint_b,
+        associated_token::authority = maker_y,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_y_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = matcher,
+        associated_token::mint = mint_a,
+        associated_token::authority = matcher,
+        associated_token::token_program = token_program,
+    )]
+    pub matcher_ata_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = matcher,
+        associated_token::mint = mint_b,
+        associated_token::authority = matcher,
+        associated_token::token_program = token_program,
+    )]
+    pub matcher_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        has_one = fee_recipient @ EscrowError::InvalidFeeRecipient,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+    /// CHECK: only used as the authority of the fee token accounts, checked against `config`
+    pub fee_recipient: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = matcher,
+        associated_token::mint = mint_a,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_recipient_ata_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = matcher,
+        associated_token::mint = mint_b,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_recipient_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = escrow_x.mint_a == mint_a.key() @ EscrowError::InvalidMint,
+        constraint = escrow_x.mint_b == mint_b.key() @ EscrowError::InvalidMint,
+        seeds = [b"escrow", maker_x.key().as_ref(), escrow_x.seed.to_le_bytes().as_ref()],
+        bump = escrow_x.bump,
+    )]
+    pub escrow_x: Box<Account<'info, Escrow>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow_x,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_x: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = escrow_y.mint_a == mint_b.key() @ EscrowError::InvalidMint,
+        constraint = escrow_y.mint_b == mint_a.key() @ EscrowError::InvalidMint,
+        seeds = [b"escrow", maker_y.key().as_ref(), escrow_y.seed.to_le_bytes().as_ref()],
+        bump = escrow_y.bump,
+    )]
+    pub escrow_y: Box<Account<'info, Escrow>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = escrow_y,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_y: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Amounts moved by a match, as reported by `EscrowsMatched`.
+pub struct Settlement {
+    /// Mint A released from X's vault.
+    pub amount_a: u64,
+    /// Mint B released from Y's vault.
+    pub amount_b: u64,
+    /// Part of `amount_a` kept by the matcher.
+    pub surplus_a: u64,
+    /// Part of `amount_b` kept by the matcher.
+    pub surplus_b: u64,
+    /// Protocol fee taken from maker Y's mint A proceeds.
+    pub fee_a: u64,
+    /// Protocol fee taken from maker X's mint B proceeds.
+    pub fee_b: u64,
+}
+
+impl<'info> MatchEscrows<'info> {
+    /// Settles as much of the two escrows as crosses and closes whichever is filled.
+    pub fn settle(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<Settlement> {
+        let now = Clock::get()?.unix_timestamp;
+
+        // Fill as much of X as possible without releasing more mint A than Y still wants
+        let fillable_b = (self.escrow_y.remaining_b as u128 * self.escrow_x.receive as u128
+            / self.escrow_x.deposit as u128)
+            .min(self.escrow_x.remaining_b as u128) as u64;
+        require!(fillable_b > 0, EscrowError::EscrowsDoNotCross);
+
+        self.escrow_x.validate_fill(self.matcher.key, fillable_b)?;
+        let amount_a = self.escrow_x.record_fill(fillable_b)?;
+
+        self.escrow_y.validate_fill(self.matcher.key, amount_a)?;
+        let amount_b = self.escrow_y.record_fill(amount_a)?;
+
+        // Each maker nets its current asking price less the fee fixed when it made its escrow,
+        // as on a take, with any transfer fee paid out of the surplus
+        let price_x = self.escrow_x.price_of(fillable_b, now)?;
+        let price_y = self.escrow_y.price_of(amount_a, now)?;
+        require!(
+            amount_a >= price_y && amount_b >= price_x,
+            EscrowError::EscrowsDoNotCross
+        );
+
+        let fee_b = Config::fee_on(self.escrow_x.fee_bps, price_x);
+        let fee_a = Config::fee_on(self.escrow_y.fee_bps, price_y);
+
+        let paid_x = extensions::gross_up(&self.mint_b, price_x - fee_b)?;
+        let paid_y = extensions::gross_up(&self.mint_a, price_y - fee_a)?;
+        let gross_fee_b = extensions::gross_up(&self.mint_b, fee_b)?;
+        let gross_fee_a = extensions::gross_up(&self.mint_a, fee_a)?;
+
+        let surplus_a = amount_a
+            .checked_sub(paid_y)
+            .and_then(|left| left.checked_sub(gross_fee_a))
+            .ok_or(EscrowError::SpreadBelowTransferFee)?;
+        let surplus_b = amount_b
+            .checked_sub(paid_x)
+            .and_then(|left| left.checked_sub(gross_fee_b))
+            .ok_or(EscrowError::SpreadBelowTransferFee)?;
+
+        let payouts = [
+            (
+                &self.escrow_x,
+                &self.vault_x,
+                &self.mint_a,
+                &self.maker_y_ata_a,
+                paid_y,
+            ),
+            (
+                &self.escrow_x,
+                &self.vault_x,
+                &self.mint_a,
+                &self.fee_recipient_ata_a,
+                gross_fee_a,
+            ),
+            (
+                &self.escrow_x,
+                &self.vault_x,
+                &self.mint_a,
+                &self.matcher_ata_a,
+                surplus_a,
+            ),
+            (
+                &self.escrow_y,
+                &self.vault_y,
+                &self.mint_b,
+                &self.maker_x_ata_b,
+                paid_x,
+            ),
+            (
+                &self.escrow_y,
+                &self.vault_y,
+                &self.mint_b,
+                &self.fee_recipient_ata_b,
+                gross_fee_b,
+            ),
+            (
+                &self.escrow_y,
+                &self.vault_y,
+                &self.mint_b,
+                &self.matcher_ata_b,
+                surplus_b,
+            ),
+        ];
+
+        for (escrow, vault, mint, to, amount) in payouts {
+            if amount > 0 {
+                transfer_from_vault(
+                    escrow,
+                    vault,
+                    mint,
+                    to,
+                    &self.token_program,
+                    amount,
+                    remaining_accounts,
+                )?;
+            }
+        }
+
+        // A filled escrow returns its rounding dust to the maker and closes
+        if self.escrow_x.is_filled() {
+            self.vault_x.reload()?;
+            return_and_close_vault(
+                &self.escrow_x,
+                &self.vault_x,
+                &self.mint_a,
+                &self.maker_x_ata_a,
+                &self.maker_x.to_account_info(),
+                &self.token_program,
+                remaining_accounts,
+            )?;
+            self.escrow_x.close(self.maker_x.to_account_info())?;
+        }
+
+        if self.escrow_y.is_filled() {
+            self.vault_y.reload()?;
+            return_and_close_vault(
+                &self.escrow_y,
+                &self.vault_y,
+                &self.mint_b,
+                &self.maker_y_ata_b,
+                &self.maker_y.to_account_info(),
+                &self.token_program,
+                remaining_accounts,
+            )?;
+            self.escrow_y.close(self.maker_y.to_account_info())?;
+        }
+
+        Ok(Settlement {
+            amount_a,
+            amount_b,
+            surplus_a,
+            surplus_b,
+            fee_a,
+            fee_b,
+        })
+    }
+}
+
+fn transfer_from_vault<'info>(
+    escrow: &Account<'info, Escrow>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let signer_seeds: [&[&[u8]]; 1] = [&[
+        b"escrow",
+        escrow.maker.as_ref(),
+        &escrow.seed.to_le_bytes()[..],
+        &[escrow.bump],
+    ]];
+
+    let cpi_program = token_program.to_account_info();
+
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        to: to.to_account_info(),
+        authority: escrow.to_account_info(),
+        mint: mint.to_account_info(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds)
+        .with_remaining_accounts(remaining_accounts.to_vec());
+
+    extensions::transfer_checked(cpi_context, amount, mint.decimals)
+}
@@ -1,7 +1,71 @@
+pub mod cancel_bid;
+pub mod claim_vested;
+pub mod crank_expired;
+pub mod fill_bid;
+pub mod initialize_config;
 pub mod make;
+pub mod make_arbitrated;
+pub mod make_basket;
+pub mod make_bid;
+pub mod make_for_native;
+pub mod make_milestones;
+pub mod make_native;
+pub mod make_nft;
+pub mod make_oracle;
+pub mod make_vesting;
+pub mod match_escrows;
+pub mod raise_dispute;
 pub mod refund;
+pub mod refund_arbitrated;
+pub mod refund_basket;
+pub mod refund_milestones;
+pub mod refund_native;
+pub mod refund_nft;
+pub mod refund_oracle;
+pub mod release;
+pub mod release_milestone;
+pub mod resolve;
 pub mod take;
+pub mod take_basket;
+pub mod take_for_native;
+pub mod take_native;
+pub mod take_nft;
+pub mod take_oracle;
+pub mod update;
+pub mod update_config;
 
+pub use cancel_bid::*;
+pub use claim_vested::*;
+pub use crank_expired::*;
+pub use fill_bid::*;
+pub use initialize_config::*;
 pub use make::*;
+pub use make_arbitrated::*;
+pub use make_basket::*;
+pub use make_bid::*;
+pub use make_for_native::*;
+pub use make_milestones::*;
+pub use make_native::*;
+pub use make_nft::*;
+pub use make_oracle::*;
+pub use make_vesting::*;
+pub use match_escrows::*;
+pub use raise_dispute::*;
 pub use refund::*;
-pub use take::*;
\ No newline at end of file
+pub use refund_arbitrated::*;
+pub use refund_basket::*;
+pub use refund_milestones::*;
+pub use refund_native::*;
+pub use refund_nft::*;
+pub use refund_oracle::*;
+pub use release::*;
+pub use release_milestone::*;
+pub use resolve::*;
+pub use take::*;
+pub use take_basket::*;
+pub use take_for_native::*;
+pub use take_native::*;
+pub use take_nft::*;
+pub use take_oracle::*;
+pub use update::*;
+pub use update_config::*;
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::EscrowError,
+    state::{ArbitratedEscrow, ArbitrationStatus},
+};
+
+// Either party freezes the escrow until the arbiter resolves it
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RaiseDispute<'info> {
+    pub party: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"arbitrated", escrow.maker.as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, ArbitratedEscrow>,
+}
+
+impl<'info> RaiseDispute<'info> {
+    pub fn raise_dispute(&mut self) -> Result<()> {
+        let party = self.party.key();
+
+        require!(
+            party == self.escrow.maker || party == self.escrow.taker,
+            EscrowError::UnauthorizedDisputant
+        );
+        require!(
+            self.escrow.status == ArbitrationStatus::Active,
+            EscrowError::EscrowDisputed
+        );
+
+        self.escrow.status = ArbitrationStatus::Disputed;
+
+        Ok(())
+    }
+}
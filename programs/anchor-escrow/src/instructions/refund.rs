@@ -1,24 +1,27 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount, close_account};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, CloseAccount, close_account};
 
-use crate::state::Escrow;
+use crate::{error::EscrowError, extensions, state::Escrow};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Refund<'info> {
     #[account(mut)]
     maker: Signer<'info>,
+    #[account(mut)]
     mint_a: InterfaceAccount<'info, Mint>,
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = maker,
+        associated_token::token_program = token_program,
     )]
     maker_ata_a: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         close = maker,
-        has_one = mint_a,
-        has_one = maker,
+        has_one = mint_a @ EscrowError::InvalidMint,
+        has_one = maker @ EscrowError::InvalidMaker,
         seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
     )]
@@ -27,6 +30,7 @@ pub struct Refund<'info> {
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
+        associated_token::token_program = token_program,
     )]
     vault: InterfaceAccount<'info, TokenAccount>,
     token_program: Interface<'info, TokenInterface>,
@@ -34,39 +38,101 @@ pub struct Refund<'info> {
 }
 
 impl<'info> Refund<'info> {
-    pub fn refund_and_close_vault(&mut self) -> Result<()> {
-        let signer_seeds: [&[&[u8]]; 1] = [&[
-            b"escrow",
-            self.maker.key.as_ref(),
-            &self.escrow.seed.to_le_bytes()[..],
-            &[self.escrow.bump]
-        ]];
-        
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = TransferChecked {
-            from: self.vault.to_account_info(),
-            to: self.maker_ata_a.to_account_info(),
-            mint: self.mint_a.to_account_info(),
-            authority: self.escrow.to_account_info(),
-        };
-
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
-
-        transfer_checked(cpi_context, self.vault.amount, self.mint_a.decimals)?;
-
-        let cpi_program = self.token_program.to_account_info();
-
-        let cpi_accounts = CloseAccount {
-            account: self.vault.to_account_info(),
-            destination: self.maker.to_account_info(),
-            authority: self.escrow.to_account_info(),
-        };
-
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
-
-        close_account(cpi_context)?;
-        
-        Ok(())
+    pub fn refund_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.escrow.unlock_at,
+            EscrowError::RefundLocked
+        );
+
+        return_and_close_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint_a,
+            &self.maker_ata_a,
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )
     }
-}
\ No newline at end of file
+}
+
+// Return everything left in the vault to the maker, then close it to the maker, who paid its rent
+// Shared by `Refund` and the permissionless `CrankExpired`, so it does not need the maker to sign
+pub fn return_and_close_vault<'info>(
+    escrow: &Account<'info, Escrow>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint_a: &InterfaceAccount<'info, Mint>,
+    maker_ata_a: &InterfaceAccount<'info, TokenAccount>,
+    maker: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let signer_seeds: [&[&[u8]]; 1] = [&[
+        b"escrow",
+        escrow.maker.as_ref(),
+        &escrow.seed.to_le_bytes()[..],
+        &[escrow.bump],
+    ]];
+
+    pay_out_and_close_vault(
+        &escrow.to_account_info(),
+        &signer_seeds,
+        vault,
+        mint_a,
+        &maker_ata_a.to_account_info(),
+        maker,
+        token_program,
+        remaining_accounts,
+    )
+}
+
+// Empty the vault into `to`, then close it to `rent_recipient`
+// `authority` is the PDA owning the vault, signing with `signer_seeds`, for any kind of escrow
+#[allow(clippy::too_many_arguments)]
+pub fn pay_out_and_close_vault<'info>(
+    authority: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    rent_recipient: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let cpi_program = token_program.to_account_info();
+
+    let cpi_accounts = TransferChecked {
+        from: vault.to_account_info(),
+        to: to.clone(),
+        mint: mint.to_account_info(),
+        authority: authority.clone(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds)
+        .with_remaining_accounts(remaining_accounts.to_vec());
+
+    let amount = vault.amount;
+
+    if amount > 0 {
+        extensions::transfer_checked(cpi_context, amount, mint.decimals)?;
+    }
+
+    extensions::harvest_withheld(token_program, mint, vault)?;
+
+    let cpi_program = token_program.to_account_info();
+
+    let cpi_accounts = CloseAccount {
+        account: vault.to_account_info(),
+        destination: rent_recipient.clone(),
+        authority: authority.clone(),
+    };
+
+    let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+    close_account(cpi_context)?;
+
+    Ok(amount)
+}
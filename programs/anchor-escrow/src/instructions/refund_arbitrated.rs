@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::EscrowError, instructions::resolve::settle_and_close_vault, state::ArbitratedEscrow,
+};
+
+// Maker takes the funds back once the escrow expires, disputed or not,
+// so they are not locked forever when the arbiter never resolves a dispute
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundArbitrated<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint @ EscrowError::InvalidMint,
+        seeds = [b"arbitrated", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, ArbitratedEscrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundArbitrated<'info> {
+    pub fn refund_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.escrow.expiry,
+            EscrowError::EscrowNotExpired
+        );
+
+        settle_and_close_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint,
+            &self.maker_ata,
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )
+    }
+}
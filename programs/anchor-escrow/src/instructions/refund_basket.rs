@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{associated_token::AssociatedToken, token_interface::TokenInterface};
+
+use crate::{
+    error::EscrowError,
+    instructions::take_basket::{
+        create_ata_if_missing, load_mint, pay_out_and_close_basket_vault, require_ata, split_legs,
+        BASKET_LEG_ACCOUNTS,
+    },
+    state::BasketEscrow,
+};
+
+// Maker takes back every deposit leg and closes the basket
+//
+// remaining_accounts:
+// - `[mint, vault, maker_ata]` for each deposit leg, in the order stored on the escrow
+// - then any accounts required by Token-2022 transfer hooks
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundBasket<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ EscrowError::InvalidMaker,
+        seeds = [b"basket", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, BasketEscrow>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundBasket<'info> {
+    pub fn refund_and_close_vaults(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let deposit = self.escrow.deposit.clone();
+        let (deposit_accounts, hook_accounts) =
+            split_legs(remaining_accounts, deposit.len(), BASKET_LEG_ACCOUNTS)?;
+
+        for (leg, accounts) in deposit
+            .iter()
+            .zip(deposit_accounts.chunks(BASKET_LEG_ACCOUNTS))
+        {
+            let [mint, vault, maker_ata] = accounts else {
+                return err!(EscrowError::InvalidBasketAccounts);
+            };
+            let mint = load_mint(mint, leg, &self.token_program)?;
+
+            require_ata(maker_ata, &self.maker.key(), &mint, &self.token_program)?;
+            create_ata_if_missing(
+                &self.maker,
+                maker_ata,
+                &self.maker,
+                &mint,
+                &self.associated_token_program,
+                &self.token_program,
+                &self.system_program,
+            )?;
+
+            pay_out_and_close_basket_vault(
+                &self.escrow,
+                vault,
+                &mint,
+                maker_ata,
+                &self.maker,
+                &self.token_program,
+                hook_accounts,
+            )?;
+        }
+
+        Ok(())
+    }
+}
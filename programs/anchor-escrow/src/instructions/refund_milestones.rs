@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError, extensions, instructions::refund::pay_out_and_close_vault,
+    state::MilestoneEscrow,
+};
+
+// Maker takes back every tranche that is still locked and closes the escrow
+// Tranches that have unlocked but were never released are paid to the taker instead
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundMilestones<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub taker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = taker @ EscrowError::InvalidTaker,
+        has_one = mint @ EscrowError::InvalidMint,
+        seeds = [b"milestone", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MilestoneEscrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundMilestones<'info> {
+    /// Returns the amount refunded to the maker and the amount paid to the taker.
+    pub fn refund_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<(u64, u64)> {
+        let now = Clock::get()?.unix_timestamp;
+        let released = self.escrow.record_refund(now);
+
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"milestone",
+            self.maker.key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        if released > 0 {
+            let cpi_program = self.token_program.to_account_info();
+
+            let cpi_accounts = TransferChecked {
+                from: self.vault.to_account_info(),
+                to: self.taker_ata.to_account_info(),
+                mint: self.mint.to_account_info(),
+                authority: self.escrow.to_account_info(),
+            };
+
+            let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds)
+                .with_remaining_accounts(remaining_accounts.to_vec());
+
+            extensions::transfer_checked(cpi_context, released, self.mint.decimals)?;
+        }
+
+        // The locked tranches, and anything sent to the vault on top of them, go back to the maker
+        self.vault.reload()?;
+
+        let amount = pay_out_and_close_vault(
+            &self.escrow.to_account_info(),
+            &signer_seeds,
+            &self.vault,
+            &self.mint,
+            &self.maker_ata.to_account_info(),
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )?;
+
+        Ok((amount, released))
+    }
+}
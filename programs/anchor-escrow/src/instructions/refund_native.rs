@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::EscrowError, state::Escrow};
+
+// Closing the escrow returns both the deposited lamports and its rent to the maker
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundNative<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ EscrowError::InvalidMaker,
+        constraint = escrow.mint_a == Escrow::NATIVE_SOL @ EscrowError::InvalidMint,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundNative<'info> {
+    pub fn refund(&mut self) -> Result<u64> {
+        require!(
+            Clock::get()?.unix_timestamp >= self.escrow.unlock_at,
+            EscrowError::RefundLocked
+        );
+
+        Ok(self.escrow.remaining_a)
+    }
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::EscrowError,
+    instructions::resolve::settle_and_close_vault,
+    state::{ArbitratedEscrow, ArbitrationStatus},
+};
+
+// Maker releases the funds to the taker, unless a dispute has been raised
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Release<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub taker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = taker @ EscrowError::InvalidTaker,
+        has_one = mint @ EscrowError::InvalidMint,
+        seeds = [b"arbitrated", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, ArbitratedEscrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Release<'info> {
+    pub fn release_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        require!(
+            self.escrow.status == ArbitrationStatus::Active,
+            EscrowError::EscrowDisputed
+        );
+
+        settle_and_close_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint,
+            &self.taker_ata,
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )
+    }
+}
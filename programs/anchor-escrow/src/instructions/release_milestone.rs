@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError, extensions, instructions::refund::pay_out_and_close_vault,
+    state::MilestoneEscrow,
+};
+
+// Pays one tranche to the taker, on the maker's approval or once the tranche unlocks
+// Closes the escrow once every tranche has been released
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReleaseMilestone<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    pub taker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = taker @ EscrowError::InvalidTaker,
+        has_one = mint @ EscrowError::InvalidMint,
+        seeds = [b"milestone", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, MilestoneEscrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ReleaseMilestone<'info> {
+    pub fn release(&mut self, index: u8, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let approved_by_maker = self.signer.key() == self.maker.key();
+        let amount = self.escrow.release(index, approved_by_maker)?;
+
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"milestone",
+            self.maker.key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: self.taker_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_context, amount, self.mint.decimals)?;
+
+        if !self.escrow.is_released() {
+            return Ok(amount);
+        }
+
+        // Every tranche released: anything sent to the vault on top of them goes to the taker
+        self.vault.reload()?;
+
+        pay_out_and_close_vault(
+            &self.escrow.to_account_info(),
+            &signer_seeds,
+            &self.vault,
+            &self.mint,
+            &self.taker_ata.to_account_info(),
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )?;
+
+        self.escrow.close(self.maker.to_account_info())?;
+
+        Ok(amount)
+    }
+}
@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::EscrowError,
+    instructions::refund::pay_out_and_close_vault,
+    state::{ArbitratedEscrow, ArbitrationStatus},
+};
+
+// Arbiter settles a dispute in favour of the taker
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResolveToTaker<'info> {
+    #[account(mut)]
+    pub arbiter: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    pub taker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        associated_token::mint = mint,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = arbiter @ EscrowError::InvalidArbiter,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = taker @ EscrowError::InvalidTaker,
+        has_one = mint @ EscrowError::InvalidMint,
+        seeds = [b"arbitrated", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, ArbitratedEscrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ResolveToTaker<'info> {
+    pub fn resolve_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        require!(
+            self.escrow.status == ArbitrationStatus::Disputed,
+            EscrowError::NotDisputed
+        );
+
+        settle_and_close_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint,
+            &self.taker_ata,
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )
+    }
+}
+
+// Arbiter settles a dispute in favour of the maker
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResolveToMaker<'info> {
+    #[account(mut)]
+    pub arbiter: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = arbiter @ EscrowError::InvalidArbiter,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint @ EscrowError::InvalidMint,
+        seeds = [b"arbitrated", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, ArbitratedEscrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ResolveToMaker<'info> {
+    pub fn resolve_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        require!(
+            self.escrow.status == ArbitrationStatus::Disputed,
+            EscrowError::NotDisputed
+        );
+
+        settle_and_close_vault(
+            &self.escrow,
+            &self.vault,
+            &self.mint,
+            &self.maker_ata,
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )
+    }
+}
+
+// Empty the vault into `to`, then close it to the maker, who paid its rent
+// Signs for the vault with the arbitrated escrow PDA
+pub fn settle_and_close_vault<'info>(
+    escrow: &Account<'info, ArbitratedEscrow>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    maker: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let signer_seeds: [&[&[u8]]; 1] = [&[
+        b"arbitrated",
+        escrow.maker.as_ref(),
+        &escrow.seed.to_le_bytes()[..],
+        &[escrow.bump],
+    ]];
+
+    pay_out_and_close_vault(
+        &escrow.to_account_info(),
+        &signer_seeds,
+        vault,
+        mint,
+        &to.to_account_info(),
+        maker,
+        token_program,
+        remaining_accounts,
+    )
+}
@@ -1,21 +1,25 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
-    },
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::state::Escrow;
+use crate::{
+    error::EscrowError,
+    extensions,
+    instructions::refund::pay_out_and_close_vault,
+    state::{Config, Escrow},
+};
 
 //Create context
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Take<'info> {
     #[account(mut)]
     pub taker: Signer<'info>,
     #[account(mut)]
     pub maker: SystemAccount<'info>,
+    #[account(mut)]
     pub mint_a: InterfaceAccount<'info, Mint>,
     pub mint_b: InterfaceAccount<'info, Mint>,
     #[account(
@@ -23,27 +27,54 @@ pub struct Take<'info> {
         payer = taker,
         associated_token::mint = mint_a,
         associated_token::authority = taker,
+        associated_token::token_program = token_program,
     )]
     pub taker_ata_a: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = taker,
+        associated_token::token_program = token_program,
     )]
     pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    /// Only needed by the fill that completes the escrow, to return rounding dust to the maker
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         init_if_needed,
         payer = taker,
         associated_token::mint = mint_b,
         associated_token::authority = maker,
+        associated_token::token_program = token_program,
     )]
     pub maker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        has_one = fee_recipient @ EscrowError::InvalidFeeRecipient,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: only used as the authority of `fee_recipient_ata_b`, checked against `config`
+    pub fee_recipient: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_recipient_ata_b: InterfaceAccount<'info, TokenAccount>,
     #[account(
         mut,
-        close = maker,
-        has_one = maker,
-        has_one = mint_a,
-        has_one = mint_b,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint_a @ EscrowError::InvalidMint,
+        has_one = mint_b @ EscrowError::InvalidMint,
         seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
     )]
@@ -52,6 +83,7 @@ pub struct Take<'info> {
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
+        associated_token::token_program = token_program,
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -59,26 +91,44 @@ pub struct Take<'info> {
     pub system_program: Program<'info, System>,
 }
 
-//Deposit tokens from taker to maker
-//Transfer tokens from vault to taker
-//Close vault account
+//Deposit tokens from taker to maker, less the protocol fee
+//Transfer the matching share of tokens from vault to taker and return it
+//Return any dust and close vault and escrow once fully filled
 impl<'info> Take<'info> {
-    pub fn deposit(&mut self) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
+    /// Returns the amount of mint B paid, fee included, and the fee.
+    pub fn deposit(
+        &mut self,
+        amount_b: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<(u64, u64)> {
+        self.escrow.validate_fill(self.taker.key, amount_b)?;
 
-        let cpi_accounts = TransferChecked {
-            from: self.taker_ata_b.to_account_info(),
-            to: self.maker_ata_b.to_account_info(),
-            authority: self.taker.to_account_info(),
-            mint: self.mint_b.to_account_info(),
-        };
+        // An auction charges its current price for the fill rather than the listed one
+        let amount_paid = self
+            .escrow
+            .price_of(amount_b, Clock::get()?.unix_timestamp)?;
+        let fee = pay_maker_less_fee(
+            &self.taker,
+            &self.taker_ata_b,
+            &self.maker_ata_b,
+            &self.fee_recipient_ata_b,
+            &self.mint_b,
+            &self.token_program,
+            self.escrow.fee_bps,
+            amount_paid,
+            remaining_accounts,
+        )?;
 
-        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
-
-        transfer_checked(cpi_ctx, self.escrow.receive, self.mint_b.decimals)
+        Ok((amount_paid, fee))
     }
 
-    pub fn withdraw_and_close_vault(&mut self) -> Result<()> {
+    pub fn withdraw_and_close_vault(
+        &mut self,
+        amount_b: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        let amount_a = self.escrow.record_fill(amount_b)?;
+
         let signer_seeds: [&[&[u8]]; 1] = [&[
             b"escrow",
             self.maker.key.as_ref(),
@@ -95,20 +145,102 @@ impl<'info> Take<'info> {
             mint: self.mint_a.to_account_info(),
         };
 
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds)
+            .with_remaining_accounts(remaining_accounts.to_vec());
 
-        transfer_checked(cpi_context, self.vault.amount, self.mint_a.decimals)?;
+        extensions::transfer_checked(cpi_context, amount_a, self.mint_a.decimals)?;
 
-        let cpi_program = self.token_program.to_account_info();
+        if !self.escrow.is_filled() {
+            return Ok(amount_a);
+        }
 
-        let cpi_accounts = CloseAccount {
-            account: self.vault.to_account_info(),
-            destination: self.maker.to_account_info(),
-            authority: self.escrow.to_account_info(),
+        // Fully filled: any rounding dust left in the vault goes back to the maker
+        let maker_ata_a = self
+            .maker_ata_a
+            .as_ref()
+            .ok_or(EscrowError::MakerAtaRequired)?;
+
+        self.vault.reload()?;
+
+        pay_out_and_close_vault(
+            &self.escrow.to_account_info(),
+            &signer_seeds,
+            &self.vault,
+            &self.mint_a,
+            &maker_ata_a.to_account_info(),
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )?;
+
+        self.escrow.close(self.maker.to_account_info())?;
+
+        Ok(amount_a)
+    }
+}
+
+// Pay `amount` of mint B from the taker to the maker, less the protocol fee at `fee_bps`,
+// which goes to the fee recipient. Returns the fee
+// Transfer fees are paid by the taker, so the maker and fee recipient net their full share
+#[allow(clippy::too_many_arguments)]
+pub fn pay_maker_less_fee<'info>(
+    taker: &Signer<'info>,
+    taker_ata_b: &InterfaceAccount<'info, TokenAccount>,
+    maker_ata_b: &InterfaceAccount<'info, TokenAccount>,
+    fee_recipient_ata_b: &InterfaceAccount<'info, TokenAccount>,
+    mint_b: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    fee_bps: u16,
+    amount: u64,
+    remaining_accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    let fee = Config::fee_on(fee_bps, amount);
+    let amount_to_maker = amount - fee;
+
+    let gross_to_maker = extensions::gross_up(mint_b, amount_to_maker)?;
+    let gross_fee = extensions::gross_up(mint_b, fee)?;
+
+    require!(
+        gross_to_maker
+            .checked_add(gross_fee)
+            .is_some_and(|total| taker_ata_b.amount >= total),
+        EscrowError::InsufficientTakerBalance
+    );
+
+    require!(
+        maker_ata_b.amount.checked_add(amount_to_maker).is_some(),
+        EscrowError::ReceiveOverflow
+    );
+
+    let cpi_program = token_program.to_account_info();
+
+    let cpi_accounts = TransferChecked {
+        from: taker_ata_b.to_account_info(),
+        to: maker_ata_b.to_account_info(),
+        authority: taker.to_account_info(),
+        mint: mint_b.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+        .with_remaining_accounts(remaining_accounts.to_vec());
+
+    extensions::transfer_checked(cpi_ctx, gross_to_maker, mint_b.decimals)?;
+
+    if fee > 0 {
+        let cpi_program = token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: taker_ata_b.to_account_info(),
+            to: fee_recipient_ata_b.to_account_info(),
+            authority: taker.to_account_info(),
+            mint: mint_b.to_account_info(),
         };
 
-        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds);
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
 
-        close_account(cpi_context)
+        extensions::transfer_checked(cpi_ctx, gross_fee, mint_b.decimals)?;
     }
+
+    Ok(fee)
 }
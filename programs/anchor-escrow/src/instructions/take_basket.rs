@@ -0,0 +1,327 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{
+        self, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    instructions::refund::pay_out_and_close_vault,
+    state::{BasketEscrow, BasketLeg, Config},
+};
+
+/// Number of accounts each deposit leg takes in `remaining_accounts`.
+pub const BASKET_LEG_ACCOUNTS: usize = 3;
+
+/// Number of accounts each receive leg of a take takes in `remaining_accounts`.
+pub const BASKET_RECEIVE_LEG_ACCOUNTS: usize = 4;
+
+// Taker pays every receive leg to the maker, less the protocol fee, and gets every deposit leg,
+// atomically
+//
+// remaining_accounts:
+// - `[mint, taker_ata, maker_ata, fee_recipient_ata]` for each receive leg, in the order stored
+//   on the escrow
+// - `[mint, vault, taker_ata]` for each deposit leg, in the order stored on the escrow
+// - then any accounts required by Token-2022 transfer hooks
+//
+// A 4+4 basket needs more accounts than a legacy transaction holds, so it is taken with an
+// address lookup table
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TakeBasket<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(
+        has_one = fee_recipient @ EscrowError::InvalidFeeRecipient,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: only used as the authority of the fee recipient's token accounts, checked against
+    /// `config`
+    pub fee_recipient: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ EscrowError::InvalidMaker,
+        seeds = [b"basket", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, BasketEscrow>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakeBasket<'info> {
+    /// Returns the protocol fee charged on each receive leg.
+    pub fn take(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Vec<BasketLeg>> {
+        require!(
+            Clock::get()?.unix_timestamp < self.escrow.expiry,
+            EscrowError::EscrowExpired
+        );
+
+        let receive = self.escrow.receive.clone();
+        let deposit = self.escrow.deposit.clone();
+
+        let (receive_accounts, rest) = split_legs(
+            remaining_accounts,
+            receive.len(),
+            BASKET_RECEIVE_LEG_ACCOUNTS,
+        )?;
+        let (deposit_accounts, hook_accounts) =
+            split_legs(rest, deposit.len(), BASKET_LEG_ACCOUNTS)?;
+
+        let fees = receive
+            .iter()
+            .zip(receive_accounts.chunks(BASKET_RECEIVE_LEG_ACCOUNTS))
+            .map(|(leg, accounts)| self.pay_maker(leg, accounts, hook_accounts))
+            .collect::<Result<Vec<_>>>()?;
+
+        for (leg, accounts) in deposit
+            .iter()
+            .zip(deposit_accounts.chunks(BASKET_LEG_ACCOUNTS))
+        {
+            let [mint, vault, taker_ata] = accounts else {
+                return err!(EscrowError::InvalidBasketAccounts);
+            };
+            let mint = load_mint(mint, leg, &self.token_program)?;
+
+            create_ata_if_missing(
+                &self.taker,
+                taker_ata,
+                &self.taker,
+                &mint,
+                &self.associated_token_program,
+                &self.token_program,
+                &self.system_program,
+            )?;
+
+            pay_out_and_close_basket_vault(
+                &self.escrow,
+                vault,
+                &mint,
+                taker_ata,
+                &self.maker,
+                &self.token_program,
+                hook_accounts,
+            )?;
+        }
+
+        Ok(fees)
+    }
+
+    // Pay one receive leg to the maker, less the protocol fee, and return the fee
+    // Transfer fees are paid by the taker, so the maker and fee recipient net their full share
+    fn pay_maker(
+        &self,
+        leg: &BasketLeg,
+        accounts: &'info [AccountInfo<'info>],
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<BasketLeg> {
+        let [mint, taker_ata, maker_ata, fee_recipient_ata] = accounts else {
+            return err!(EscrowError::InvalidBasketAccounts);
+        };
+        let mint = load_mint(mint, leg, &self.token_program)?;
+        extensions::check_mint(&mint)?;
+
+        require_ata(maker_ata, &self.maker.key(), &mint, &self.token_program)?;
+        create_ata_if_missing(
+            &self.taker,
+            maker_ata,
+            &self.maker,
+            &mint,
+            &self.associated_token_program,
+            &self.token_program,
+            &self.system_program,
+        )?;
+
+        let fee = Config::fee_on(self.escrow.fee_bps, leg.amount);
+        let amount_to_maker = leg.amount - fee;
+
+        let gross_to_maker = extensions::gross_up(&mint, amount_to_maker)?;
+        let gross_fee = extensions::gross_up(&mint, fee)?;
+        let taker_balance = InterfaceAccount::<TokenAccount>::try_from(taker_ata)?.amount;
+
+        require!(
+            gross_to_maker
+                .checked_add(gross_fee)
+                .is_some_and(|total| taker_balance >= total),
+            EscrowError::InsufficientTakerBalance
+        );
+
+        self.transfer_from_taker(taker_ata, maker_ata, &mint, gross_to_maker, hook_accounts)?;
+
+        if fee > 0 {
+            require_ata(
+                fee_recipient_ata,
+                &self.fee_recipient.key(),
+                &mint,
+                &self.token_program,
+            )?;
+            create_ata_if_missing(
+                &self.taker,
+                fee_recipient_ata,
+                &self.fee_recipient,
+                &mint,
+                &self.associated_token_program,
+                &self.token_program,
+                &self.system_program,
+            )?;
+
+            self.transfer_from_taker(
+                taker_ata,
+                fee_recipient_ata,
+                &mint,
+                gross_fee,
+                hook_accounts,
+            )?;
+        }
+
+        Ok(BasketLeg {
+            mint: leg.mint,
+            amount: fee,
+        })
+    }
+
+    fn transfer_from_taker(
+        &self,
+        taker_ata: &AccountInfo<'info>,
+        to: &AccountInfo<'info>,
+        mint: &InterfaceAccount<'info, Mint>,
+        amount: u64,
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: taker_ata.clone(),
+            to: to.clone(),
+            authority: self.taker.to_account_info(),
+            mint: mint.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(hook_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, amount, mint.decimals)
+    }
+}
+
+/// Split `remaining_accounts` into the account groups of `legs` legs of `leg_accounts` accounts
+/// each and whatever follows them.
+pub fn split_legs<'info>(
+    remaining_accounts: &'info [AccountInfo<'info>],
+    legs: usize,
+    leg_accounts: usize,
+) -> Result<(&'info [AccountInfo<'info>], &'info [AccountInfo<'info>])> {
+    let len = legs * leg_accounts;
+
+    require!(
+        remaining_accounts.len() >= len,
+        EscrowError::InvalidBasketAccounts
+    );
+
+    Ok(remaining_accounts.split_at(len))
+}
+
+/// Load the mint of `leg` from `info`, checking that it is the mint recorded on the escrow
+/// and that it belongs to the one token program the whole basket settles through.
+pub fn load_mint<'info>(
+    info: &'info AccountInfo<'info>,
+    leg: &BasketLeg,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<InterfaceAccount<'info, Mint>> {
+    require_keys_eq!(info.key(), leg.mint, EscrowError::InvalidMint);
+    require_keys_eq!(
+        *info.owner,
+        token_program.key(),
+        EscrowError::MixedTokenPrograms
+    );
+
+    InterfaceAccount::try_from(info)
+}
+
+/// Check that `info` is the associated token account of `wallet` for `mint`.
+pub fn require_ata(
+    info: &AccountInfo,
+    wallet: &Pubkey,
+    mint: &InterfaceAccount<Mint>,
+    token_program: &Interface<TokenInterface>,
+) -> Result<()> {
+    let expected =
+        get_associated_token_address_with_program_id(wallet, &mint.key(), &token_program.key());
+    require_keys_eq!(info.key(), expected, EscrowError::InvalidBasketAccounts);
+
+    Ok(())
+}
+
+/// Create the associated token account of `wallet` for `mint` unless it already exists.
+/// The associated token program checks the address when it creates the account.
+pub fn create_ata_if_missing<'info>(
+    payer: &impl ToAccountInfo<'info>,
+    ata: &AccountInfo<'info>,
+    wallet: &impl ToAccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    associated_token_program: &Program<'info, AssociatedToken>,
+    token_program: &Interface<'info, TokenInterface>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if !ata.data_is_empty() {
+        return Ok(());
+    }
+
+    let cpi_accounts = Create {
+        payer: payer.to_account_info(),
+        associated_token: ata.clone(),
+        authority: wallet.to_account_info(),
+        mint: mint.to_account_info(),
+        system_program: system_program.to_account_info(),
+        token_program: token_program.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new(associated_token_program.to_account_info(), cpi_accounts);
+
+    associated_token::create(cpi_ctx)
+}
+
+/// Empty the basket vault of `mint` into `to`, then close it to the maker, who paid its rent.
+pub fn pay_out_and_close_basket_vault<'info>(
+    escrow: &Account<'info, BasketEscrow>,
+    vault: &'info AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    to: &AccountInfo<'info>,
+    maker: &impl ToAccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<u64> {
+    require_ata(vault, &escrow.key(), mint, token_program)?;
+    let vault = InterfaceAccount::<TokenAccount>::try_from(vault)?;
+
+    let signer_seeds: [&[&[u8]]; 1] = [&[
+        b"basket",
+        escrow.maker.as_ref(),
+        &escrow.seed.to_le_bytes()[..],
+        &[escrow.bump],
+    ]];
+
+    pay_out_and_close_vault(
+        &escrow.to_account_info(),
+        &signer_seeds,
+        &vault,
+        mint,
+        to,
+        &maker.to_account_info(),
+        token_program,
+        hook_accounts,
+    )
+}
@@ -0,0 +1,177 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    instructions::refund::pay_out_and_close_vault,
+    state::{Config, Escrow},
+};
+
+// Take an escrow that wants native SOL by paying lamports
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TakeForNative<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_a,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    /// Only needed by the fill that completes the escrow, to return rounding dust to the maker
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        has_one = fee_recipient @ EscrowError::InvalidFeeRecipient,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: receives the fee in lamports, checked against `config`
+    #[account(mut)]
+    pub fee_recipient: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint_a @ EscrowError::InvalidMint,
+        constraint = escrow.mint_b == Escrow::NATIVE_SOL @ EscrowError::InvalidMint,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+//Deposit lamports from taker to maker, less the protocol fee
+//Transfer the matching share of tokens from vault to taker and return it
+//Return any dust and close vault and escrow once fully filled
+impl<'info> TakeForNative<'info> {
+    pub fn deposit(&mut self, amount_b: u64) -> Result<u64> {
+        self.escrow.validate_fill(self.taker.key, amount_b)?;
+
+        require!(
+            self.taker.lamports() >= amount_b,
+            EscrowError::InsufficientTakerBalance
+        );
+
+        let fee = Config::fee_on(self.escrow.fee_bps, amount_b);
+        let amount_to_maker = amount_b - fee;
+
+        self.pay_lamports(self.maker.to_account_info(), amount_to_maker)?;
+
+        if fee > 0 {
+            self.pay_lamports(self.fee_recipient.to_account_info(), fee)?;
+        }
+
+        Ok(fee)
+    }
+
+    // A system account must end up rent-exempt, so reject payments that would leave an
+    // empty recipient with a balance below the minimum
+    fn pay_lamports(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let balance = to
+            .lamports()
+            .checked_add(amount)
+            .ok_or(EscrowError::ReceiveOverflow)?;
+        require!(
+            Rent::get()?.is_exempt(balance, to.data_len()),
+            EscrowError::RecipientNotRentExempt
+        );
+
+        let cpi_program = self.system_program.to_account_info();
+
+        let cpi_accounts = Transfer {
+            from: self.taker.to_account_info(),
+            to,
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        transfer(cpi_ctx, amount)
+    }
+
+    pub fn withdraw_and_close_vault(
+        &mut self,
+        amount_b: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        let amount_a = self.escrow.record_fill(amount_b)?;
+
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"escrow",
+            self.maker.key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: self.taker_ata_a.to_account_info(),
+            authority: self.escrow.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_context, amount_a, self.mint_a.decimals)?;
+
+        if !self.escrow.is_filled() {
+            return Ok(amount_a);
+        }
+
+        // Fully filled: any rounding dust left in the vault goes back to the maker
+        let maker_ata_a = self
+            .maker_ata_a
+            .as_ref()
+            .ok_or(EscrowError::MakerAtaRequired)?;
+
+        self.vault.reload()?;
+
+        pay_out_and_close_vault(
+            &self.escrow.to_account_info(),
+            &signer_seeds,
+            &self.vault,
+            &self.mint_a,
+            &maker_ata_a.to_account_info(),
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )?;
+
+        self.escrow.close(self.maker.to_account_info())?;
+
+        Ok(amount_a)
+    }
+}
@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    state::{Config, Escrow},
+};
+
+// Take an escrow that holds native SOL by paying mint B
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TakeNative<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        has_one = fee_recipient @ EscrowError::InvalidFeeRecipient,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: only used as the authority of `fee_recipient_ata_b`, checked against `config`
+    pub fee_recipient: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_recipient_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint_b @ EscrowError::InvalidMint,
+        constraint = escrow.mint_a == Escrow::NATIVE_SOL @ EscrowError::InvalidMint,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+//Deposit tokens from taker to maker, less the protocol fee
+//Move the matching share of lamports from escrow to taker and return it
+//Close the escrow once fully filled, returning its rent and any dust to the maker
+impl<'info> TakeNative<'info> {
+    pub fn deposit(
+        &mut self,
+        amount_b: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        self.escrow.validate_fill(self.taker.key, amount_b)?;
+
+        let fee = Config::fee_on(self.escrow.fee_bps, amount_b);
+        let amount_to_maker = amount_b - fee;
+
+        // Transfer fees are paid by the taker, so the maker and fee recipient net their full share
+        let gross_to_maker = extensions::gross_up(&self.mint_b, amount_to_maker)?;
+        let gross_fee = extensions::gross_up(&self.mint_b, fee)?;
+
+        require!(
+            gross_to_maker
+                .checked_add(gross_fee)
+                .is_some_and(|total| self.taker_ata_b.amount >= total),
+            EscrowError::InsufficientTakerBalance
+        );
+
+        require!(
+            self.maker_ata_b
+                .amount
+                .checked_add(amount_to_maker)
+                .is_some(),
+            EscrowError::ReceiveOverflow
+        );
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.taker_ata_b.to_account_info(),
+            to: self.maker_ata_b.to_account_info(),
+            authority: self.taker.to_account_info(),
+            mint: self.mint_b.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, gross_to_maker, self.mint_b.decimals)?;
+
+        if fee > 0 {
+            let cpi_program = self.token_program.to_account_info();
+
+            let cpi_accounts = TransferChecked {
+                from: self.taker_ata_b.to_account_info(),
+                to: self.fee_recipient_ata_b.to_account_info(),
+                authority: self.taker.to_account_info(),
+                mint: self.mint_b.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+                .with_remaining_accounts(remaining_accounts.to_vec());
+
+            extensions::transfer_checked(cpi_ctx, gross_fee, self.mint_b.decimals)?;
+        }
+
+        Ok(fee)
+    }
+
+    pub fn withdraw_and_close(&mut self, amount_b: u64) -> Result<u64> {
+        let amount_a = self.escrow.record_fill(amount_b)?;
+
+        // The escrow is owned by this program, so its lamports can be moved directly.
+        // Only deposited lamports are paid out, so the escrow stays rent-exempt.
+        self.escrow.sub_lamports(amount_a)?;
+        self.taker.add_lamports(amount_a)?;
+
+        if self.escrow.is_filled() {
+            self.escrow.close(self.maker.to_account_info())?;
+        }
+
+        Ok(amount_a)
+    }
+}
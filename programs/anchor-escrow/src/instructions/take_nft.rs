@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::EscrowError,
+    extensions,
+    instructions::{
+        refund::pay_out_and_close_vault,
+        take_basket::{create_ata_if_missing, require_ata},
+    },
+    state::{Config, NftEscrow},
+};
+
+/// Number of accounts each royalty creator takes in `remaining_accounts`.
+pub const CREATOR_ACCOUNTS: usize = 2;
+
+// Taker pays the price, split between the maker, the creators and the protocol, and gets the NFT
+//
+// remaining_accounts:
+// - `[creator, creator_ata_b]` for each creator stored on the escrow, in order
+// - then any accounts required by Token-2022 transfer hooks
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TakeNft<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_a,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        has_one = fee_recipient @ EscrowError::InvalidFeeRecipient,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: only used as the authority of `fee_recipient_ata_b`, checked against `config`
+    pub fee_recipient: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_recipient_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint_a @ EscrowError::InvalidMint,
+        has_one = mint_b @ EscrowError::InvalidMint,
+        seeds = [b"nft", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, NftEscrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakeNft<'info> {
+    /// Pays the maker, the creators and the protocol, and returns the fee and total royalty.
+    /// Transfer fees are paid by the taker, so every recipient nets their full share.
+    pub fn pay(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<(u64, u64)> {
+        let creator_accounts_len = self.escrow.creators.len() * CREATOR_ACCOUNTS;
+
+        require!(
+            remaining_accounts.len() >= creator_accounts_len,
+            EscrowError::InvalidCreator
+        );
+
+        let (creator_accounts, hook_accounts) = remaining_accounts.split_at(creator_accounts_len);
+
+        let royalties = self.escrow.royalties();
+        let royalty: u64 = royalties.iter().sum();
+        let fee = Config::fee_on(self.escrow.fee_bps, self.escrow.receive);
+        let amount_to_maker = self
+            .escrow
+            .receive
+            .checked_sub(fee)
+            .and_then(|amount| amount.checked_sub(royalty))
+            .ok_or(EscrowError::RoyaltyExceedsPrice)?;
+
+        let mut payments = vec![
+            (self.maker_ata_b.to_account_info(), amount_to_maker),
+            (self.fee_recipient_ata_b.to_account_info(), fee),
+        ];
+
+        for ((creator, accounts), amount) in self
+            .escrow
+            .creators
+            .iter()
+            .zip(creator_accounts.chunks(CREATOR_ACCOUNTS))
+            .zip(royalties)
+        {
+            let [wallet, ata] = accounts else {
+                return err!(EscrowError::InvalidCreator);
+            };
+
+            require_keys_eq!(wallet.key(), creator.address, EscrowError::InvalidCreator);
+            require_ata(ata, &creator.address, &self.mint_b, &self.token_program)
+                .map_err(|_| error!(EscrowError::InvalidCreator))?;
+
+            if amount > 0 {
+                create_ata_if_missing(
+                    &self.taker,
+                    ata,
+                    wallet,
+                    &self.mint_b,
+                    &self.associated_token_program,
+                    &self.token_program,
+                    &self.system_program,
+                )?;
+            }
+
+            payments.push((ata.clone(), amount));
+        }
+
+        let mut total = 0u64;
+        for (_, amount) in payments.iter_mut() {
+            *amount = extensions::gross_up(&self.mint_b, *amount)?;
+            total = total
+                .checked_add(*amount)
+                .ok_or(EscrowError::InsufficientTakerBalance)?;
+        }
+
+        require!(
+            self.taker_ata_b.amount >= total,
+            EscrowError::InsufficientTakerBalance
+        );
+
+        for (to, amount) in payments {
+            if amount == 0 {
+                continue;
+            }
+
+            let cpi_program = self.token_program.to_account_info();
+
+            let cpi_accounts = TransferChecked {
+                from: self.taker_ata_b.to_account_info(),
+                to,
+                authority: self.taker.to_account_info(),
+                mint: self.mint_b.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+                .with_remaining_accounts(hook_accounts.to_vec());
+
+            extensions::transfer_checked(cpi_ctx, amount, self.mint_b.decimals)?;
+        }
+
+        Ok((fee, royalty))
+    }
+
+    pub fn withdraw_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"nft",
+            self.maker.key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        pay_out_and_close_vault(
+            &self.escrow.to_account_info(),
+            &signer_seeds,
+            &self.vault,
+            &self.mint_a,
+            &self.taker_ata_a.to_account_info(),
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::EscrowError,
+    instructions::{refund::pay_out_and_close_vault, take::pay_maker_less_fee},
+    oracle::PriceFeed,
+    state::{Config, OracleEscrow},
+};
+
+// Taker pays for the whole deposit at the feed's current price, less at most the maker's
+// slippage, as long as the feed is fresh
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TakeOracle<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(mut)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    pub mint_b: InterfaceAccount<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_a,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program,
+    )]
+    pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        has_one = fee_recipient @ EscrowError::InvalidFeeRecipient,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+    /// CHECK: only used as the authority of `fee_recipient_ata_b`, checked against `config`
+    pub fee_recipient: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = fee_recipient,
+        associated_token::token_program = token_program,
+    )]
+    pub fee_recipient_ata_b: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: checked against the feed pinned in `escrow`, owner and layout by `PriceFeed::load`
+    pub oracle: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        close = maker,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint_a @ EscrowError::InvalidMint,
+        has_one = mint_b @ EscrowError::InvalidMint,
+        has_one = oracle @ EscrowError::InvalidOracle,
+        seeds = [b"oracle", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, OracleEscrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakeOracle<'info> {
+    /// Reads the feed and checks that `amount_b` pays for the deposit at its price, less at most
+    /// the maker's slippage. Returns the price and its exponent.
+    pub fn check_payment(&self, amount_b: u64) -> Result<(u64, i32)> {
+        let feed = PriceFeed::load(&self.oracle, &self.escrow.oracle_program)?;
+        let price = feed.fresh_price(self.escrow.max_staleness)?;
+
+        let owed =
+            self.escrow
+                .receive_at(price, feed.expo, self.mint_a.decimals, self.mint_b.decimals)?;
+        require!(
+            amount_b >= self.escrow.min_payment(owed),
+            EscrowError::PaymentBelowOraclePrice
+        );
+
+        Ok((price, feed.expo))
+    }
+
+    /// Pays `amount_b` to the maker and the protocol, and returns the fee.
+    pub fn deposit(
+        &mut self,
+        amount_b: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        pay_maker_less_fee(
+            &self.taker,
+            &self.taker_ata_b,
+            &self.maker_ata_b,
+            &self.fee_recipient_ata_b,
+            &self.mint_b,
+            &self.token_program,
+            self.escrow.fee_bps,
+            amount_b,
+            remaining_accounts,
+        )
+    }
+
+    pub fn withdraw_and_close_vault(
+        &mut self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"oracle",
+            self.maker.key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        pay_out_and_close_vault(
+            &self.escrow.to_account_info(),
+            &signer_seeds,
+            &self.vault,
+            &self.mint_a,
+            &self.taker_ata_a.to_account_info(),
+            &self.maker.to_account_info(),
+            &self.token_program,
+            remaining_accounts,
+        )
+    }
+}
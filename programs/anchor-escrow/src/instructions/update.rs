@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{error::EscrowError, extensions, state::Escrow};
+
+// Maker changes the terms of an open token escrow in place, keeping its seed and PDA
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    pub mint_a: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program,
+    )]
+    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        mut,
+        has_one = mint_a @ EscrowError::InvalidMint,
+        has_one = maker @ EscrowError::InvalidMaker,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Update<'info> {
+    // `deposit` and `receive` replace the terms of whatever is still unfilled
+    // Withdrawing from the deposit is a partial refund, so it honours the refund lock
+    pub fn update_escrow(
+        &mut self,
+        deposit: Option<u64>,
+        receive: Option<u64>,
+        expiry: Option<i64>,
+        allowed_taker: Option<Option<Pubkey>>,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        if let Some(deposit) = deposit {
+            require!(deposit > 0, EscrowError::ZeroDeposit);
+
+            let remaining_a = self.escrow.remaining_a;
+
+            if deposit > remaining_a {
+                self.add_to_vault(deposit - remaining_a, remaining_accounts)?;
+            } else if deposit < remaining_a {
+                require!(now >= self.escrow.unlock_at, EscrowError::RefundLocked);
+                self.withdraw_from_vault(remaining_a - deposit, remaining_accounts)?;
+            }
+        }
+
+        if let Some(receive) = receive {
+            require!(receive > 0, EscrowError::ZeroReceive);
+        }
+
+        // Reprice the remainder at the new ratio
+        if deposit.is_some() || receive.is_some() {
+            let escrow = &mut self.escrow;
+            escrow.remaining_a = deposit.unwrap_or(escrow.remaining_a);
+            escrow.remaining_b = receive.unwrap_or(escrow.remaining_b);
+
+            // An auction keeps its curve, with the floor scaled to the new asking price
+            let (new_receive, old_receive) = (escrow.remaining_b, escrow.receive);
+            if let Some(auction) = escrow.auction.as_mut() {
+                let floor = auction.floor as u128 * new_receive as u128 / old_receive as u128;
+                auction.floor = (floor as u64).max(1);
+            }
+
+            escrow.deposit = escrow.remaining_a;
+            escrow.receive = escrow.remaining_b;
+        }
+
+        if let Some(expiry) = expiry {
+            // An auction must still reach its floor before the escrow expires
+            let auction_end = self.escrow.auction.map_or(i64::MIN, |auction| auction.end);
+            require!(
+                expiry > now && expiry >= self.escrow.unlock_at && expiry >= auction_end,
+                EscrowError::InvalidExpiry
+            );
+            self.escrow.expiry = expiry;
+        }
+
+        if let Some(allowed_taker) = allowed_taker {
+            self.escrow.allowed_taker = allowed_taker;
+        }
+
+        Ok(())
+    }
+
+    // The vault must end up holding exactly `amount` more, so any transfer fee is paid on top
+    fn add_to_vault(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let gross = extensions::gross_up(&self.mint_a, amount)?;
+
+        require!(
+            self.maker_ata_a.amount >= gross,
+            EscrowError::InsufficientMakerBalance
+        );
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.maker_ata_a.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, gross, self.mint_a.decimals)
+    }
+
+    fn withdraw_from_vault(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"escrow",
+            self.maker.key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        let cpi_program = self.token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: self.maker_ata_a.to_account_info(),
+            authority: self.escrow.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, &signer_seeds)
+            .with_remaining_accounts(remaining_accounts.to_vec());
+
+        extensions::transfer_checked(cpi_ctx, amount, self.mint_a.decimals)
+    }
+}
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::EscrowError, state::Config};
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(
+        mut,
+        has_one = admin @ EscrowError::InvalidAdmin,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(
+        &mut self,
+        fee_bps: u16,
+        fee_recipient: Pubkey,
+        oracle_program: Pubkey,
+        admin: Pubkey,
+    ) -> Result<()> {
+        require!(fee_bps <= Config::MAX_FEE_BPS, EscrowError::InvalidFee);
+
+        self.config.fee_bps = fee_bps;
+        self.config.fee_recipient = fee_recipient;
+        self.config.oracle_program = oracle_program;
+        self.config.admin = admin;
+
+        Ok(())
+    }
+}
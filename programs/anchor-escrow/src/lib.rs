@@ -3,11 +3,19 @@
 
 use anchor_lang::prelude::*;
 
-mod state;
+mod error;
+mod events;
+mod extensions;
+mod metadata;
+mod oracle;
+pub mod state;
 mod instructions;
+#[cfg(test)]
 mod tests;
 
+use events::*;
 use instructions::*;
+use state::{BasketLeg, DutchAuction, MilestoneTerms};
 
 declare_id!("FircrADQ2wgGuvpm8qneNCfKM7o5zoHTWnDQxngpTQ3J");
 
@@ -15,17 +23,473 @@ declare_id!("FircrADQ2wgGuvpm8qneNCfKM7o5zoHTWnDQxngpTQ3J");
 pub mod anchor_escrow {
     use super::*;
 
-    pub fn make(ctx: Context<Make>, seed: u64, deposit: u64, receive: u64) -> Result<()> {
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        fee_bps: u16,
+        fee_recipient: Pubkey,
+        oracle_program: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.init_config(fee_bps, fee_recipient, oracle_program, &ctx.bumps)
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        fee_bps: u16,
+        fee_recipient: Pubkey,
+        oracle_program: Pubkey,
+        admin: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.update_config(fee_bps, fee_recipient, oracle_program, admin)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn make<'info>(
+        ctx: Context<'_, '_, '_, 'info, Make<'info>>,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+        expiry: i64,
+        lock_period: i64,
+        allowed_taker: Option<Pubkey>,
+        auction: Option<DutchAuction>,
+        bounty: u64,
+    ) -> Result<()> {
+        ctx.accounts.init_escrow(
+            seed,
+            deposit,
+            receive,
+            expiry,
+            lock_period,
+            allowed_taker,
+            auction,
+            bounty,
+            &ctx.bumps,
+        )?;
+        ctx.accounts.deposit(deposit, ctx.remaining_accounts)?;
+        emit_cpi!(EscrowCreated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn make_native(
+        ctx: Context<MakeNative>,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+        expiry: i64,
+        lock_period: i64,
+        allowed_taker: Option<Pubkey>,
+        bounty: u64,
+    ) -> Result<()> {
+        ctx.accounts.init_escrow(
+            seed,
+            deposit,
+            receive,
+            expiry,
+            lock_period,
+            allowed_taker,
+            bounty,
+            &ctx.bumps,
+        )?;
+        ctx.accounts.deposit(deposit)?;
+        emit_cpi!(EscrowCreated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn make_for_native<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeForNative<'info>>,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+        expiry: i64,
+        lock_period: i64,
+        allowed_taker: Option<Pubkey>,
+        bounty: u64,
+    ) -> Result<()> {
+        ctx.accounts.init_escrow(
+            seed,
+            deposit,
+            receive,
+            expiry,
+            lock_period,
+            allowed_taker,
+            bounty,
+            &ctx.bumps,
+        )?;
+        ctx.accounts.deposit(deposit, ctx.remaining_accounts)?;
+        emit_cpi!(EscrowCreated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn refund<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>) -> Result<()> {
+        let amount_a = ctx.accounts.refund_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(EscrowRefunded::new(&ctx.accounts.escrow, amount_a)?);
+        Ok(())
+    }
+
+    pub fn refund_native(ctx: Context<RefundNative>) -> Result<()> {
+        let amount_a = ctx.accounts.refund()?;
+        emit_cpi!(EscrowRefunded::new(&ctx.accounts.escrow, amount_a)?);
+        Ok(())
+    }
+
+    pub fn update<'info>(
+        ctx: Context<'_, '_, '_, 'info, Update<'info>>,
+        deposit: Option<u64>,
+        receive: Option<u64>,
+        expiry: Option<i64>,
+        allowed_taker: Option<Option<Pubkey>>,
+    ) -> Result<()> {
+        ctx.accounts.update_escrow(
+            deposit,
+            receive,
+            expiry,
+            allowed_taker,
+            ctx.remaining_accounts,
+        )?;
+        emit_cpi!(EscrowUpdated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn take<'info>(ctx: Context<'_, '_, '_, 'info, Take<'info>>) -> Result<()> {
+        let amount_b = ctx.accounts.escrow.remaining_b;
+        let (amount_paid, fee) = ctx.accounts.deposit(amount_b, ctx.remaining_accounts)?;
+        let amount_a = ctx.accounts.withdraw_and_close_vault(amount_b, ctx.remaining_accounts)?;
+        emit_cpi!(EscrowTaken::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.taker.key(),
+            amount_a,
+            amount_paid,
+            fee,
+        )?);
+        Ok(())
+    }
+
+    pub fn take_partial<'info>(ctx: Context<'_, '_, '_, 'info, Take<'info>>, amount_b: u64) -> Result<()> {
+        let (amount_paid, fee) = ctx.accounts.deposit(amount_b, ctx.remaining_accounts)?;
+        let amount_a = ctx.accounts.withdraw_and_close_vault(amount_b, ctx.remaining_accounts)?;
+        emit_cpi!(EscrowTaken::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.taker.key(),
+            amount_a,
+            amount_paid,
+            fee,
+        )?);
+        Ok(())
+    }
+
+    pub fn take_native<'info>(ctx: Context<'_, '_, '_, 'info, TakeNative<'info>>, amount_b: u64) -> Result<()> {
+        let fee = ctx.accounts.deposit(amount_b, ctx.remaining_accounts)?;
+        let amount_a = ctx.accounts.withdraw_and_close(amount_b)?;
+        emit_cpi!(EscrowTaken::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.taker.key(),
+            amount_a,
+            amount_b,
+            fee,
+        )?);
+        Ok(())
+    }
+
+    pub fn take_for_native<'info>(ctx: Context<'_, '_, '_, 'info, TakeForNative<'info>>, amount_b: u64) -> Result<()> {
+        let fee = ctx.accounts.deposit(amount_b)?;
+        let amount_a = ctx.accounts.withdraw_and_close_vault(amount_b, ctx.remaining_accounts)?;
+        emit_cpi!(EscrowTaken::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.taker.key(),
+            amount_a,
+            amount_b,
+            fee,
+        )?);
+        Ok(())
+    }
+
+    pub fn make_arbitrated<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeArbitrated<'info>>,
+        seed: u64,
+        amount: u64,
+        taker: Pubkey,
+        arbiter: Pubkey,
+        expiry: i64,
+    ) -> Result<()> {
+        ctx.accounts.init_escrow(seed, amount, taker, arbiter, expiry, &ctx.bumps)?;
+        ctx.accounts.deposit(amount, ctx.remaining_accounts)?;
+        emit_cpi!(ArbitratedEscrowCreated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn release<'info>(ctx: Context<'_, '_, '_, 'info, Release<'info>>) -> Result<()> {
+        let amount = ctx.accounts.release_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(EscrowResolved::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.taker.key(),
+            ctx.accounts.maker.key(),
+            amount,
+        )?);
+        Ok(())
+    }
+
+    pub fn raise_dispute(ctx: Context<RaiseDispute>) -> Result<()> {
+        ctx.accounts.raise_dispute()?;
+        emit_cpi!(EscrowDisputed::new(&ctx.accounts.escrow, ctx.accounts.party.key())?);
+        Ok(())
+    }
+
+    pub fn resolve_to_taker<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResolveToTaker<'info>>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.resolve_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(EscrowResolved::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.taker.key(),
+            ctx.accounts.arbiter.key(),
+            amount,
+        )?);
+        Ok(())
+    }
+
+    pub fn resolve_to_maker<'info>(
+        ctx: Context<'_, '_, '_, 'info, ResolveToMaker<'info>>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.resolve_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(EscrowResolved::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.maker.key(),
+            ctx.accounts.arbiter.key(),
+            amount,
+        )?);
+        Ok(())
+    }
+
+    pub fn refund_arbitrated<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefundArbitrated<'info>>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.refund_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(EscrowResolved::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.maker.key(),
+            ctx.accounts.maker.key(),
+            amount,
+        )?);
+        Ok(())
+    }
+
+    pub fn make_milestones<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeMilestones<'info>>,
+        seed: u64,
+        taker: Pubkey,
+        milestones: Vec<MilestoneTerms>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.init_escrow(seed, taker, milestones, &ctx.bumps)?;
+        ctx.accounts.deposit(amount, ctx.remaining_accounts)?;
+        emit_cpi!(MilestoneEscrowCreated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn release_milestone<'info>(
+        ctx: Context<'_, '_, '_, 'info, ReleaseMilestone<'info>>,
+        index: u8,
+    ) -> Result<()> {
+        let amount = ctx.accounts.release(index, ctx.remaining_accounts)?;
+        emit_cpi!(MilestoneReleased::new(
+            &ctx.accounts.escrow,
+            index,
+            amount,
+            ctx.accounts.signer.key(),
+        )?);
+        Ok(())
+    }
+
+    pub fn refund_milestones<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefundMilestones<'info>>,
+    ) -> Result<()> {
+        let (amount, released) = ctx.accounts.refund_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(MilestonesRefunded::new(&ctx.accounts.escrow, amount, released)?);
+        Ok(())
+    }
+
+    pub fn make_vesting<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeVesting<'info>>,
+        seed: u64,
+        beneficiary: Pubkey,
+        amount: u64,
+        start: i64,
+        cliff: i64,
+        end: i64,
+    ) -> Result<()> {
+        ctx.accounts.init_escrow(seed, beneficiary, amount, start, cliff, end, &ctx.bumps)?;
+        ctx.accounts.deposit(amount, ctx.remaining_accounts)?;
+        emit_cpi!(VestingEscrowCreated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn claim_vested<'info>(ctx: Context<'_, '_, '_, 'info, ClaimVested<'info>>) -> Result<()> {
+        let amount = ctx.accounts.claim(ctx.remaining_accounts)?;
+        emit_cpi!(VestedClaimed::new(&ctx.accounts.escrow, amount)?);
+        Ok(())
+    }
+
+    pub fn make_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakeBasket<'info>>,
+        seed: u64,
+        deposit: Vec<BasketLeg>,
+        receive: Vec<BasketLeg>,
+        expiry: i64,
+    ) -> Result<()> {
+        ctx.accounts.init_escrow(seed, deposit, receive, expiry, &ctx.bumps)?;
+        ctx.accounts.deposit(ctx.remaining_accounts)?;
+        emit_cpi!(BasketCreated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn take_basket<'info>(ctx: Context<'_, '_, 'info, 'info, TakeBasket<'info>>) -> Result<()> {
+        let fees = ctx.accounts.take(ctx.remaining_accounts)?;
+        emit_cpi!(BasketTaken::new(&ctx.accounts.escrow, ctx.accounts.taker.key(), fees)?);
+        Ok(())
+    }
+
+    pub fn refund_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundBasket<'info>>,
+    ) -> Result<()> {
+        ctx.accounts.refund_and_close_vaults(ctx.remaining_accounts)?;
+        emit_cpi!(BasketRefunded::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn make_nft<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeNft<'info>>,
+        seed: u64,
+        receive: u64,
+    ) -> Result<()> {
         ctx.accounts.init_escrow(seed, receive, &ctx.bumps)?;
-        ctx.accounts.deposit(deposit)
+        ctx.accounts.deposit(ctx.remaining_accounts)?;
+        emit_cpi!(NftEscrowCreated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn take_nft<'info>(ctx: Context<'_, '_, 'info, 'info, TakeNft<'info>>) -> Result<()> {
+        let (fee, royalty) = ctx.accounts.pay(ctx.remaining_accounts)?;
+        let creator_accounts = ctx.accounts.escrow.creators.len() * CREATOR_ACCOUNTS;
+        ctx.accounts
+            .withdraw_and_close_vault(&ctx.remaining_accounts[creator_accounts..])?;
+        emit_cpi!(NftEscrowTaken::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.taker.key(),
+            fee,
+            royalty,
+        )?);
+        Ok(())
+    }
+
+    pub fn refund_nft<'info>(ctx: Context<'_, '_, '_, 'info, RefundNft<'info>>) -> Result<()> {
+        ctx.accounts.refund_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(NftEscrowRefunded::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn crank_expired<'info>(
+        ctx: Context<'_, '_, '_, 'info, CrankExpired<'info>>,
+    ) -> Result<()> {
+        let amount_a = ctx.accounts.return_and_close_vault(ctx.remaining_accounts)?;
+        let bounty = ctx.accounts.pay_bounty()?;
+        emit_cpi!(EscrowCranked::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.cranker.key(),
+            amount_a,
+            bounty,
+        )?);
+        Ok(())
+    }
+
+    pub fn crank_expired_native(ctx: Context<CrankExpiredNative>) -> Result<()> {
+        let amount_a = ctx.accounts.crank()?;
+        let bounty = ctx.accounts.pay_bounty()?;
+        emit_cpi!(EscrowCranked::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.cranker.key(),
+            amount_a,
+            bounty,
+        )?);
+        Ok(())
+    }
+
+    pub fn make_oracle<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeOracle<'info>>,
+        seed: u64,
+        deposit: u64,
+        max_slippage_bps: u16,
+        max_staleness: i64,
+    ) -> Result<()> {
+        ctx.accounts.init_escrow(seed, deposit, max_slippage_bps, max_staleness, &ctx.bumps)?;
+        ctx.accounts.deposit(ctx.remaining_accounts)?;
+        emit_cpi!(OracleEscrowCreated::new(&ctx.accounts.escrow)?);
+        Ok(())
+    }
+
+    pub fn take_oracle<'info>(
+        ctx: Context<'_, '_, '_, 'info, TakeOracle<'info>>,
+        amount_b: u64,
+    ) -> Result<()> {
+        let (price, expo) = ctx.accounts.check_payment(amount_b)?;
+        let fee = ctx.accounts.deposit(amount_b, ctx.remaining_accounts)?;
+        let amount_a = ctx.accounts.withdraw_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(OracleEscrowTaken::new(
+            &ctx.accounts.escrow,
+            ctx.accounts.taker.key(),
+            price,
+            expo,
+            amount_a,
+            amount_b,
+            fee,
+        )?);
+        Ok(())
+    }
+
+    pub fn refund_oracle<'info>(
+        ctx: Context<'_, '_, '_, 'info, RefundOracle<'info>>,
+    ) -> Result<()> {
+        let amount_a = ctx.accounts.refund_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(OracleEscrowRefunded::new(&ctx.accounts.escrow, amount_a)?);
+        Ok(())
+    }
+
+    pub fn make_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeBid<'info>>,
+        seed: u64,
+        amount_b: u64,
+        amount_a: u64,
+        expiry: i64,
+    ) -> Result<()> {
+        ctx.accounts.init_bid(seed, amount_b, amount_a, expiry, &ctx.bumps)?;
+        ctx.accounts.deposit(ctx.remaining_accounts)?;
+        emit_cpi!(BidCreated::new(&ctx.accounts.bid)?);
+        Ok(())
+    }
+
+    pub fn fill_bid<'info>(ctx: Context<'_, '_, '_, 'info, FillBid<'info>>) -> Result<()> {
+        let fee = ctx.accounts.deposit(ctx.remaining_accounts)?;
+        ctx.accounts.withdraw_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(BidFilled::new(&ctx.accounts.bid, ctx.accounts.filler.key(), fee)?);
+        Ok(())
     }
 
-    pub fn refund(ctx: Context<Refund>) -> Result<()> {
-        ctx.accounts.refund_and_close_vault()
+    pub fn cancel_bid<'info>(ctx: Context<'_, '_, '_, 'info, CancelBid<'info>>) -> Result<()> {
+        let amount_b = ctx.accounts.refund_and_close_vault(ctx.remaining_accounts)?;
+        emit_cpi!(BidCancelled::new(&ctx.accounts.bid, amount_b)?);
+        Ok(())
     }
 
-    pub fn take(ctx: Context<Take>) -> Result<()> {
-        ctx.accounts.deposit()?;
-        ctx.accounts.withdraw_and_close_vault()
+    pub fn match_escrows<'info>(
+        ctx: Context<'_, '_, '_, 'info, MatchEscrows<'info>>,
+    ) -> Result<()> {
+        let settlement = ctx.accounts.settle(ctx.remaining_accounts)?;
+        emit_cpi!(EscrowsMatched::new(
+            &ctx.accounts.escrow_x,
+            &ctx.accounts.escrow_y,
+            ctx.accounts.matcher.key(),
+            &settlement,
+        )?);
+        Ok(())
     }
-}
\ No newline at end of file
+}
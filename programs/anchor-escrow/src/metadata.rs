@@ -0,0 +1,97 @@
+use anchor_lang::{prelude::*, solana_program::pubkey};
+
+use crate::{error::EscrowError, state::RoyaltyShare};
+
+/// Metaplex Token Metadata program.
+pub const METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Most creators a Metaplex metadata account can list.
+pub const MAX_CREATORS: usize = 5;
+
+/// `Key::MetadataV1` discriminant of Metaplex accounts.
+const METADATA_V1: u8 = 4;
+
+#[derive(AnchorDeserialize)]
+struct Creator {
+    address: Pubkey,
+    _verified: bool,
+    share: u8,
+}
+
+/// Leading fields of a Metaplex `Metadata` account, up to and including the creators.
+#[derive(AnchorDeserialize)]
+struct MetadataPrefix {
+    key: u8,
+    _update_authority: Pubkey,
+    mint: Pubkey,
+    _name: String,
+    _symbol: String,
+    _uri: String,
+    seller_fee_basis_points: u16,
+    creators: Option<Vec<Creator>>,
+}
+
+/// Master edition PDA of `mint`, which Metaplex makes the mint authority of an NFT.
+pub fn master_edition(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            b"edition",
+        ],
+        &METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Royalty terms of `mint` from its Metaplex metadata account.
+/// Returns the royalty in basis points and the creators sharing it, skipping zero shares.
+/// An empty account at the metadata PDA means the NFT has no metadata, and so no royalty.
+pub fn royalty(metadata: &AccountInfo, mint: &Pubkey) -> Result<(u16, Vec<RoyaltyShare>)> {
+    let (expected, _) = Pubkey::find_program_address(
+        &[b"metadata", METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &METADATA_PROGRAM_ID,
+    );
+
+    require_keys_eq!(metadata.key(), expected, EscrowError::InvalidNftMetadata);
+
+    if metadata.data_is_empty() {
+        return Ok((0, vec![]));
+    }
+
+    require_keys_eq!(
+        *metadata.owner,
+        METADATA_PROGRAM_ID,
+        EscrowError::InvalidNftMetadata
+    );
+
+    let data = metadata.try_borrow_data()?;
+    let prefix = MetadataPrefix::deserialize(&mut &data[..])
+        .map_err(|_| error!(EscrowError::InvalidNftMetadata))?;
+
+    require!(
+        prefix.key == METADATA_V1 && prefix.mint == *mint,
+        EscrowError::InvalidNftMetadata
+    );
+
+    let creators = prefix.creators.unwrap_or_default();
+
+    require!(
+        prefix.seller_fee_basis_points <= 10_000
+            && creators.len() <= MAX_CREATORS
+            && (creators.is_empty() || creators.iter().map(|c| c.share as u16).sum::<u16>() == 100),
+        EscrowError::InvalidNftMetadata
+    );
+
+    let shares = creators
+        .into_iter()
+        .filter(|creator| creator.share > 0)
+        .map(|creator| RoyaltyShare {
+            address: creator.address,
+            share: creator.share,
+        })
+        .collect();
+
+    Ok((prefix.seller_fee_basis_points, shares))
+}
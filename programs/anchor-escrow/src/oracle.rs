@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+
+/// Magic number at the start of every price feed account.
+pub const PRICE_FEED_MAGIC: u32 = 0x5052_4345;
+
+/// Price feed account layout, the subset of a push oracle's price account the escrow reads.
+/// The feed and its owning program are pinned when the escrow is made; loading checks the owner.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PriceFeed {
+    pub magic: u32,
+    /// Price of one whole token of mint A in whole tokens of mint B, scaled by `10^expo`.
+    pub price: i64,
+    pub expo: i32,
+    /// Unix timestamp the price was published at.
+    pub publish_time: i64,
+}
+
+impl PriceFeed {
+    /// Read a feed, which must be owned by `oracle_program`.
+    pub fn load(feed: &AccountInfo, oracle_program: &Pubkey) -> Result<Self> {
+        require_keys_eq!(*feed.owner, *oracle_program, EscrowError::InvalidOracle);
+
+        let data = feed.try_borrow_data()?;
+        let feed =
+            Self::deserialize(&mut &data[..]).map_err(|_| error!(EscrowError::InvalidOracle))?;
+
+        require!(feed.magic == PRICE_FEED_MAGIC, EscrowError::InvalidOracle);
+
+        Ok(feed)
+    }
+
+    /// The published price, if it is positive and no older than `max_staleness` seconds.
+    pub fn fresh_price(&self, max_staleness: i64) -> Result<u64> {
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            now.saturating_sub(self.publish_time) <= max_staleness,
+            EscrowError::StaleOraclePrice
+        );
+        require!(self.price > 0, EscrowError::InvalidOraclePrice);
+
+        Ok(self.price as u64)
+    }
+}
@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum ArbitrationStatus {
+    /// Funds can be released to the taker by the maker.
+    Active,
+    /// Funds are frozen until the arbiter resolves the dispute.
+    Disputed,
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct ArbitratedEscrow {
+    pub seed: u64,
+    /// Depositor, refunded when a dispute is resolved in their favour.
+    pub maker: Pubkey,
+    /// Beneficiary, paid on release or when a dispute is resolved in their favour.
+    pub taker: Pubkey,
+    /// Third party allowed to resolve disputes.
+    pub arbiter: Pubkey,
+    pub mint: Pubkey,
+    /// Amount of `mint` held in the vault.
+    pub amount: u64,
+    /// Unix timestamp from which the maker can take the funds back if they are still held,
+    /// so a dispute the arbiter never resolves does not lock them forever.
+    pub expiry: i64,
+    pub status: ArbitrationStatus,
+    pub bump: u8,
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+
+/// Most legs a basket escrow can hold on each side.
+pub const MAX_BASKET_LEGS: usize = 4;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct BasketLeg {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct BasketEscrow {
+    pub seed: u64,
+    pub maker: Pubkey,
+    /// Tokens deposited by the maker, one vault per mint.
+    #[max_len(MAX_BASKET_LEGS)]
+    pub deposit: Vec<BasketLeg>,
+    /// Tokens the maker wants for the whole deposit.
+    #[max_len(MAX_BASKET_LEGS)]
+    pub receive: Vec<BasketLeg>,
+    /// Unix timestamp after which the basket can no longer be taken.
+    pub expiry: i64,
+    /// Protocol fee on each receive leg, snapshotted from the config when the basket is made.
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+impl BasketEscrow {
+    /// Validate both sides of a new basket.
+    /// Mints must be unique across the whole basket, so each deposited mint has exactly one vault.
+    pub fn validate_legs(deposit: &[BasketLeg], receive: &[BasketLeg]) -> Result<()> {
+        require!(
+            (1..=MAX_BASKET_LEGS).contains(&deposit.len())
+                && (1..=MAX_BASKET_LEGS).contains(&receive.len()),
+            EscrowError::InvalidBasketSize
+        );
+        require!(
+            deposit.iter().all(|leg| leg.amount > 0),
+            EscrowError::ZeroDeposit
+        );
+        require!(
+            receive.iter().all(|leg| leg.amount > 0),
+            EscrowError::ZeroReceive
+        );
+
+        let mints: Vec<&Pubkey> = deposit.iter().chain(receive).map(|leg| &leg.mint).collect();
+
+        for (i, mint) in mints.iter().enumerate() {
+            require!(!mints[..i].contains(mint), EscrowError::DuplicateBasketMint);
+        }
+
+        Ok(())
+    }
+}
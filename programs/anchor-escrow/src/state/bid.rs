@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Bid {
+    pub seed: u64,
+    pub bidder: Pubkey,
+    /// Mint the bidder wants.
+    pub mint_a: Pubkey,
+    /// Mint the bidder pays with, held in the vault.
+    pub mint_b: Pubkey,
+    /// Amount of mint A the bidder wants for the whole vault.
+    pub amount_a: u64,
+    /// Amount of mint B locked in the vault.
+    pub amount_b: u64,
+    /// Unix timestamp after which the bid can no longer be filled.
+    pub expiry: i64,
+    /// Protocol fee in basis points, fixed when the bid was made.
+    pub fee_bps: u16,
+    pub bump: u8,
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct Config {
+    /// Authority allowed to update the config.
+    pub admin: Pubkey,
+    /// Owner of the token accounts that collect protocol fees.
+    pub fee_recipient: Pubkey,
+    /// Fee charged on mint B proceeds of takes, in basis points.
+    /// Escrows keep the fee in force when they were made.
+    pub fee_bps: u16,
+    /// Program that must own the price feeds of oracle escrows.
+    pub oracle_program: Pubkey,
+    pub bump: u8,
+}
+
+impl Config {
+    pub const MAX_FEE_BPS: u16 = 1_000;
+
+    /// Protocol fee of `fee_bps` on `amount`, rounded down in favour of the maker.
+    pub fn fee_on(fee_bps: u16, amount: u64) -> u64 {
+        (amount as u128 * fee_bps as u128 / 10_000) as u64
+    }
+}
@@ -1,12 +1,153 @@
 use anchor_lang::prelude::*;
 
+use crate::error::EscrowError;
+
 #[account]
 #[derive(InitSpace, Debug)]
 pub struct Escrow {
     pub seed: u64,
     pub maker: Pubkey,
+    /// Mint of the deposited leg, or `Escrow::NATIVE_SOL` for lamports held by the escrow.
     pub mint_a: Pubkey,
+    /// Mint of the requested leg, or `Escrow::NATIVE_SOL` for lamports paid to the maker.
     pub mint_b: Pubkey,
+    /// Amount of mint A deposited by the maker.
+    pub deposit: u64,
+    /// Amount of mint B the maker wants for the full deposit.
     pub receive: u64,
+    /// Amount of mint A still held for takers.
+    pub remaining_a: u64,
+    /// Amount of mint B still owed to the maker.
+    pub remaining_b: u64,
+    /// Unix timestamp after which the escrow can no longer be taken.
+    pub expiry: i64,
+    /// Unix timestamp before which the maker cannot refund.
+    pub unlock_at: i64,
+    /// Lamports posted by the maker for whoever cranks the escrow once it has expired.
+    /// Held on the escrow on top of its rent and returned to the maker on take or refund.
+    pub bounty: u64,
+    /// Only this taker may fill the escrow when set.
+    pub allowed_taker: Option<Pubkey>,
+    /// Descending price schedule starting from `receive`, when set.
+    pub auction: Option<DutchAuction>,
+    /// Protocol fee in basis points, fixed when the escrow was made.
+    pub fee_bps: u16,
     pub bump: u8,
-}
\ No newline at end of file
+}
+
+/// Asking price that decays linearly from `Escrow::receive` to `floor` between `start` and `end`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct DutchAuction {
+    /// Amount of mint B asked for the full deposit once the auction has ended.
+    pub floor: u64,
+    /// Unix timestamp the price starts decaying at.
+    pub start: i64,
+    /// Unix timestamp the price reaches `floor` at.
+    pub end: i64,
+}
+
+impl DutchAuction {
+    /// Check that the auction decays from `receive` to a non-zero floor over a non-empty window
+    /// that ends by `expiry`, so the floor can be reached before the escrow expires.
+    pub fn validate(&self, receive: u64, expiry: i64) -> Result<()> {
+        require!(
+            self.floor > 0 && self.floor <= receive && self.start < self.end && self.end <= expiry,
+            EscrowError::InvalidAuction
+        );
+
+        Ok(())
+    }
+
+    /// Asking price for the full deposit at `now`, rounded up in favour of the maker.
+    pub fn price_at(&self, receive: u64, now: i64) -> u64 {
+        let elapsed = now.clamp(self.start, self.end) - self.start;
+        let decay =
+            (receive - self.floor) as u128 * elapsed as u128 / (self.end - self.start) as u128;
+
+        receive - decay as u64
+    }
+}
+
+impl Escrow {
+    /// Mint recorded for a leg settled in native lamports instead of an SPL token.
+    pub const NATIVE_SOL: Pubkey = Pubkey::new_from_array([0; 32]);
+
+    /// Validate the terms of a new escrow and return the time it can be refunded from.
+    pub fn validate_terms(
+        deposit: u64,
+        receive: u64,
+        expiry: i64,
+        lock_period: i64,
+    ) -> Result<i64> {
+        require!(deposit > 0, EscrowError::ZeroDeposit);
+        require!(receive > 0, EscrowError::ZeroReceive);
+
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(expiry > now, EscrowError::InvalidExpiry);
+
+        let unlock_at = now
+            .checked_add(lock_period)
+            .ok_or(EscrowError::InvalidLockPeriod)?;
+        require!(
+            lock_period >= 0 && unlock_at <= expiry,
+            EscrowError::InvalidLockPeriod
+        );
+
+        Ok(unlock_at)
+    }
+
+    /// Check that `taker` may fill `amount_b` of mint B at the current time.
+    pub fn validate_fill(&self, taker: &Pubkey, amount_b: u64) -> Result<()> {
+        if let Some(allowed_taker) = self.allowed_taker {
+            require_keys_eq!(*taker, allowed_taker, EscrowError::UnauthorizedTaker);
+        }
+
+        require!(
+            Clock::get()?.unix_timestamp < self.expiry,
+            EscrowError::EscrowExpired
+        );
+        require!(
+            amount_b > 0 && amount_b <= self.remaining_b,
+            EscrowError::InvalidFillAmount
+        );
+
+        Ok(())
+    }
+
+    /// Record a fill of `amount_b` and return the share of the deposit owed to the taker.
+    /// Pays out at the original deposit/receive ratio, rounding down in favour of the maker.
+    pub fn record_fill(&mut self, amount_b: u64) -> Result<u64> {
+        let amount_a = (amount_b as u128)
+            .checked_mul(self.deposit as u128)
+            .and_then(|amount| amount.checked_div(self.receive as u128))
+            .and_then(|amount| u64::try_from(amount).ok())
+            .ok_or(EscrowError::InvalidFillAmount)?;
+        require!(amount_a > 0, EscrowError::FillTooSmall);
+
+        self.remaining_a = self
+            .remaining_a
+            .checked_sub(amount_a)
+            .ok_or(EscrowError::InvalidFillAmount)?;
+        self.remaining_b -= amount_b;
+
+        Ok(amount_a)
+    }
+
+    /// Amount of mint B a fill of `amount_b` costs at `now`.
+    /// Fills are sized at the listed `receive`; an auction scales them to its current price.
+    pub fn price_of(&self, amount_b: u64, now: i64) -> Result<u64> {
+        let Some(auction) = self.auction else {
+            return Ok(amount_b);
+        };
+
+        let price = auction.price_at(self.receive, now);
+        let amount = (amount_b as u128 * price as u128).div_ceil(self.receive as u128);
+
+        Ok(u64::try_from(amount).map_err(|_| EscrowError::InvalidFillAmount)?)
+    }
+
+    pub fn is_filled(&self) -> bool {
+        self.remaining_b == 0
+    }
+}
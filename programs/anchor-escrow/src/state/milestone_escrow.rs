@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+
+/// Most tranches a single milestone escrow can hold.
+pub const MAX_MILESTONES: usize = 8;
+
+/// Tranche as requested by the maker.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MilestoneTerms {
+    pub amount: u64,
+    /// Unix timestamp after which anyone can release the tranche to the taker.
+    pub unlock_at: Option<i64>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct Milestone {
+    /// Amount of `mint` paid to the taker when the tranche is released.
+    pub amount: u64,
+    /// Unix timestamp after which anyone can release the tranche to the taker.
+    /// Without it, only the maker can release it.
+    pub unlock_at: Option<i64>,
+    pub released: bool,
+}
+
+impl Milestone {
+    /// Whether anyone can release the tranche at `now`.
+    pub fn is_unlocked(&self, now: i64) -> bool {
+        self.unlock_at.is_some_and(|unlock_at| now >= unlock_at)
+    }
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct MilestoneEscrow {
+    pub seed: u64,
+    /// Depositor, approves tranches and is refunded whatever is left unreleased.
+    pub maker: Pubkey,
+    /// Beneficiary, paid tranche by tranche.
+    pub taker: Pubkey,
+    pub mint: Pubkey,
+    #[max_len(MAX_MILESTONES)]
+    pub milestones: Vec<Milestone>,
+    pub bump: u8,
+}
+
+impl MilestoneEscrow {
+    /// Validate the requested tranches and return the total to deposit.
+    pub fn validate_milestones(milestones: &[MilestoneTerms]) -> Result<u64> {
+        require!(
+            !milestones.is_empty() && milestones.len() <= MAX_MILESTONES,
+            EscrowError::InvalidMilestoneCount
+        );
+
+        milestones.iter().try_fold(0u64, |total, terms| {
+            require!(terms.amount > 0, EscrowError::ZeroDeposit);
+            total
+                .checked_add(terms.amount)
+                .ok_or(EscrowError::DepositOverflow.into())
+        })
+    }
+
+    /// Mark tranche `index` released and return its amount.
+    /// The maker can approve any tranche; anyone else has to wait for its unlock time.
+    pub fn release(&mut self, index: u8, approved_by_maker: bool) -> Result<u64> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let milestone = self
+            .milestones
+            .get_mut(index as usize)
+            .ok_or(EscrowError::InvalidMilestone)?;
+
+        require!(!milestone.released, EscrowError::MilestoneReleased);
+        require!(
+            approved_by_maker || milestone.is_unlocked(now),
+            EscrowError::MilestoneLocked
+        );
+
+        milestone.released = true;
+
+        Ok(milestone.amount)
+    }
+
+    /// Amount of `mint` still held for tranches that have not been released.
+    pub fn unreleased(&self) -> u64 {
+        self.milestones
+            .iter()
+            .filter(|milestone| !milestone.released)
+            .map(|milestone| milestone.amount)
+            .sum()
+    }
+
+    /// Mark every unreleased tranche released ahead of a refund, and return the amount of
+    /// those that have unlocked by `now`, which are owed to the taker rather than the maker.
+    pub fn record_refund(&mut self, now: i64) -> u64 {
+        self.milestones
+            .iter_mut()
+            .filter(|milestone| !milestone.released)
+            .map(|milestone| {
+                milestone.released = true;
+                if milestone.is_unlocked(now) {
+                    milestone.amount
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    pub fn is_released(&self) -> bool {
+        self.milestones.iter().all(|milestone| milestone.released)
+    }
+}
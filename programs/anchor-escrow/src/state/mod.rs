@@ -1,3 +1,19 @@
+pub mod arbitrated_escrow;
+pub mod basket_escrow;
+pub mod bid;
+pub mod config;
 pub mod escrow;
+pub mod milestone_escrow;
+pub mod nft_escrow;
+pub mod oracle_escrow;
+pub mod vesting_escrow;
 
-pub use escrow::*;
\ No newline at end of file
+pub use arbitrated_escrow::*;
+pub use basket_escrow::*;
+pub use bid::*;
+pub use config::*;
+pub use escrow::*;
+pub use milestone_escrow::*;
+pub use nft_escrow::*;
+pub use oracle_escrow::*;
+pub use vesting_escrow::*;
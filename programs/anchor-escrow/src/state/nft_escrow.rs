@@ -0,0 +1,78 @@
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    error::EscrowError,
+    metadata::{self, MAX_CREATORS},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct RoyaltyShare {
+    pub address: Pubkey,
+    /// Percentage of the royalty paid to `address`.
+    pub share: u8,
+}
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct NftEscrow {
+    pub seed: u64,
+    pub maker: Pubkey,
+    /// The NFT on offer.
+    pub mint_a: Pubkey,
+    /// Mint of the price, fungible or another NFT.
+    pub mint_b: Pubkey,
+    /// Amount of mint B the maker wants for the NFT, royalty and protocol fee included.
+    pub receive: u64,
+    /// Royalty paid out of `receive` to the creators, in basis points.
+    pub royalty_bps: u16,
+    #[max_len(MAX_CREATORS)]
+    pub creators: Vec<RoyaltyShare>,
+    /// Protocol fee in basis points, fixed when the escrow was made.
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+impl NftEscrow {
+    pub const MAX_ROYALTY_BPS: u16 = 10_000;
+
+    /// A mint with no decimals and a single token in existence that can never mint another:
+    /// either nobody holds the mint authority or Metaplex locked it in the master edition.
+    pub fn is_nft(mint: &InterfaceAccount<Mint>) -> bool {
+        let fixed_supply = match mint.mint_authority {
+            COption::None => true,
+            COption::Some(authority) => authority == metadata::master_edition(&mint.key()),
+        };
+
+        mint.decimals == 0 && mint.supply == 1 && fixed_supply
+    }
+
+    /// Validate the price of a new NFT escrow.
+    pub fn validate_terms(
+        mint_a: &InterfaceAccount<Mint>,
+        mint_b: &InterfaceAccount<Mint>,
+        receive: u64,
+    ) -> Result<()> {
+        require!(Self::is_nft(mint_a), EscrowError::NotAnNft);
+        require!(receive > 0, EscrowError::ZeroReceive);
+
+        // An NFT price is the single token of its mint
+        if Self::is_nft(mint_b) {
+            require!(receive == 1, EscrowError::InvalidNftPrice);
+        }
+
+        Ok(())
+    }
+
+    /// Royalty owed to each creator, in creator order, on a sale at `receive`.
+    /// Rounds down, leaving the dust to the maker.
+    pub fn royalties(&self) -> Vec<u64> {
+        let royalty =
+            self.receive as u128 * self.royalty_bps as u128 / Self::MAX_ROYALTY_BPS as u128;
+
+        self.creators
+            .iter()
+            .map(|creator| (royalty * creator.share as u128 / 100) as u64)
+            .collect()
+    }
+}
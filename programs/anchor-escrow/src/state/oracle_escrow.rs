@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct OracleEscrow {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Amount of mint A deposited by the maker.
+    pub deposit: u64,
+    /// Price feed the amount of mint B owed for the deposit is derived from.
+    pub oracle: Pubkey,
+    /// Program that must own `oracle`, fixed when the escrow was made.
+    pub oracle_program: Pubkey,
+    /// Largest discount off the feed price a take may pay, in basis points.
+    pub max_slippage_bps: u16,
+    /// Oldest a feed price may be, in seconds, to be used.
+    pub max_staleness: i64,
+    /// Protocol fee in basis points, fixed when the escrow was made.
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+impl OracleEscrow {
+    pub const MAX_SLIPPAGE_BPS: u16 = 10_000;
+
+    /// Validate the terms of a new oracle-priced escrow.
+    pub fn validate_terms(deposit: u64, max_slippage_bps: u16, max_staleness: i64) -> Result<()> {
+        require!(deposit > 0, EscrowError::ZeroDeposit);
+        require!(
+            max_slippage_bps <= Self::MAX_SLIPPAGE_BPS,
+            EscrowError::InvalidSlippage
+        );
+        require!(max_staleness >= 0, EscrowError::InvalidStaleness);
+
+        Ok(())
+    }
+
+    /// Amount of mint B owed for the deposit at `price` scaled by `10^expo`,
+    /// rounded up in favour of the maker.
+    pub fn receive_at(&self, price: u64, expo: i32, decimals_a: u8, decimals_b: u8) -> Result<u64> {
+        let value = self.deposit as u128 * price as u128;
+        let scale = expo as i64 + decimals_b as i64 - decimals_a as i64;
+
+        let amount = if scale >= 0 {
+            10u128
+                .checked_pow(scale as u32)
+                .and_then(|factor| value.checked_mul(factor))
+        } else {
+            10u128
+                .checked_pow(scale.unsigned_abs() as u32)
+                .map(|factor| value.div_ceil(factor))
+        };
+
+        let amount = amount
+            .and_then(|amount| u64::try_from(amount).ok())
+            .ok_or(EscrowError::ReceiveOverflow)?;
+        require!(amount > 0, EscrowError::ZeroReceive);
+
+        Ok(amount)
+    }
+
+    /// Least a take may pay for `amount_b` owed at the feed price, after the maker's slippage.
+    pub fn min_payment(&self, amount_b: u64) -> u64 {
+        let discount =
+            amount_b as u128 * self.max_slippage_bps as u128 / Self::MAX_SLIPPAGE_BPS as u128;
+
+        amount_b - discount as u64
+    }
+}
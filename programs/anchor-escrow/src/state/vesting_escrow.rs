@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::error::EscrowError;
+
+#[account]
+#[derive(InitSpace, Debug)]
+pub struct VestingEscrow {
+    pub seed: u64,
+    /// Depositor, receives the rent back once everything has been claimed.
+    pub maker: Pubkey,
+    /// Only account allowed to claim the vested tokens.
+    pub beneficiary: Pubkey,
+    pub mint: Pubkey,
+    /// Amount of `mint` vested over the whole schedule.
+    pub amount: u64,
+    /// Amount of `mint` already paid out to the beneficiary.
+    pub claimed: u64,
+    /// Unix timestamp the linear schedule starts from.
+    pub start: i64,
+    /// Unix timestamp before which nothing can be claimed.
+    pub cliff: i64,
+    /// Unix timestamp from which the whole amount is vested.
+    pub end: i64,
+    pub bump: u8,
+}
+
+impl VestingEscrow {
+    /// Validate the terms of a new vesting schedule.
+    pub fn validate_schedule(amount: u64, start: i64, cliff: i64, end: i64) -> Result<()> {
+        require!(amount > 0, EscrowError::ZeroDeposit);
+        require!(
+            start <= cliff && cliff <= end && start < end,
+            EscrowError::InvalidVestingSchedule
+        );
+
+        Ok(())
+    }
+
+    /// Amount vested at `now`, claimed or not.
+    /// Vests linearly from `start` to `end`, but nothing is vested before `cliff`.
+    pub fn vested(&self, now: i64) -> u64 {
+        if now < self.cliff {
+            return 0;
+        }
+        if now >= self.end {
+            return self.amount;
+        }
+
+        let elapsed = now.saturating_sub(self.start) as u128;
+        let duration = self.end.saturating_sub(self.start) as u128;
+
+        // Cannot exceed `amount` since `elapsed < duration`
+        (self.amount as u128 * elapsed / duration) as u64
+    }
+
+    /// Record a claim of everything vested but not yet claimed, and return its amount.
+    pub fn record_claim(&mut self, now: i64) -> Result<u64> {
+        let claimable = self.vested(now).saturating_sub(self.claimed);
+        require!(claimable > 0, EscrowError::NothingToClaim);
+
+        self.claimed += claimable;
+
+        Ok(claimable)
+    }
+
+    pub fn is_drained(&self) -> bool {
+        self.claimed == self.amount
+    }
+}
@@ -0,0 +1,516 @@
+use super::*;
+
+// Derive the arbitrated escrow PDA and its vault for the maker's `seed`
+fn arbitrated_pdas(test_data: &TestData, seed: u64) -> (Pubkey, Pubkey) {
+    let escrow = Pubkey::find_program_address(
+        &[b"arbitrated", test_data.maker.as_ref(), &seed.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0;
+    let vault = associated_token::get_associated_token_address_with_program_id(
+        &escrow,
+        &test_data.mint_a,
+        &test_data.token_program,
+    );
+
+    (escrow, vault)
+}
+
+fn arbitrated_state(program: &LiteSVM, escrow: &Pubkey) -> crate::state::ArbitratedEscrow {
+    let account = program.get_account(escrow).unwrap();
+    crate::state::ArbitratedEscrow::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+// Build and send the "MakeArbitrated" instruction depositing `amount` of Mint A,
+// refundable to the maker from `expiry`
+#[allow(clippy::too_many_arguments)]
+fn send_make_arbitrated(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    amount: u64,
+    taker: Pubkey,
+    arbiter: Pubkey,
+    expiry: i64,
+) -> TransactionResult {
+    let (escrow, vault) = arbitrated_pdas(test_data, seed);
+
+    let mut make_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MakeArbitrated {
+            maker: test_data.maker,
+            mint: test_data.mint_a,
+            maker_ata: test_data.maker_ata_a,
+            escrow,
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MakeArbitrated {
+            seed,
+            amount,
+            taker,
+            arbiter,
+            expiry,
+        }
+        .data(),
+    };
+    make_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[make_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_raise_dispute(program: &mut LiteSVM, party: &Keypair, escrow: Pubkey) -> TransactionResult {
+    let dispute_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::RaiseDispute {
+            party: party.pubkey(),
+            escrow,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::RaiseDispute {}.data(),
+    };
+
+    let message = Message::new(&[dispute_ix], Some(&party.pubkey()));
+    let transaction = Transaction::new(&[party], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_release(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    taker: Pubkey,
+) -> TransactionResult {
+    let (escrow, vault) = arbitrated_pdas(test_data, seed);
+    let taker_ata = associated_token::get_associated_token_address_with_program_id(
+        &taker,
+        &test_data.mint_a,
+        &test_data.token_program,
+    );
+
+    let mut release_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::Release {
+            maker: test_data.maker,
+            taker,
+            mint: test_data.mint_a,
+            taker_ata,
+            escrow,
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::Release {}.data(),
+    };
+    release_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[release_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_resolve_to_taker(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    arbiter: &Keypair,
+    taker: Pubkey,
+) -> TransactionResult {
+    let (escrow, vault) = arbitrated_pdas(test_data, seed);
+    let taker_ata = associated_token::get_associated_token_address_with_program_id(
+        &taker,
+        &test_data.mint_a,
+        &test_data.token_program,
+    );
+
+    let mut resolve_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::ResolveToTaker {
+            arbiter: arbiter.pubkey(),
+            maker: test_data.maker,
+            taker,
+            mint: test_data.mint_a,
+            taker_ata,
+            escrow,
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::ResolveToTaker {}.data(),
+    };
+    resolve_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[resolve_ix], Some(&arbiter.pubkey()));
+    let transaction = Transaction::new(&[arbiter], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_resolve_to_maker(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    arbiter: &Keypair,
+) -> TransactionResult {
+    let (escrow, vault) = arbitrated_pdas(test_data, seed);
+
+    let mut resolve_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::ResolveToMaker {
+            arbiter: arbiter.pubkey(),
+            maker: test_data.maker,
+            mint: test_data.mint_a,
+            maker_ata: test_data.maker_ata_a,
+            escrow,
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::ResolveToMaker {}.data(),
+    };
+    resolve_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[resolve_ix], Some(&arbiter.pubkey()));
+    let transaction = Transaction::new(&[arbiter], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_refund_arbitrated(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+) -> TransactionResult {
+    let (escrow, vault) = arbitrated_pdas(test_data, seed);
+
+    let mut refund_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::RefundArbitrated {
+            maker: test_data.maker,
+            mint: test_data.mint_a,
+            maker_ata: test_data.maker_ata_a,
+            escrow,
+            vault,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::RefundArbitrated {}.data(),
+    };
+    refund_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[refund_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Arbitrated escrow of 100 Mint A under seed 7, with a fresh taker and arbiter,
+// refundable to the maker after a week
+fn setup_arbitrated() -> (LiteSVM, TestData, Keypair, Keypair) {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let taker = funded_keypair(&mut program);
+    let arbiter = funded_keypair(&mut program);
+
+    let expiry = now(&program) + ONE_WEEK;
+    let tx = send_make_arbitrated(
+        &mut program,
+        &test_data,
+        7,
+        100,
+        taker.pubkey(),
+        arbiter.pubkey(),
+        expiry,
+    )
+    .unwrap();
+
+    let events = decode_events::<ArbitratedEscrowCreated>(&tx);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].seed, 7);
+    assert_eq!(events[0].arbiter, arbiter.pubkey());
+    assert_eq!(events[0].amount, 100);
+    assert_eq!(events[0].expiry, expiry);
+
+    (program, test_data, taker, arbiter)
+}
+
+#[test]
+fn test_arbitrated_make() {
+    let (program, test_data, taker, arbiter) = setup_arbitrated();
+    let (escrow, vault) = arbitrated_pdas(&test_data, 7);
+
+    let escrow_data = arbitrated_state(&program, &escrow);
+    assert_eq!(escrow_data.maker, test_data.maker);
+    assert_eq!(escrow_data.taker, taker.pubkey());
+    assert_eq!(escrow_data.arbiter, arbiter.pubkey());
+    assert_eq!(escrow_data.mint, test_data.mint_a);
+    assert_eq!(escrow_data.amount, 100);
+    assert_eq!(escrow_data.expiry, now(&program) + ONE_WEEK);
+    assert_eq!(escrow_data.status, ArbitrationStatus::Active);
+
+    assert_eq!(token_balance(&program, &vault), 100);
+}
+
+#[test]
+fn test_arbitrated_release() {
+    let (mut program, test_data, taker, _arbiter) = setup_arbitrated();
+    let (escrow, vault) = arbitrated_pdas(&test_data, 7);
+
+    let tx = send_release(&mut program, &test_data, 7, taker.pubkey()).unwrap();
+
+    let taker_ata =
+        associated_token::get_associated_token_address(&taker.pubkey(), &test_data.mint_a);
+    assert_eq!(token_balance(&program, &taker_ata), 100);
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+
+    let events = decode_events::<EscrowResolved>(&tx);
+    assert_eq!(events[0].recipient, taker.pubkey());
+    assert_eq!(events[0].resolved_by, test_data.maker);
+    assert_eq!(events[0].amount, 100);
+}
+
+#[test]
+fn test_arbitrated_resolve_to_taker() {
+    let (mut program, test_data, taker, arbiter) = setup_arbitrated();
+    let (escrow, vault) = arbitrated_pdas(&test_data, 7);
+
+    let tx = send_raise_dispute(&mut program, &taker, escrow).unwrap();
+    assert_eq!(
+        arbitrated_state(&program, &escrow).status,
+        ArbitrationStatus::Disputed
+    );
+    let events = decode_events::<EscrowDisputed>(&tx);
+    assert_eq!(events[0].raised_by, taker.pubkey());
+
+    // A disputed escrow can no longer be released by the maker
+    assert_escrow_error(
+        send_release(&mut program, &test_data, 7, taker.pubkey()),
+        EscrowError::EscrowDisputed,
+    );
+
+    let maker_lamports = lamports(&program, &test_data.maker);
+    let tx = send_resolve_to_taker(&mut program, &test_data, 7, &arbiter, taker.pubkey()).unwrap();
+
+    let taker_ata =
+        associated_token::get_associated_token_address(&taker.pubkey(), &test_data.mint_a);
+    assert_eq!(token_balance(&program, &taker_ata), 100);
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+
+    // The maker paid the escrow and vault rent, so it gets it back
+    assert!(lamports(&program, &test_data.maker) > maker_lamports);
+
+    let events = decode_events::<EscrowResolved>(&tx);
+    assert_eq!(events[0].recipient, taker.pubkey());
+    assert_eq!(events[0].resolved_by, arbiter.pubkey());
+    assert_eq!(events[0].amount, 100);
+}
+
+#[test]
+fn test_arbitrated_resolve_to_maker() {
+    let (mut program, test_data, _taker, arbiter) = setup_arbitrated();
+    let (escrow, vault) = arbitrated_pdas(&test_data, 7);
+
+    let maker_balance = token_balance(&program, &test_data.maker_ata_a);
+
+    send_raise_dispute(&mut program, &test_data.payer, escrow).unwrap();
+
+    // A second dispute on the same escrow is rejected
+    program.expire_blockhash();
+    assert_escrow_error(
+        send_raise_dispute(&mut program, &test_data.payer, escrow),
+        EscrowError::EscrowDisputed,
+    );
+
+    let tx = send_resolve_to_maker(&mut program, &test_data, 7, &arbiter).unwrap();
+
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        maker_balance + 100
+    );
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+
+    let events = decode_events::<EscrowResolved>(&tx);
+    assert_eq!(events[0].recipient, test_data.maker);
+    assert_eq!(events[0].resolved_by, arbiter.pubkey());
+}
+
+#[test]
+fn test_arbitrated_resolve_without_dispute_fails() {
+    let (mut program, test_data, taker, arbiter) = setup_arbitrated();
+
+    assert_escrow_error(
+        send_resolve_to_taker(&mut program, &test_data, 7, &arbiter, taker.pubkey()),
+        EscrowError::NotDisputed,
+    );
+    assert_escrow_error(
+        send_resolve_to_maker(&mut program, &test_data, 7, &arbiter),
+        EscrowError::NotDisputed,
+    );
+}
+
+#[test]
+fn test_arbitrated_resolve_by_non_arbiter_fails() {
+    let (mut program, test_data, taker, _arbiter) = setup_arbitrated();
+    let (escrow, _vault) = arbitrated_pdas(&test_data, 7);
+
+    send_raise_dispute(&mut program, &taker, escrow).unwrap();
+
+    // Neither party nor an outsider can stand in for the arbiter
+    let outsider = funded_keypair(&mut program);
+    for impostor in [&taker, &outsider] {
+        assert_escrow_error(
+            send_resolve_to_taker(&mut program, &test_data, 7, impostor, taker.pubkey()),
+            EscrowError::InvalidArbiter,
+        );
+        assert_escrow_error(
+            send_resolve_to_maker(&mut program, &test_data, 7, impostor),
+            EscrowError::InvalidArbiter,
+        );
+    }
+}
+
+#[test]
+fn test_arbitrated_dispute_by_outsider_fails() {
+    let (mut program, test_data, _taker, arbiter) = setup_arbitrated();
+    let (escrow, _vault) = arbitrated_pdas(&test_data, 7);
+
+    // The arbiter settles disputes but cannot raise one
+    let outsider = funded_keypair(&mut program);
+    for outsider in [&outsider, &arbiter] {
+        assert_escrow_error(
+            send_raise_dispute(&mut program, outsider, escrow),
+            EscrowError::UnauthorizedDisputant,
+        );
+    }
+}
+
+#[test]
+fn test_arbitrated_release_to_wrong_taker_fails() {
+    let (mut program, test_data, _taker, _arbiter) = setup_arbitrated();
+
+    assert_escrow_error(
+        send_release(&mut program, &test_data, 7, Pubkey::new_unique()),
+        EscrowError::InvalidTaker,
+    );
+}
+
+#[test]
+fn test_arbitrated_refund_after_expiry() {
+    let (mut program, test_data, taker, _arbiter) = setup_arbitrated();
+    let (escrow, vault) = arbitrated_pdas(&test_data, 7);
+
+    let maker_balance = token_balance(&program, &test_data.maker_ata_a);
+
+    // The taker disputes and the arbiter never resolves
+    send_raise_dispute(&mut program, &taker, escrow).unwrap();
+
+    // Until the escrow expires, the funds stay with the arbiter
+    assert_escrow_error(
+        send_refund_arbitrated(&mut program, &test_data, 7),
+        EscrowError::EscrowNotExpired,
+    );
+
+    let expiry = arbitrated_state(&program, &escrow).expiry;
+    warp_to(&mut program, expiry);
+
+    let tx = send_refund_arbitrated(&mut program, &test_data, 7).unwrap();
+
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        maker_balance + 100
+    );
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+
+    let events = decode_events::<EscrowResolved>(&tx);
+    assert_eq!(events[0].recipient, test_data.maker);
+    assert_eq!(events[0].resolved_by, test_data.maker);
+    assert_eq!(events[0].amount, 100);
+}
+
+#[test]
+fn test_arbitrated_make_with_party_as_arbiter_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let taker = Pubkey::new_unique();
+    let expiry = now(&program) + ONE_WEEK;
+
+    assert_escrow_error(
+        send_make_arbitrated(
+            &mut program,
+            &test_data,
+            7,
+            100,
+            taker,
+            test_data.maker,
+            expiry,
+        ),
+        EscrowError::ArbiterIsParty,
+    );
+    assert_escrow_error(
+        send_make_arbitrated(&mut program, &test_data, 7, 100, taker, taker, expiry),
+        EscrowError::ArbiterIsParty,
+    );
+    assert_escrow_error(
+        send_make_arbitrated(
+            &mut program,
+            &test_data,
+            7,
+            0,
+            taker,
+            Pubkey::new_unique(),
+            expiry,
+        ),
+        EscrowError::ZeroDeposit,
+    );
+    assert_escrow_error(
+        send_make_arbitrated(
+            &mut program,
+            &test_data,
+            7,
+            100,
+            taker,
+            Pubkey::new_unique(),
+            expiry - ONE_WEEK,
+        ),
+        EscrowError::InvalidExpiry,
+    );
+}
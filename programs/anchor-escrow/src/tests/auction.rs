@@ -0,0 +1,165 @@
+use super::update::send_update;
+use super::*;
+
+// Build and send a "Make" instruction listing `deposit` of Mint A by Dutch auction,
+// starting at `receive` of Mint B
+fn send_make_auction(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    deposit: u64,
+    receive: u64,
+    auction: DutchAuction,
+) -> TransactionResult {
+    // The builder links its own copy of this crate, so the auction is rebuilt in its type
+    let make_ix = make_builder(program, test_data, 7, test_data.mint_b)
+        .deposit(deposit)
+        .receive(receive)
+        .auction(Some(escrow_client::DutchAuction {
+            floor: auction.floor,
+            start: auction.start,
+            end: auction.end,
+        }))
+        .instruction();
+
+    send_signed(program, &test_data.payer, make_ix)
+}
+
+// Auction of 1,000 Mint A starting at 1,000 Mint B and decaying to 400 over ten minutes from now
+fn setup_auction() -> (LiteSVM, TestData, i64) {
+    setup_auction_with_fee(0)
+}
+
+// Same as `setup_auction`, but the auction is made while the protocol charges `fee_bps`
+fn setup_auction_with_fee(fee_bps: u16) -> (LiteSVM, TestData, i64) {
+    let (mut program, test_data) = setup_with_fee(fee_bps);
+    let test_data = with_seed(&test_data, 7);
+    let start = now(&program);
+
+    let tx = send_make_auction(
+        &mut program,
+        &test_data,
+        1000,
+        1000,
+        DutchAuction {
+            floor: 400,
+            start,
+            end: start + 600,
+        },
+    )
+    .unwrap();
+
+    let events = decode_events::<EscrowCreated>(&tx);
+    assert_eq!(events[0].auction.unwrap().floor, 400);
+
+    (program, test_data, start)
+}
+
+#[test]
+fn test_auction_price_decays_linearly() {
+    let (mut program, test_data, start) = setup_auction();
+    let (taker, taker_ata_a, taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    // Take a quarter of the listing at several points along the curve,
+    // paying the current price for it, rounded up
+    let fills = [
+        (0, 250),   // price 1,000
+        (150, 213), // price 850
+        (450, 138), // price 550
+        (900, 100), // past the end, price stays at the 400 floor
+    ];
+
+    let mut paid = 0;
+    for (elapsed, expected) in fills {
+        warp_to(&mut program, start + elapsed);
+
+        let tx = send_take_partial(&mut program, &test_data, &taker, 250).unwrap();
+        paid += expected;
+
+        assert_eq!(decode_events::<EscrowTaken>(&tx)[0].amount_b, expected);
+        assert_eq!(token_balance(&program, &taker_ata_b), 1000000000 - paid);
+        assert_eq!(token_balance(&program, &test_data.maker_ata_b), paid);
+    }
+
+    assert_eq!(token_balance(&program, &taker_ata_a), 1000);
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_auction_take_with_fee() {
+    // Charge a 1% fee
+    let (mut program, test_data, start) = setup_auction_with_fee(100);
+    let (taker, taker_ata_a, taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    // Halfway through, the whole deposit costs 700
+    warp_to(&mut program, start + 300);
+    let tx = send_take(&mut program, &test_data, &taker).unwrap();
+
+    let fee_recipient_ata_b =
+        associated_token::get_associated_token_address(&test_data.fee_recipient, &test_data.mint_b);
+    assert_eq!(token_balance(&program, &taker_ata_b), 1000000000 - 700);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 693);
+    assert_eq!(token_balance(&program, &fee_recipient_ata_b), 7);
+    assert_eq!(token_balance(&program, &taker_ata_a), 1000);
+
+    let events = decode_events::<EscrowTaken>(&tx);
+    assert_eq!(events[0].amount_b, 700);
+    assert_eq!(events[0].fee, 7);
+}
+
+#[test]
+fn test_make_invalid_auction_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let test_data = with_seed(&test_data, 7);
+    let start = now(&program);
+
+    let cases = [
+        (0, start, start + 600),
+        (1001, start, start + 600),
+        (400, start, start),
+        (400, start + 600, start),
+        // Ends after the escrow expires, a week from now
+        (400, start, start + ONE_WEEK + 1),
+    ];
+
+    for (floor, start, end) in cases {
+        assert_escrow_error(
+            send_make_auction(
+                &mut program,
+                &test_data,
+                1000,
+                1000,
+                DutchAuction { floor, start, end },
+            ),
+            EscrowError::InvalidAuction,
+        );
+    }
+}
+
+#[test]
+fn test_update_expiry_before_auction_end_fails() {
+    let (mut program, test_data, start) = setup_auction();
+
+    // The auction ends ten minutes in, so the escrow cannot expire before then
+    assert_escrow_error(
+        send_update(
+            &mut program,
+            &test_data,
+            None,
+            None,
+            Some(start + 599),
+            None,
+        ),
+        EscrowError::InvalidExpiry,
+    );
+
+    send_update(
+        &mut program,
+        &test_data,
+        None,
+        None,
+        Some(start + 600),
+        None,
+    )
+    .unwrap();
+    assert_eq!(escrow_state(&program, &test_data).expiry, start + 600);
+}
@@ -0,0 +1,658 @@
+use super::*;
+
+// Baskets settle up to eight token legs in one instruction, more than the default
+// 200,000 compute units allow, so basket transactions request this limit
+const BASKET_COMPUTE_LIMIT: u32 = 400_000;
+
+// Basket transactions start with the compute budget instruction, so the basket
+// instruction is the second one
+fn assert_basket_error(result: TransactionResult, expected: EscrowError) {
+    assert_custom_error_at(result, 1, expected.into());
+}
+
+fn basket_pda(test_data: &TestData, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"basket", test_data.maker.as_ref(), &seed.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+// Create `count` mints, each with `amount` in the associated token account of `owner`
+fn basket_mints(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    owner: &Pubkey,
+    count: usize,
+    amount: u64,
+) -> Vec<Pubkey> {
+    (0..count)
+        .map(|_| {
+            let mint = CreateMint::new(program, &test_data.payer)
+                .decimals(6)
+                .authority(&test_data.maker)
+                .send()
+                .unwrap();
+            let ata = CreateAssociatedTokenAccount::new(program, &test_data.payer, &mint)
+                .owner(owner)
+                .send()
+                .unwrap();
+            mint_to(program, test_data, mint, ata, amount);
+
+            mint
+        })
+        .collect()
+}
+
+// Maker deposits 100, 200, ... of `deposit_legs` mints for 50, 100, ... of `receive_legs` mints
+// The taker holds 1,000,000 of every receive mint
+fn setup_basket(
+    deposit_legs: usize,
+    receive_legs: usize,
+) -> (LiteSVM, TestData, Keypair, Vec<BasketLeg>, Vec<BasketLeg>) {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let taker = funded_keypair(&mut program);
+
+    let maker = test_data.maker;
+    let deposit = basket_mints(&mut program, &test_data, &maker, deposit_legs, 1_000_000)
+        .into_iter()
+        .zip(1..)
+        .map(|(mint, i)| BasketLeg {
+            mint,
+            amount: 100 * i,
+        })
+        .collect();
+    let receive = basket_mints(
+        &mut program,
+        &test_data,
+        &taker.pubkey(),
+        receive_legs,
+        1_000_000,
+    )
+    .into_iter()
+    .zip(1..)
+    .map(|(mint, i)| BasketLeg {
+        mint,
+        amount: 50 * i,
+    })
+    .collect();
+
+    (program, test_data, taker, deposit, receive)
+}
+
+// Send `ix` behind a compute unit limit large enough for a full basket
+fn send_basket_ix(program: &mut LiteSVM, signer: &Keypair, ix: Instruction) -> TransactionResult {
+    let transaction = basket_transaction(program, signer, ix);
+
+    program.send_transaction(transaction)
+}
+
+fn basket_transaction(program: &LiteSVM, signer: &Keypair, ix: Instruction) -> Transaction {
+    let compute_ix = ComputeBudgetInstruction::set_compute_unit_limit(BASKET_COMPUTE_LIMIT);
+    let message = Message::new(&[compute_ix, ix], Some(&signer.pubkey()));
+
+    Transaction::new(&[signer], message, program.latest_blockhash())
+}
+
+// Same as `basket_transaction`, but as a v0 transaction that loads the accounts of `ix`
+// other than its signers from an address lookup table
+fn basket_lookup_transaction(
+    program: &mut LiteSVM,
+    signer: &Keypair,
+    ix: Instruction,
+) -> VersionedTransaction {
+    let addresses = ix
+        .accounts
+        .iter()
+        .filter(|account| !account.is_signer)
+        .map(|account| account.pubkey)
+        .collect::<Vec<_>>();
+
+    // Every address was added before the current slot, so all of them are active
+    let table = AddressLookupTable {
+        meta: LookupTableMeta::default(),
+        addresses: addresses.as_slice().into(),
+    };
+    let data = table.serialize_for_tests().unwrap();
+    let key = Pubkey::new_unique();
+    program
+        .set_account(
+            key,
+            solana_account::Account {
+                lamports: program.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: solana_sdk_ids::address_lookup_table::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+    program.warp_to_slot(program.get_sysvar::<Clock>().slot + 1);
+
+    let compute_ix = ComputeBudgetInstruction::set_compute_unit_limit(BASKET_COMPUTE_LIMIT);
+    let message = v0::Message::try_compile(
+        &signer.pubkey(),
+        &[compute_ix, ix],
+        &[AddressLookupTableAccount { key, addresses }],
+        program.latest_blockhash(),
+    )
+    .unwrap();
+
+    VersionedTransaction::try_new(VersionedMessage::V0(message), &[signer]).unwrap()
+}
+
+// Remaining accounts of "MakeBasket": `[mint, maker_ata, vault]` per deposit leg,
+// then the transfer hook accounts of every mint
+fn make_basket_remaining_accounts(
+    program: &LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    deposit: &[BasketLeg],
+) -> Vec<AccountMeta> {
+    let escrow = basket_pda(test_data, seed);
+    let mints: Vec<Pubkey> = deposit.iter().map(|leg| leg.mint).collect();
+
+    let mut accounts: Vec<AccountMeta> = deposit
+        .iter()
+        .flat_map(|leg| {
+            [
+                AccountMeta::new_readonly(leg.mint, false),
+                AccountMeta::new(basket_ata(test_data, &test_data.maker, &leg.mint), false),
+                AccountMeta::new(basket_ata(test_data, &escrow, &leg.mint), false),
+            ]
+        })
+        .collect();
+    accounts.extend(transfer_hook_accounts(program, &mints));
+
+    accounts
+}
+
+// Remaining accounts of "TakeBasket": `[mint, taker_ata, maker_ata, fee_recipient_ata]` per
+// receive leg, then `[mint, vault, taker_ata]` per deposit leg, then the transfer hook accounts
+// of every mint
+// Deposit mints are writable so that transfer fees withheld in the vaults can be harvested
+fn take_basket_remaining_accounts(
+    program: &LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    taker: &Pubkey,
+    deposit: &[BasketLeg],
+    receive: &[BasketLeg],
+) -> Vec<AccountMeta> {
+    let escrow = basket_pda(test_data, seed);
+    let mints: Vec<Pubkey> = receive.iter().chain(deposit).map(|leg| leg.mint).collect();
+
+    let mut accounts: Vec<AccountMeta> = receive
+        .iter()
+        .flat_map(|leg| {
+            [
+                AccountMeta::new_readonly(leg.mint, false),
+                AccountMeta::new(basket_ata(test_data, taker, &leg.mint), false),
+                AccountMeta::new(basket_ata(test_data, &test_data.maker, &leg.mint), false),
+                AccountMeta::new(
+                    basket_ata(test_data, &test_data.fee_recipient, &leg.mint),
+                    false,
+                ),
+            ]
+        })
+        .collect();
+    accounts.extend(deposit.iter().flat_map(|leg| {
+        [
+            AccountMeta::new(leg.mint, false),
+            AccountMeta::new(basket_ata(test_data, &escrow, &leg.mint), false),
+            AccountMeta::new(basket_ata(test_data, taker, &leg.mint), false),
+        ]
+    }));
+    accounts.extend(transfer_hook_accounts(program, &mints));
+
+    accounts
+}
+
+fn make_basket_ix(
+    program: &LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    deposit: &[BasketLeg],
+    receive: &[BasketLeg],
+    expiry: i64,
+) -> Instruction {
+    let mut make_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MakeBasket {
+            maker: test_data.maker,
+            config: config_pda(),
+            escrow: basket_pda(test_data, seed),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MakeBasket {
+            seed,
+            deposit: deposit.to_vec(),
+            receive: receive.to_vec(),
+            expiry,
+        }
+        .data(),
+    };
+    make_ix.accounts.extend(make_basket_remaining_accounts(
+        program, test_data, seed, deposit,
+    ));
+
+    make_ix
+}
+
+fn take_basket_ix(
+    test_data: &TestData,
+    seed: u64,
+    taker: &Pubkey,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut take_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::TakeBasket {
+            taker: *taker,
+            maker: test_data.maker,
+            config: config_pda(),
+            fee_recipient: test_data.fee_recipient,
+            escrow: basket_pda(test_data, seed),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::TakeBasket {}.data(),
+    };
+    take_ix.accounts.extend(remaining_accounts);
+
+    take_ix
+}
+
+fn send_make_basket(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    deposit: &[BasketLeg],
+    receive: &[BasketLeg],
+) -> TransactionResult {
+    let expiry = now(program) + ONE_WEEK;
+    let make_ix = make_basket_ix(program, test_data, seed, deposit, receive, expiry);
+
+    send_basket_ix(program, &test_data.payer, make_ix)
+}
+
+fn send_take_basket(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    taker: &Keypair,
+    deposit: &[BasketLeg],
+    receive: &[BasketLeg],
+) -> TransactionResult {
+    let remaining_accounts =
+        take_basket_remaining_accounts(program, test_data, seed, &taker.pubkey(), deposit, receive);
+    let take_ix = take_basket_ix(test_data, seed, &taker.pubkey(), remaining_accounts);
+
+    send_basket_ix(program, taker, take_ix)
+}
+
+// Remaining accounts of "RefundBasket": `[mint, vault, maker_ata]` per deposit leg,
+// then the transfer hook accounts of every mint
+fn send_refund_basket(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    deposit: &[BasketLeg],
+) -> TransactionResult {
+    let escrow = basket_pda(test_data, seed);
+    let mints: Vec<Pubkey> = deposit.iter().map(|leg| leg.mint).collect();
+
+    let mut refund_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::RefundBasket {
+            maker: test_data.maker,
+            escrow,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::RefundBasket {}.data(),
+    };
+    refund_ix.accounts.extend(deposit.iter().flat_map(|leg| {
+        [
+            AccountMeta::new(leg.mint, false),
+            AccountMeta::new(basket_ata(test_data, &escrow, &leg.mint), false),
+            AccountMeta::new(basket_ata(test_data, &test_data.maker, &leg.mint), false),
+        ]
+    }));
+    refund_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &mints));
+
+    send_basket_ix(program, &test_data.payer, refund_ix)
+}
+
+#[test]
+fn test_basket_make_and_take() {
+    // 3 tokens for 2 tokens
+    let (mut program, test_data, taker, deposit, receive) = setup_basket(3, 2);
+    let escrow = basket_pda(&test_data, 5);
+
+    let tx = send_make_basket(&mut program, &test_data, 5, &deposit, &receive).unwrap();
+
+    for leg in &deposit {
+        let vault = basket_ata(&test_data, &escrow, &leg.mint);
+        assert_eq!(token_balance(&program, &vault), leg.amount);
+    }
+    let events = decode_events::<BasketCreated>(&tx);
+    assert_eq!(events[0].deposit, deposit);
+    assert_eq!(events[0].receive, receive);
+
+    let tx = send_take_basket(&mut program, &test_data, 5, &taker, &deposit, &receive).unwrap();
+
+    // Every leg settles in the same transaction
+    for leg in &deposit {
+        let taker_ata = basket_ata(&test_data, &taker.pubkey(), &leg.mint);
+        assert_eq!(token_balance(&program, &taker_ata), leg.amount);
+        assert_closed(&program, &basket_ata(&test_data, &escrow, &leg.mint));
+    }
+    for leg in &receive {
+        let maker_ata = basket_ata(&test_data, &test_data.maker, &leg.mint);
+        let taker_ata = basket_ata(&test_data, &taker.pubkey(), &leg.mint);
+        assert_eq!(token_balance(&program, &maker_ata), leg.amount);
+        assert_eq!(token_balance(&program, &taker_ata), 1_000_000 - leg.amount);
+    }
+    assert_closed(&program, &escrow);
+
+    let events = decode_events::<BasketTaken>(&tx);
+    assert_eq!(events[0].taker, taker.pubkey());
+}
+
+#[test]
+fn test_basket_refund() {
+    let (mut program, test_data, _taker, deposit, receive) = setup_basket(2, 3);
+    let escrow = basket_pda(&test_data, 5);
+
+    send_make_basket(&mut program, &test_data, 5, &deposit, &receive).unwrap();
+    let tx = send_refund_basket(&mut program, &test_data, 5, &deposit).unwrap();
+
+    for leg in &deposit {
+        let maker_ata = basket_ata(&test_data, &test_data.maker, &leg.mint);
+        assert_eq!(token_balance(&program, &maker_ata), 1_000_000);
+        assert_closed(&program, &basket_ata(&test_data, &escrow, &leg.mint));
+    }
+    assert_closed(&program, &escrow);
+    assert_eq!(decode_events::<BasketRefunded>(&tx).len(), 1);
+}
+
+#[test]
+fn test_basket_take_account_order() {
+    let (mut program, test_data, taker, deposit, receive) = setup_basket(2, 2);
+    send_make_basket(&mut program, &test_data, 5, &deposit, &receive).unwrap();
+
+    let remaining_accounts = take_basket_remaining_accounts(
+        &program,
+        &test_data,
+        5,
+        &taker.pubkey(),
+        &deposit,
+        &receive,
+    );
+
+    // Legs must follow the order stored on the escrow
+    let mut swapped = remaining_accounts.clone();
+    swapped[..8].rotate_left(4);
+    let take_ix = take_basket_ix(&test_data, 5, &taker.pubkey(), swapped);
+    assert_basket_error(
+        send_basket_ix(&mut program, &taker, take_ix),
+        EscrowError::InvalidMint,
+    );
+
+    // Every leg needs all of its accounts
+    let mut truncated = remaining_accounts.clone();
+    truncated.pop();
+    let take_ix = take_basket_ix(&test_data, 5, &taker.pubkey(), truncated);
+    assert_basket_error(
+        send_basket_ix(&mut program, &taker, take_ix),
+        EscrowError::InvalidBasketAccounts,
+    );
+
+    // The taker cannot pay a receive leg to an account other than the maker's
+    let mut redirected = remaining_accounts.clone();
+    redirected[2] = redirected[1].clone();
+    let take_ix = take_basket_ix(&test_data, 5, &taker.pubkey(), redirected);
+    assert_basket_error(
+        send_basket_ix(&mut program, &taker, take_ix),
+        EscrowError::InvalidBasketAccounts,
+    );
+
+    let take_ix = take_basket_ix(&test_data, 5, &taker.pubkey(), remaining_accounts);
+    send_basket_ix(&mut program, &taker, take_ix).unwrap();
+}
+
+#[test]
+fn test_basket_take_with_fee() {
+    let (mut program, test_data, taker, deposit, receive) = setup_basket(2, 2);
+
+    // The basket is made while the protocol charges a 2.5% fee, on every receive leg
+    send_update_config(
+        &mut program,
+        &test_data.payer,
+        250,
+        test_data.fee_recipient,
+        test_data.maker,
+    )
+    .unwrap();
+    send_make_basket(&mut program, &test_data, 5, &deposit, &receive).unwrap();
+
+    let tx = send_take_basket(&mut program, &test_data, 5, &taker, &deposit, &receive).unwrap();
+
+    // Receive legs of 50 and 100 pay fees of 1 and 2
+    let fees = receive
+        .iter()
+        .map(|leg| BasketLeg {
+            mint: leg.mint,
+            amount: leg.amount / 40,
+        })
+        .collect::<Vec<_>>();
+    for (leg, fee) in receive.iter().zip(&fees) {
+        let maker_ata = basket_ata(&test_data, &test_data.maker, &leg.mint);
+        let fee_recipient_ata = basket_ata(&test_data, &test_data.fee_recipient, &leg.mint);
+        let taker_ata = basket_ata(&test_data, &taker.pubkey(), &leg.mint);
+        assert_eq!(token_balance(&program, &maker_ata), leg.amount - fee.amount);
+        assert_eq!(token_balance(&program, &fee_recipient_ata), fee.amount);
+        assert_eq!(token_balance(&program, &taker_ata), 1_000_000 - leg.amount);
+    }
+
+    assert_eq!(decode_events::<BasketTaken>(&tx)[0].fees, fees);
+}
+
+#[test]
+fn test_basket_expiry() {
+    let (mut program, test_data, taker, deposit, receive) = setup_basket(2, 2);
+
+    // A basket must expire in the future
+    let make_ix = make_basket_ix(&program, &test_data, 5, &deposit, &receive, now(&program));
+    assert_basket_error(
+        send_basket_ix(&mut program, &test_data.payer, make_ix),
+        EscrowError::InvalidExpiry,
+    );
+
+    let tx = send_make_basket(&mut program, &test_data, 5, &deposit, &receive).unwrap();
+    let expiry = decode_events::<BasketCreated>(&tx)[0].expiry;
+
+    warp_to(&mut program, expiry);
+    assert_basket_error(
+        send_take_basket(&mut program, &test_data, 5, &taker, &deposit, &receive),
+        EscrowError::EscrowExpired,
+    );
+
+    // The maker can still refund an expired basket
+    send_refund_basket(&mut program, &test_data, 5, &deposit).unwrap();
+    assert_closed(&program, &basket_pda(&test_data, 5));
+}
+
+#[test]
+fn test_basket_take_unsupported_receive_mint_fails() {
+    let (mut program, test_data) = setup_token_2022(&[], &[]);
+    let taker = funded_keypair(&mut program);
+
+    // Receive mints are only passed on take, which rejects them like "Take" does
+    let non_transferable = create_mint_2022(
+        &mut program,
+        &test_data.payer,
+        &[MintExtension::NonTransferable],
+    );
+    let deposit = [BasketLeg {
+        mint: test_data.mint_a,
+        amount: 100,
+    }];
+    let receive = [BasketLeg {
+        mint: non_transferable,
+        amount: 50,
+    }];
+
+    send_make_basket(&mut program, &test_data, 5, &deposit, &receive).unwrap();
+    assert_basket_error(
+        send_take_basket(&mut program, &test_data, 5, &taker, &deposit, &receive),
+        EscrowError::UnsupportedMintExtension,
+    );
+}
+
+#[test]
+fn test_basket_take_insufficient_balance_is_atomic() {
+    let (mut program, test_data, taker, deposit, mut receive) = setup_basket(2, 2);
+
+    // The taker can cover the first receive leg but not the second
+    receive[1].amount = 2_000_000;
+    send_make_basket(&mut program, &test_data, 5, &deposit, &receive).unwrap();
+
+    assert_basket_error(
+        send_take_basket(&mut program, &test_data, 5, &taker, &deposit, &receive),
+        EscrowError::InsufficientTakerBalance,
+    );
+
+    // Nothing moved, not even the first leg
+    let maker_ata = basket_ata(&test_data, &test_data.maker, &receive[0].mint);
+    assert!(program.get_account(&maker_ata).is_none());
+    for leg in &deposit {
+        let vault = basket_ata(&test_data, &basket_pda(&test_data, 5), &leg.mint);
+        assert_eq!(token_balance(&program, &vault), leg.amount);
+    }
+}
+
+#[test]
+fn test_basket_invalid_legs_fail() {
+    let (mut program, test_data, _taker, deposit, receive) = setup_basket(5, 1);
+
+    // (deposit, receive, expected error)
+    let duplicate = [deposit[0], deposit[0]];
+    let zero = [BasketLeg {
+        amount: 0,
+        ..deposit[0]
+    }];
+    let cases = [
+        (&deposit[..0], &receive[..], EscrowError::InvalidBasketSize),
+        (&deposit[..], &receive[..], EscrowError::InvalidBasketSize),
+        (&deposit[..1], &receive[..0], EscrowError::InvalidBasketSize),
+        (
+            &duplicate[..],
+            &receive[..],
+            EscrowError::DuplicateBasketMint,
+        ),
+        (
+            &deposit[..1],
+            &deposit[..1],
+            EscrowError::DuplicateBasketMint,
+        ),
+        (&zero[..], &receive[..], EscrowError::ZeroDeposit),
+    ];
+
+    for (deposit, receive, expected) in cases {
+        assert_basket_error(
+            send_make_basket(&mut program, &test_data, 5, deposit, receive),
+            expected,
+        );
+    }
+}
+
+#[test]
+fn test_basket_mixed_token_programs_fail() {
+    let (mut program, test_data, taker, deposit, receive) = setup_basket(2, 2);
+    let mint_2022 = create_mint_2022(&mut program, &test_data.payer, &[]);
+    let leg_2022 = BasketLeg {
+        mint: mint_2022,
+        amount: 100,
+    };
+
+    // The basket settles through SPL Token, so a Token-2022 deposit leg is rejected up front
+    assert_basket_error(
+        send_make_basket(
+            &mut program,
+            &test_data,
+            5,
+            &[deposit[0], leg_2022],
+            &receive,
+        ),
+        EscrowError::MixedTokenPrograms,
+    );
+
+    // A Token-2022 receive leg is only seen on take, which rejects it the same way
+    let receive = [receive[0], leg_2022];
+    send_make_basket(&mut program, &test_data, 5, &deposit, &receive).unwrap();
+    assert_basket_error(
+        send_take_basket(&mut program, &test_data, 5, &taker, &deposit, &receive),
+        EscrowError::MixedTokenPrograms,
+    );
+
+    // The maker gets the deposit back
+    send_refund_basket(&mut program, &test_data, 5, &deposit).unwrap();
+    assert_closed(&program, &basket_pda(&test_data, 5));
+}
+
+#[test]
+fn test_basket_max_legs_fit_compute_and_size_limits() {
+    let (mut program, test_data, taker, deposit, receive) = setup_basket(4, 4);
+
+    let expiry = now(&program) + ONE_WEEK;
+    let make_ix = make_basket_ix(&program, &test_data, 5, &deposit, &receive, expiry);
+    let transaction = basket_transaction(&program, &test_data.payer, make_ix);
+    assert!(bincode::serialize(&transaction).unwrap().len() <= PACKET_DATA_SIZE);
+
+    let tx = program.send_transaction(transaction).unwrap();
+    msg!(
+        "4+4 basket make: {} compute units",
+        tx.compute_units_consumed
+    );
+    assert!(tx.compute_units_consumed <= BASKET_COMPUTE_LIMIT as u64);
+
+    let remaining_accounts = take_basket_remaining_accounts(
+        &program,
+        &test_data,
+        5,
+        &taker.pubkey(),
+        &deposit,
+        &receive,
+    );
+    // A 4+4 take needs more accounts than a legacy transaction holds, so it loads them from a
+    // lookup table
+    let take_ix = take_basket_ix(&test_data, 5, &taker.pubkey(), remaining_accounts);
+    let transaction = basket_lookup_transaction(&mut program, &taker, take_ix);
+    assert!(bincode::serialize(&transaction).unwrap().len() <= PACKET_DATA_SIZE);
+
+    let tx = program.send_transaction(transaction).unwrap();
+    msg!(
+        "4+4 basket take: {} compute units",
+        tx.compute_units_consumed
+    );
+    assert!(tx.compute_units_consumed <= BASKET_COMPUTE_LIMIT as u64);
+
+    assert_closed(&program, &basket_pda(&test_data, 5));
+}
@@ -0,0 +1,326 @@
+use super::*;
+
+fn bid_pda(bidder: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"bid", bidder.as_ref(), &seed.to_le_bytes()], &PROGRAM_ID).0
+}
+
+// Build and send the "MakeBid" instruction locking `amount_b` of Mint B for `amount_a` of Mint A
+fn send_make_bid(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    bidder: &Keypair,
+    seed: u64,
+    amount_b: u64,
+    amount_a: u64,
+    expires_in: i64,
+) -> TransactionResult {
+    let bid = bid_pda(&bidder.pubkey(), seed);
+
+    let make_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MakeBid {
+            bidder: bidder.pubkey(),
+            mint_a: test_data.mint_a,
+            mint_b: test_data.mint_b,
+            bidder_ata_b: basket_ata(test_data, &bidder.pubkey(), &test_data.mint_b),
+            config: config_pda(),
+            bid,
+            vault: basket_ata(test_data, &bid, &test_data.mint_b),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MakeBid {
+            seed,
+            amount_b,
+            amount_a,
+            expiry: now(program) + expires_in,
+        }
+        .data(),
+    };
+
+    let message = Message::new(&[make_ix], Some(&bidder.pubkey()));
+    let transaction = Transaction::new(&[bidder], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "FillBid" instruction, paying from the filler's Mint A account
+fn send_fill_bid(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    bidder: &Pubkey,
+    seed: u64,
+    filler: &Keypair,
+) -> TransactionResult {
+    let bid = bid_pda(bidder, seed);
+
+    let fill_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::FillBid {
+            filler: filler.pubkey(),
+            bidder: *bidder,
+            mint_a: test_data.mint_a,
+            mint_b: test_data.mint_b,
+            filler_ata_a: basket_ata(test_data, &filler.pubkey(), &test_data.mint_a),
+            filler_ata_b: basket_ata(test_data, &filler.pubkey(), &test_data.mint_b),
+            bidder_ata_a: basket_ata(test_data, bidder, &test_data.mint_a),
+            config: config_pda(),
+            fee_recipient: test_data.fee_recipient,
+            fee_recipient_ata_a: basket_ata(test_data, &test_data.fee_recipient, &test_data.mint_a),
+            bid,
+            vault: basket_ata(test_data, &bid, &test_data.mint_b),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::FillBid {}.data(),
+    };
+
+    let message = Message::new(&[fill_ix], Some(&filler.pubkey()));
+    let transaction = Transaction::new(&[filler], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "CancelBid" instruction for the bid at `bidder`/`seed`
+fn send_cancel_bid(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    bidder: &Pubkey,
+    seed: u64,
+    signer: &Keypair,
+) -> TransactionResult {
+    let bid = bid_pda(bidder, seed);
+
+    let cancel_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::CancelBid {
+            bidder: signer.pubkey(),
+            mint_b: test_data.mint_b,
+            bidder_ata_b: basket_ata(test_data, &signer.pubkey(), &test_data.mint_b),
+            bid,
+            vault: basket_ata(test_data, &bid, &test_data.mint_b),
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::CancelBid {}.data(),
+    };
+
+    let message = Message::new(&[cancel_ix], Some(&signer.pubkey()));
+    let transaction = Transaction::new(&[signer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// The taker from `setup_taker` holds Mint B, so it bids 2,000 B for 500 A from the maker
+fn setup_bid() -> (LiteSVM, TestData, Keypair) {
+    setup_bid_with_fee(0)
+}
+
+// Same as `setup_bid`, but the bid is made while the protocol charges `fee_bps`
+fn setup_bid_with_fee(fee_bps: u16) -> (LiteSVM, TestData, Keypair) {
+    let (mut program, test_data) = setup_with_fee(fee_bps);
+    let (bidder, _, _) = setup_taker(&mut program, &test_data);
+
+    send_make_bid(&mut program, &test_data, &bidder, 9, 2_000, 500, ONE_WEEK).unwrap();
+
+    (program, test_data, bidder)
+}
+
+#[test]
+fn test_make_bid() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (bidder, _, bidder_ata_b) = setup_taker(&mut program, &test_data);
+
+    let tx = send_make_bid(&mut program, &test_data, &bidder, 9, 2_000, 500, ONE_WEEK).unwrap();
+
+    let bid = bid_pda(&bidder.pubkey(), 9);
+    assert_eq!(
+        token_balance(&program, &basket_ata(&test_data, &bid, &test_data.mint_b)),
+        2_000
+    );
+    assert_eq!(token_balance(&program, &bidder_ata_b), 1000000000 - 2_000);
+
+    let events = decode_events::<BidCreated>(&tx);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].bidder, bidder.pubkey());
+    assert_eq!(events[0].mint_a, test_data.mint_a);
+    assert_eq!(events[0].mint_b, test_data.mint_b);
+    assert_eq!(events[0].amount_a, 500);
+    assert_eq!(events[0].amount_b, 2_000);
+}
+
+#[test]
+fn test_make_bid_invalid_terms_fail() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (bidder, _, _) = setup_taker(&mut program, &test_data);
+
+    let tx = send_make_bid(&mut program, &test_data, &bidder, 9, 0, 500, ONE_WEEK);
+    assert_escrow_error(tx, EscrowError::ZeroDeposit);
+
+    let tx = send_make_bid(&mut program, &test_data, &bidder, 9, 2_000, 0, ONE_WEEK);
+    assert_escrow_error(tx, EscrowError::ZeroReceive);
+
+    let tx = send_make_bid(
+        &mut program,
+        &test_data,
+        &bidder,
+        9,
+        2_000_000_000,
+        500,
+        ONE_WEEK,
+    );
+    assert_escrow_error(tx, EscrowError::InsufficientMakerBalance);
+}
+
+#[test]
+fn test_fill_bid() {
+    // Charge a 1% fee, taken from the bidder's mint A proceeds as an ask takes it from the maker's
+    let (mut program, test_data, bidder) = setup_bid_with_fee(100);
+
+    let tx = send_fill_bid(
+        &mut program,
+        &test_data,
+        &bidder.pubkey(),
+        9,
+        &test_data.payer,
+    )
+    .unwrap();
+
+    let bid = bid_pda(&bidder.pubkey(), 9);
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &bidder.pubkey(), &test_data.mint_a)
+        ),
+        495
+    );
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &test_data.fee_recipient, &test_data.mint_a)
+        ),
+        5
+    );
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1000000000 - 10 - 500
+    );
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 2_000);
+    assert_closed(&program, &bid);
+    assert_closed(&program, &basket_ata(&test_data, &bid, &test_data.mint_b));
+
+    let events = decode_events::<BidFilled>(&tx);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].filler, test_data.maker);
+    assert_eq!(events[0].amount_a, 500);
+    assert_eq!(events[0].amount_b, 2_000);
+    assert_eq!(events[0].fee, 5);
+}
+
+#[test]
+fn test_fill_bid_after_expiry_fails() {
+    let (mut program, test_data, bidder) = setup_bid();
+
+    let expiry = now(&program) + ONE_WEEK;
+    warp_to(&mut program, expiry);
+
+    let tx = send_fill_bid(
+        &mut program,
+        &test_data,
+        &bidder.pubkey(),
+        9,
+        &test_data.payer,
+    );
+    assert_escrow_error(tx, EscrowError::EscrowExpired);
+}
+
+#[test]
+fn test_fill_bid_insufficient_balance_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (bidder, _, _) = setup_taker(&mut program, &test_data);
+
+    send_make_bid(
+        &mut program,
+        &test_data,
+        &bidder,
+        9,
+        2_000,
+        2_000_000_000,
+        ONE_WEEK,
+    )
+    .unwrap();
+
+    let tx = send_fill_bid(
+        &mut program,
+        &test_data,
+        &bidder.pubkey(),
+        9,
+        &test_data.payer,
+    );
+    assert_escrow_error(tx, EscrowError::InsufficientTakerBalance);
+}
+
+#[test]
+fn test_cancel_bid() {
+    let (mut program, test_data, bidder) = setup_bid();
+
+    let tx = send_cancel_bid(&mut program, &test_data, &bidder.pubkey(), 9, &bidder).unwrap();
+
+    let bid = bid_pda(&bidder.pubkey(), 9);
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &bidder.pubkey(), &test_data.mint_b)
+        ),
+        1000000000
+    );
+    assert_closed(&program, &bid);
+    assert_closed(&program, &basket_ata(&test_data, &bid, &test_data.mint_b));
+
+    let events = decode_events::<BidCancelled>(&tx);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].amount_b, 2_000);
+
+    // A cancelled bid can no longer be filled
+    let tx = send_fill_bid(
+        &mut program,
+        &test_data,
+        &bidder.pubkey(),
+        9,
+        &test_data.payer,
+    );
+    assert_anchor_error(tx, ErrorCode::AccountNotInitialized);
+}
+
+#[test]
+fn test_cancel_bid_by_non_bidder_fails() {
+    let (mut program, test_data, bidder) = setup_bid();
+
+    // The maker signs as bidder, so the bid PDA seeded by the real bidder does not match
+    let tx = send_cancel_bid(
+        &mut program,
+        &test_data,
+        &bidder.pubkey(),
+        9,
+        &test_data.payer,
+    );
+    assert!(tx.is_err());
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &bid_pda(&bidder.pubkey(), 9), &test_data.mint_b)
+        ),
+        2_000
+    );
+}
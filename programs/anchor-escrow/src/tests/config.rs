@@ -0,0 +1,266 @@
+use super::*;
+
+#[test]
+fn test_initialize_config() {
+    let (program, test_data, _tx) = setup_with_make();
+
+    let config = config_state(&program);
+    assert_eq!(config.admin, test_data.maker);
+    assert_eq!(config.fee_recipient, test_data.fee_recipient);
+    assert_eq!(config.fee_bps, 0);
+    assert_eq!(config.oracle_program, ORACLE_PROGRAM_ID);
+
+    // The config can only be initialized once
+    let (mut program, payer) = setup();
+    send_initialize_config(&mut program, &payer, 100, Pubkey::new_unique()).unwrap();
+    program.expire_blockhash();
+    assert!(send_initialize_config(&mut program, &payer, 100, Pubkey::new_unique()).is_err());
+
+    // Fees above 10% are rejected
+    let (mut program, payer) = setup();
+    assert_escrow_error(
+        send_initialize_config(&mut program, &payer, 1_001, Pubkey::new_unique()),
+        EscrowError::InvalidFee,
+    );
+}
+
+#[test]
+fn test_initialize_config_non_upgrade_authority_fails() {
+    let (mut program, _payer) = setup();
+
+    // Someone racing the deployer to the config cannot claim it
+    let outsider = Keypair::new();
+    program
+        .airdrop(&outsider.pubkey(), LAMPORTS_PER_SOL)
+        .expect("Failed to airdrop SOL to outsider");
+    assert_escrow_error(
+        send_initialize_config(&mut program, &outsider, 0, outsider.pubkey()),
+        EscrowError::InvalidAdmin,
+    );
+    assert!(program.get_account(&config_pda()).is_none());
+
+    // Nor once the program is immutable
+    program.expire_blockhash();
+    let data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: None,
+    })
+    .unwrap();
+    let mut program_data = program.get_account(&program_data_pda()).unwrap();
+    program_data.data = data;
+    program
+        .set_account(program_data_pda(), program_data)
+        .unwrap();
+    assert_escrow_error(
+        send_initialize_config(&mut program, &outsider, 0, outsider.pubkey()),
+        EscrowError::InvalidAdmin,
+    );
+}
+
+#[test]
+fn test_take_with_fee() {
+    // The escrow is made while the protocol charges a 2.5% fee
+    let scenario = EscrowScenario::new()
+        .deposit(1000)
+        .receive(400)
+        .fee_bps(250)
+        .build();
+    let test_data = scenario.escrow(0);
+    let mut program = scenario.program;
+
+    let (taker, taker_ata_a, taker_ata_b) = setup_taker(&mut program, &test_data);
+    let tx = send_take(&mut program, &test_data, &taker).unwrap();
+
+    let fee_recipient_ata_b =
+        associated_token::get_associated_token_address(&test_data.fee_recipient, &test_data.mint_b);
+
+    // The taker pays 400 in total, split 390 / 10 between maker and fee recipient
+    assert_eq!(token_balance(&program, &taker_ata_b), 1000000000 - 400);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 390);
+    assert_eq!(token_balance(&program, &fee_recipient_ata_b), 10);
+    assert_eq!(token_balance(&program, &taker_ata_a), 1000);
+
+    let events = decode_events::<EscrowTaken>(&tx);
+    assert_eq!(events[0].amount_b, 400);
+    assert_eq!(events[0].fee, 10);
+}
+
+#[test]
+fn test_take_fee_rounds_down() {
+    // A 3.33% fee does not divide evenly into the fills below
+    let scenario = EscrowScenario::new()
+        .deposit(1000)
+        .receive(400)
+        .fee_bps(333)
+        .build();
+    let test_data = scenario.escrow(0);
+    let mut program = scenario.program;
+
+    let fee_recipient_ata_b =
+        associated_token::get_associated_token_address(&test_data.fee_recipient, &test_data.mint_b);
+
+    // 3.33% of 100 is 3.33, rounded down to 3
+    let (taker, _, taker_ata_b) = setup_taker(&mut program, &test_data);
+    send_take_partial(&mut program, &test_data, &taker, 100).unwrap();
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 97);
+    assert_eq!(token_balance(&program, &fee_recipient_ata_b), 3);
+
+    // 3.33% of 29 is 0.96, rounded down to no fee at all
+    send_take_partial(&mut program, &test_data, &taker, 29).unwrap();
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 97 + 29);
+    assert_eq!(token_balance(&program, &fee_recipient_ata_b), 3);
+
+    // The taker always pays exactly the fill amount
+    assert_eq!(token_balance(&program, &taker_ata_b), 1000000000 - 129);
+}
+
+#[test]
+fn test_take_keeps_fee_at_make() {
+    let scenario = EscrowScenario::new()
+        .deposit(1000)
+        .receive(400)
+        .fee_bps(100)
+        .build();
+    let test_data = scenario.escrow(0);
+    let mut program = scenario.program;
+    assert_eq!(escrow_state(&program, &test_data).fee_bps, 100);
+
+    // Raising the fee afterwards does not reprice the open escrow
+    send_update_config(
+        &mut program,
+        &test_data.payer,
+        1_000,
+        test_data.fee_recipient,
+        test_data.maker,
+    )
+    .unwrap();
+
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+    let tx = send_take(&mut program, &test_data, &taker).unwrap();
+
+    let fee_recipient_ata_b =
+        associated_token::get_associated_token_address(&test_data.fee_recipient, &test_data.mint_b);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 396);
+    assert_eq!(token_balance(&program, &fee_recipient_ata_b), 4);
+    assert_eq!(decode_events::<EscrowTaken>(&tx)[0].fee, 4);
+
+    // Escrows made from now on carry the new fee
+    let next = with_seed(&test_data, 7);
+    send_make(&mut program, &next, 7, next.mint_b, 1000, 400, None).unwrap();
+    assert_eq!(escrow_state(&program, &next).fee_bps, 1_000);
+}
+
+#[test]
+fn test_take_zero_fee() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    assert_eq!(config_state(&program).fee_bps, 0);
+
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+    send_take(&mut program, &test_data, &taker).unwrap();
+
+    // The maker receives everything and the fee account stays empty
+    let fee_recipient_ata_b =
+        associated_token::get_associated_token_address(&test_data.fee_recipient, &test_data.mint_b);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 10);
+    assert_eq!(token_balance(&program, &fee_recipient_ata_b), 0);
+}
+
+#[test]
+fn test_take_wrong_fee_recipient_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+
+    // The taker tries to route the fee to itself
+    let wrong_recipient = TestData {
+        payer: test_data.payer.insecure_clone(),
+        fee_recipient: taker.pubkey(),
+        ..test_data
+    };
+
+    assert_escrow_error(
+        send_take(&mut program, &wrong_recipient, &taker),
+        EscrowError::InvalidFeeRecipient,
+    );
+}
+
+#[test]
+fn test_update_config() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    let new_admin = Keypair::new();
+    program
+        .airdrop(&new_admin.pubkey(), LAMPORTS_PER_SOL)
+        .expect("Failed to airdrop SOL to new admin");
+    let new_fee_recipient = Pubkey::new_unique();
+
+    // The admin can change the fee and hand over control
+    send_update_config(
+        &mut program,
+        &test_data.payer,
+        500,
+        new_fee_recipient,
+        new_admin.pubkey(),
+    )
+    .unwrap();
+
+    let config = config_state(&program);
+    assert_eq!(config.fee_bps, 500);
+    assert_eq!(config.fee_recipient, new_fee_recipient);
+    assert_eq!(config.admin, new_admin.pubkey());
+
+    // The previous admin no longer can
+    assert_escrow_error(
+        send_update_config(
+            &mut program,
+            &test_data.payer,
+            0,
+            test_data.fee_recipient,
+            test_data.maker,
+        ),
+        EscrowError::InvalidAdmin,
+    );
+
+    // Fees above 10% are rejected
+    assert_escrow_error(
+        send_update_config(
+            &mut program,
+            &new_admin,
+            1_001,
+            new_fee_recipient,
+            new_admin.pubkey(),
+        ),
+        EscrowError::InvalidFee,
+    );
+
+    // 10% is the upper bound
+    send_update_config(
+        &mut program,
+        &new_admin,
+        1_000,
+        new_fee_recipient,
+        new_admin.pubkey(),
+    )
+    .unwrap();
+    assert_eq!(config_state(&program).fee_bps, 1_000);
+}
+
+#[test]
+fn test_update_config_non_admin_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (outsider, _, _) = setup_taker(&mut program, &test_data);
+
+    assert_escrow_error(
+        send_update_config(
+            &mut program,
+            &outsider,
+            1_000,
+            outsider.pubkey(),
+            outsider.pubkey(),
+        ),
+        EscrowError::InvalidAdmin,
+    );
+
+    let config = config_state(&program);
+    assert_eq!(config.admin, test_data.maker);
+    assert_eq!(config.fee_bps, 0);
+}
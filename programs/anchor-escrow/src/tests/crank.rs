@@ -0,0 +1,240 @@
+use super::native::send_make_native;
+use super::*;
+
+// Default LiteSVM fee for each transaction signature
+const SIGNATURE_FEE: u64 = 5_000;
+
+// Build and send the "CrankExpired" instruction for the escrow in `test_data`, signed by `cranker`
+fn send_crank_expired(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    cranker: &Keypair,
+) -> TransactionResult {
+    let mut crank_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::CrankExpired {
+            cranker: cranker.pubkey(),
+            maker: test_data.maker,
+            mint_a: test_data.mint_a,
+            maker_ata_a: test_data.maker_ata_a,
+            escrow: test_data.escrow,
+            vault: test_data.vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::CrankExpired {}.data(),
+    };
+    crank_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[crank_ix], Some(&cranker.pubkey()));
+    let transaction = Transaction::new(&[cranker], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "CrankExpiredNative" instruction for the native SOL escrow in `test_data`
+fn send_crank_expired_native(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    cranker: &Keypair,
+) -> TransactionResult {
+    let crank_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::CrankExpiredNative {
+            cranker: cranker.pubkey(),
+            maker: test_data.maker,
+            escrow: test_data.escrow,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::CrankExpiredNative {}.data(),
+    };
+
+    let message = Message::new(&[crank_ix], Some(&cranker.pubkey()));
+    let transaction = Transaction::new(&[cranker], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+#[test]
+fn test_crank_expired_by_third_party() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let cranker = funded_keypair(&mut program);
+
+    assert_escrow_error(
+        send_crank_expired(&mut program, &test_data, &cranker),
+        EscrowError::EscrowNotExpired,
+    );
+
+    let later = now(&program) + ONE_WEEK;
+    warp_to(&mut program, later);
+
+    let escrow_lamports = lamports(&program, &test_data.escrow);
+    let vault_lamports = lamports(&program, &test_data.vault);
+    let maker_lamports = lamports(&program, &test_data.maker);
+
+    let tx = send_crank_expired(&mut program, &test_data, &cranker).unwrap();
+
+    // Tokens and rent go back to the maker, who did not sign
+    assert_eq!(token_balance(&program, &test_data.maker_ata_a), 1000000000);
+    assert_eq!(
+        lamports(&program, &test_data.maker),
+        maker_lamports + escrow_lamports + vault_lamports
+    );
+    assert_closed(&program, &test_data.escrow);
+    assert_closed(&program, &test_data.vault);
+
+    let events = decode_events::<EscrowCranked>(&tx);
+    assert_eq!(events[0].cranker, cranker.pubkey());
+    assert_eq!(events[0].amount_a, 10);
+    assert_eq!(events[0].bounty, 0);
+}
+
+#[test]
+fn test_crank_expired_creates_maker_ata() {
+    // The maker deposits their whole balance, then closes their token account
+    let (mut program, test_data, tx) = setup_with_make_args(1000000000, 10, ONE_WEEK, 0);
+    tx.unwrap();
+
+    let close_ix = spl_token::instruction::close_account(
+        &test_data.token_program,
+        &test_data.maker_ata_a,
+        &test_data.maker,
+        &test_data.maker,
+        &[],
+    )
+    .unwrap();
+    let message = Message::new(&[close_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+    program.send_transaction(transaction).unwrap();
+    assert_closed(&program, &test_data.maker_ata_a);
+
+    let later = now(&program) + ONE_WEEK;
+    warp_to(&mut program, later);
+
+    let cranker = funded_keypair(&mut program);
+    send_crank_expired(&mut program, &test_data, &cranker).unwrap();
+
+    assert_eq!(token_balance(&program, &test_data.maker_ata_a), 1000000000);
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_crank_expired_pays_bounty() {
+    let scenario = EscrowScenario::new().bounty(100_000).build();
+    let test_data = scenario.escrow(0);
+    let mut program = scenario.program;
+
+    let later = now(&program) + ONE_WEEK;
+    warp_to(&mut program, later);
+
+    let escrow_lamports = lamports(&program, &test_data.escrow);
+    let vault_lamports = lamports(&program, &test_data.vault);
+    let maker_lamports = lamports(&program, &test_data.maker);
+    let cranker = funded_keypair(&mut program);
+    let cranker_lamports = lamports(&program, &cranker.pubkey());
+
+    let tx = send_crank_expired(&mut program, &test_data, &cranker).unwrap();
+
+    // The cranker nets the bounty minus the transaction fee, the maker gets the rent back
+    assert_eq!(
+        lamports(&program, &cranker.pubkey()),
+        cranker_lamports + 100_000 - SIGNATURE_FEE
+    );
+    assert_eq!(
+        lamports(&program, &test_data.maker),
+        maker_lamports + escrow_lamports - 100_000 + vault_lamports
+    );
+    assert_eq!(decode_events::<EscrowCranked>(&tx)[0].bounty, 100_000);
+}
+
+#[test]
+fn test_bounty_returned_to_maker_on_take_and_refund() {
+    // Maker 0 refunds its escrow and maker 1 has its escrow taken, both before expiry
+    let mut scenario = EscrowScenario::new()
+        .makers(2)
+        .takers(1)
+        .bounty(100_000)
+        .build();
+    let refunded = scenario.escrow(0);
+    let taken = scenario.escrow(1);
+
+    let escrow_lamports = lamports(&scenario.program, &refunded.escrow);
+    let vault_lamports = lamports(&scenario.program, &refunded.vault);
+    assert_eq!(escrow_lamports, escrow_rent(&scenario.program) + 100_000);
+
+    let maker_lamports = lamports(&scenario.program, &refunded.maker);
+    send_refund(&mut scenario.program, &refunded).unwrap();
+    assert_eq!(
+        lamports(&scenario.program, &refunded.maker),
+        maker_lamports + escrow_lamports + vault_lamports - SIGNATURE_FEE
+    );
+
+    let maker_lamports = lamports(&scenario.program, &taken.maker);
+    scenario.send_take(0, 1).unwrap();
+    assert_eq!(
+        lamports(&scenario.program, &taken.maker),
+        maker_lamports + escrow_lamports + vault_lamports
+    );
+}
+
+#[test]
+fn test_crank_expired_native() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    send_make_native(
+        &mut program,
+        &test_data,
+        456,
+        LAMPORTS_PER_SOL,
+        500,
+        100_000,
+    )
+    .unwrap();
+    let native = with_seed(&test_data, 456);
+    let cranker = funded_keypair(&mut program);
+
+    assert_escrow_error(
+        send_crank_expired_native(&mut program, &native, &cranker),
+        EscrowError::EscrowNotExpired,
+    );
+
+    let later = now(&program) + ONE_WEEK;
+    warp_to(&mut program, later);
+
+    let escrow_lamports = lamports(&program, &native.escrow);
+    let maker_lamports = lamports(&program, &test_data.maker);
+    let cranker_lamports = lamports(&program, &cranker.pubkey());
+
+    let tx = send_crank_expired_native(&mut program, &native, &cranker).unwrap();
+
+    // The deposit and rent go back to the maker, the bounty to the cranker
+    assert_closed(&program, &native.escrow);
+    assert_eq!(
+        lamports(&program, &test_data.maker),
+        maker_lamports + escrow_lamports - 100_000
+    );
+    assert_eq!(
+        lamports(&program, &cranker.pubkey()),
+        cranker_lamports + 100_000 - SIGNATURE_FEE
+    );
+
+    let events = decode_events::<EscrowCranked>(&tx);
+    assert_eq!(events[0].amount_a, LAMPORTS_PER_SOL);
+    assert_eq!(events[0].mint_a, crate::state::Escrow::NATIVE_SOL);
+    assert_eq!(events[0].bounty, 100_000);
+
+    // A token escrow cannot be closed through the native crank, which would strand its vault
+    assert_escrow_error(
+        send_crank_expired_native(&mut program, &test_data, &cranker),
+        EscrowError::InvalidMint,
+    );
+}
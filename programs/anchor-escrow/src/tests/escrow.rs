@@ -0,0 +1,850 @@
+use super::*;
+
+#[test]
+fn test_make() {
+    // Setup the maker and create an escrow using the "Make" instruction
+    let (program, test_data, tx) = setup_with_make();
+
+    // Extract relevant data from the test setup
+    let vault = test_data.vault;
+    let escrow = test_data.escrow;
+    let mint_a = test_data.mint_a;
+    let maker = test_data.maker;
+    let mint_b = test_data.mint_b;
+
+    // Log transaction details
+    msg!("\nMake transaction sucessful");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+    msg!("Tx Signature: {}", tx.signature);
+
+    // Verify the vault account and escrow account data after the "Make" instruction
+    let vault_account = program.get_account(&vault).unwrap();
+    let vault_data = spl_token::state::Account::unpack(&vault_account.data).unwrap();
+    assert_eq!(vault_data.amount, 10);
+    assert_eq!(vault_data.owner, escrow);
+    assert_eq!(vault_data.mint, mint_a);
+
+    let escrow_account = program.get_account(&escrow).unwrap();
+    let escrow_data =
+        crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
+    assert_eq!(escrow_data.seed, 123u64);
+    assert_eq!(escrow_data.maker, maker);
+    assert_eq!(escrow_data.mint_a, mint_a);
+    assert_eq!(escrow_data.mint_b, mint_b);
+    assert_eq!(escrow_data.receive, 10);
+    assert_eq!(escrow_data.deposit, 10);
+    assert_eq!(escrow_data.remaining_a, 10);
+    assert_eq!(escrow_data.remaining_b, 10);
+    assert_eq!(escrow_data.expiry, now(&program) + ONE_WEEK);
+    assert_eq!(escrow_data.unlock_at, now(&program));
+}
+
+#[test]
+fn test_take() {
+    // Setup the maker and create an escrow using the "Make" instruction
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    // Extract relevant data from the test setup
+    let payer = test_data.payer.insecure_clone();
+    let vault = test_data.vault;
+    let escrow = test_data.escrow;
+    let mint_a = test_data.mint_a;
+    let mint_b = test_data.mint_b;
+
+    // Create a taker keypair and airdrop some SOL to the taker for transaction fees
+    let taker = Keypair::new();
+
+    program
+        .airdrop(
+            &taker.pubkey(),
+            10u64.checked_mul(LAMPORTS_PER_SOL).unwrap(),
+        )
+        .expect("Failed to airdrop SOL to taker");
+
+    // Create the taker's associated token accounts for Mint A and Mint B
+    let taker_ata_a = CreateAssociatedTokenAccount::new(&mut program, &taker, &mint_a)
+        .owner(&taker.pubkey())
+        .send()
+        .unwrap();
+    // msg!(">>> Taker ATA A: {}\n\n", taker_ata_a);
+
+    let taker_ata_b = CreateAssociatedTokenAccount::new(&mut program, &taker, &mint_b)
+        .owner(&taker.pubkey())
+        .send()
+        .unwrap();
+    // msg!(">>> Taker ATA B: {}", taker_ata_b);
+
+    // Mint 1,000 tokens (with 6 decimal places) of Mint B to the taker's associated token account
+    MintTo::new(&mut program, &payer, &mint_b, &taker_ata_b, 1000000000)
+        .send()
+        .unwrap();
+
+    // Create the "Take" instruction to accept and send tokens
+    let take_ix = take_ix(&program, &test_data, &taker, None);
+
+    // Create and send the transaction containing the "Take" instruction
+    let message = Message::new(&[take_ix], Some(&taker.pubkey()));
+    let recent_blockhash = program.latest_blockhash();
+
+    let transaction = Transaction::new(&[&taker], message, recent_blockhash);
+
+    // Send the transaction and capture the result
+    let tx = program.send_transaction(transaction).unwrap();
+
+    // Log transaction details
+    msg!("\n\nTake transaction successful");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+    msg!("Tx Signature: {}", tx.signature);
+
+    // Verify the escrow account has been closed
+    let escrow_account = program.get_account(&escrow);
+    if let Some(account) = escrow_account {
+        assert_eq!(
+            account.lamports, 0,
+            "Escrow account should have 0 lamports after closure"
+        );
+        assert!(
+            account.data.is_empty() || account.data.iter().all(|&b| b == 0),
+            "Escrow account data should be empty or zeroed"
+        );
+    }
+
+    // Verify the vault account has been closed
+    let vault_account = program.get_account(&vault);
+    if let Some(account) = vault_account {
+        assert_eq!(
+            account.lamports, 0,
+            "Vault account should have 0 lamports after closure"
+        );
+    }
+
+    // Verify taker received the escrowed tokens (mint_a)
+    let taker_ata_a_account = program.get_account(&taker_ata_a).unwrap();
+    let taker_ata_a_data = Account::unpack(&taker_ata_a_account.data).unwrap();
+    assert_eq!(
+        taker_ata_a_data.amount, 10,
+        "Taker should have received 10 tokens of mint_a"
+    );
+    assert_eq!(taker_ata_a_data.owner, taker.pubkey());
+    assert_eq!(taker_ata_a_data.mint, mint_a);
+}
+
+#[test]
+fn test_refund() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    let payer = test_data.payer.insecure_clone();
+    let maker = test_data.maker;
+    let vault = test_data.vault;
+    let escrow = test_data.escrow;
+    let maker_ata_a = test_data.maker_ata_a;
+
+    // Create the "Refund" instruction to accept and send tokens
+    let refund_ix = refund_ix(&program, &test_data);
+
+    // Create and send the transaction containing the "Refund" instruction
+    let message = Message::new(&[refund_ix], Some(&maker));
+    let recent_blockhash = program.latest_blockhash();
+
+    let transaction = Transaction::new(&[&payer], message, recent_blockhash);
+
+    // Send the transaction and capture the result
+    let tx = program.send_transaction(transaction).unwrap();
+
+    // Log transaction details
+    msg!("\n\nRefund transaction successful");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+    msg!("Tx Signature: {}", tx.signature);
+
+    // 1. Verify maker received their tokens back
+    let maker_ata_a_account = program.get_account(&maker_ata_a).unwrap();
+    let maker_ata_a_data = spl_token::state::Account::unpack(&maker_ata_a_account.data).unwrap();
+    assert_eq!(
+        maker_ata_a_data.amount, 1000000000,
+        "Maker should have all their tokens back (original 1B)"
+    );
+
+    // 2. Verify escrow account has been closed
+    let escrow_account = program.get_account(&escrow);
+    if let Some(account) = escrow_account {
+        assert_eq!(
+            account.lamports, 0,
+            "Escrow account should have 0 lamports after refund"
+        );
+        assert!(
+            account.data.is_empty() || account.data.iter().all(|&b| b == 0),
+            "Escrow account data should be empty or zeroed"
+        );
+    }
+
+    // 3. Verify vault account has been closed
+    let vault_account = program.get_account(&vault);
+    if let Some(account) = vault_account {
+        assert_eq!(
+            account.lamports, 0,
+            "Vault account should have 0 lamports after refund"
+        );
+    }
+}
+
+#[test]
+fn test_refund_after_delay() {
+    // Escrow that stays locked for exactly five days
+    let (mut program, test_data, tx) = setup_with_timed_make(ONE_WEEK, 5 * ONE_DAY);
+    tx.unwrap();
+
+    let payer = test_data.payer.insecure_clone();
+    let maker = test_data.maker;
+    let vault = test_data.vault;
+    let escrow = test_data.escrow;
+    let maker_ata_a = test_data.maker_ata_a;
+
+    // Time travel BEFORE attempting refund
+    let mut clock = program.get_sysvar::<Clock>();
+    let current_timestamp = clock.unix_timestamp;
+    let five_days = 5i64
+        .checked_mul(24)
+        .unwrap()
+        .checked_mul(60)
+        .unwrap()
+        .checked_mul(60)
+        .unwrap();
+    let time_jump = current_timestamp.checked_add(five_days).unwrap();
+
+    // Update timestamp to 5 days in the future
+    clock.unix_timestamp = time_jump;
+    program.set_sysvar::<Clock>(&clock);
+
+    msg!("\n\nTime travelled {} seconds into the future", five_days);
+
+    // Create the "Refund" instruction
+    let refund_ix = refund_ix(&program, &test_data);
+
+    // Create and send the transaction containing the "Refund" instruction
+    let message = Message::new(&[refund_ix], Some(&maker));
+    let recent_blockhash = program.latest_blockhash();
+
+    let transaction = Transaction::new(&[&payer], message, recent_blockhash);
+
+    // Send the transaction and capture the result
+    let tx = program.send_transaction(transaction).unwrap();
+
+    // Log transaction details
+    msg!("\n\nRefund after delay transaction successful");
+    msg!("CUs Consumed: {}", tx.compute_units_consumed);
+    msg!("Tx Signature: {}", tx.signature);
+
+    // 1. Verify maker received their tokens back
+    let maker_ata_a_account = program.get_account(&maker_ata_a).unwrap();
+    let maker_ata_a_data = spl_token::state::Account::unpack(&maker_ata_a_account.data).unwrap();
+    assert_eq!(
+        maker_ata_a_data.amount, 1000000000,
+        "Maker should have all their tokens back after time-locked refund"
+    );
+
+    // 2. Verify escrow account has been closed
+    let escrow_account = program.get_account(&escrow);
+    if let Some(account) = escrow_account {
+        assert_eq!(
+            account.lamports, 0,
+            "Escrow account should have 0 lamports after time-locked refund"
+        );
+        assert!(
+            account.data.is_empty() || account.data.iter().all(|&b| b == 0),
+            "Escrow account data should be empty or zeroed after time-locked refund"
+        );
+    }
+
+    // 3. Verify vault account has been closed
+    let vault_account = program.get_account(&vault);
+    if let Some(account) = vault_account {
+        assert_eq!(
+            account.lamports, 0,
+            "Vault account should have 0 lamports after time-locked refund"
+        );
+    }
+}
+
+#[test]
+fn test_refund_before_unlock_fails() {
+    let (mut program, test_data, tx) = setup_with_timed_make(ONE_WEEK, 5 * ONE_DAY);
+    tx.unwrap();
+
+    let escrow_account = program.get_account(&test_data.escrow).unwrap();
+    let escrow_data =
+        crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
+
+    // Refunding immediately is rejected
+    assert_escrow_error(
+        send_refund(&mut program, &test_data),
+        EscrowError::RefundLocked,
+    );
+
+    // One second before the lock ends is still rejected
+    warp_to(&mut program, escrow_data.unlock_at - 1);
+    assert_escrow_error(
+        send_refund(&mut program, &test_data),
+        EscrowError::RefundLocked,
+    );
+
+    // Once the lock ends the refund goes through
+    warp_to(&mut program, escrow_data.unlock_at);
+    send_refund(&mut program, &test_data).unwrap();
+
+    let maker_ata_a_account = program.get_account(&test_data.maker_ata_a).unwrap();
+    let maker_ata_a_data = spl_token::state::Account::unpack(&maker_ata_a_account.data).unwrap();
+    assert_eq!(maker_ata_a_data.amount, 1000000000);
+}
+
+#[test]
+fn test_take_before_expiry() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+
+    let escrow_account = program.get_account(&test_data.escrow).unwrap();
+    let escrow_data =
+        crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
+
+    // One second before expiry the escrow can still be taken
+    warp_to(&mut program, escrow_data.expiry - 1);
+    send_take(&mut program, &test_data, &taker).unwrap();
+
+    let taker_ata_a_account = program.get_account(&taker_ata_a).unwrap();
+    let taker_ata_a_data = Account::unpack(&taker_ata_a_account.data).unwrap();
+    assert_eq!(taker_ata_a_data.amount, 10);
+}
+
+#[test]
+fn test_take_after_expiry_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+
+    let escrow_account = program.get_account(&test_data.escrow).unwrap();
+    let escrow_data =
+        crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
+
+    // At the expiry timestamp the escrow can no longer be taken
+    warp_to(&mut program, escrow_data.expiry);
+    assert_escrow_error(
+        send_take(&mut program, &test_data, &taker),
+        EscrowError::EscrowExpired,
+    );
+
+    // Nor any time after
+    warp_to(&mut program, escrow_data.expiry + ONE_DAY);
+    assert_escrow_error(
+        send_take(&mut program, &test_data, &taker),
+        EscrowError::EscrowExpired,
+    );
+
+    // The vault still holds the maker's deposit
+    let vault_account = program.get_account(&test_data.vault).unwrap();
+    let vault_data = spl_token::state::Account::unpack(&vault_account.data).unwrap();
+    assert_eq!(vault_data.amount, 10);
+}
+
+#[test]
+fn test_make_with_invalid_schedule_fails() {
+    // An expiry that is not in the future is rejected
+    let (_program, _test_data, tx) = setup_with_timed_make(0, 0);
+    assert_escrow_error(tx, EscrowError::InvalidExpiry);
+
+    // A lock period that outlasts the expiry is rejected
+    let (_program, _test_data, tx) = setup_with_timed_make(ONE_DAY, ONE_DAY + 1);
+    assert_escrow_error(tx, EscrowError::InvalidLockPeriod);
+
+    // A negative lock period is rejected
+    let (_program, _test_data, tx) = setup_with_timed_make(ONE_DAY, -1);
+    assert_escrow_error(tx, EscrowError::InvalidLockPeriod);
+
+    // A lock period that ends exactly at expiry is allowed
+    let (_program, _test_data, tx) = setup_with_timed_make(ONE_DAY, ONE_DAY);
+    tx.unwrap();
+}
+
+#[test]
+fn test_take_partial_sequential_takers() {
+    // 1,000 of Mint A offered for 400 of Mint B
+    let (mut program, test_data, tx) = setup_with_make_args(1000, 400, ONE_WEEK, 0);
+    tx.unwrap();
+
+    // Three takers fill 100, 150 and the remaining 150 of Mint B
+    let fills = [(100, 250), (150, 375), (150, 375)];
+
+    for (i, (amount_b, expected_a)) in fills.into_iter().enumerate() {
+        let (taker, taker_ata_a, taker_ata_b) = setup_taker(&mut program, &test_data);
+
+        let tx = send_take_partial(&mut program, &test_data, &taker, amount_b).unwrap();
+
+        msg!(
+            "Partial take {} CUs Consumed: {}",
+            i,
+            tx.compute_units_consumed
+        );
+
+        assert_eq!(token_balance(&program, &taker_ata_a), expected_a);
+        assert_eq!(token_balance(&program, &taker_ata_b), 1000000000 - amount_b);
+    }
+
+    // The maker received the full 400 of Mint B
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 400);
+
+    // No dust: the vault and escrow have been closed
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1000000000 - 1000
+    );
+    assert_closed(&program, &test_data.vault);
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_take_partial_updates_remaining_amounts() {
+    let (mut program, test_data, tx) = setup_with_make_args(1000, 400, ONE_WEEK, 0);
+    tx.unwrap();
+
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+    send_take_partial(&mut program, &test_data, &taker, 100).unwrap();
+
+    let escrow_data = escrow_state(&program, &test_data);
+    assert_eq!(escrow_data.deposit, 1000);
+    assert_eq!(escrow_data.receive, 400);
+    assert_eq!(escrow_data.remaining_a, 750);
+    assert_eq!(escrow_data.remaining_b, 300);
+    assert_eq!(token_balance(&program, &test_data.vault), 750);
+
+    // A full take after a partial one pays out the rest of the vault
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+    send_take(&mut program, &test_data, &taker).unwrap();
+
+    assert_eq!(token_balance(&program, &taker_ata_a), 750);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 400);
+    assert_closed(&program, &test_data.vault);
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_take_partial_rounding_leaves_dust_to_maker() {
+    // 10 of Mint A offered for 3 of Mint B, which does not divide evenly
+    let (mut program, test_data, tx) = setup_with_make_args(10, 3, ONE_WEEK, 0);
+    tx.unwrap();
+
+    // Each taker pays 1 of Mint B and receives floor(10 / 3) = 3 of Mint A
+    for _ in 0..3 {
+        let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+        send_take_partial(&mut program, &test_data, &taker, 1).unwrap();
+
+        assert_eq!(token_balance(&program, &taker_ata_a), 3);
+    }
+
+    // The order is fully filled and the leftover 1 of Mint A goes back to the maker
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 3);
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1000000000 - 10 + 1
+    );
+    assert_closed(&program, &test_data.vault);
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_take_partial_without_maker_ata() {
+    // 10 of Mint A offered for 3 of Mint B, which leaves 1 of Mint A as dust
+    let (mut program, test_data, tx) = setup_with_make_args(10, 3, ONE_WEEK, 0);
+    tx.unwrap();
+
+    // Leave out the maker's Mint A account, which Anchor reads as `None` given the program id
+    let take_without_maker_ata = |program: &LiteSVM, taker: &Keypair| {
+        let mut take_ix = take_ix(program, &test_data, taker, Some(1));
+        let maker_ata_a = take_ix
+            .accounts
+            .iter_mut()
+            .find(|meta| meta.pubkey == test_data.maker_ata_a)
+            .unwrap();
+        *maker_ata_a = AccountMeta::new_readonly(PROGRAM_ID, false);
+        take_ix
+    };
+
+    // Takes that leave the escrow open do not need it
+    for _ in 0..2 {
+        let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+        let take_ix = take_without_maker_ata(&program, &taker);
+        send_signed(&mut program, &taker, take_ix).unwrap();
+
+        assert_eq!(token_balance(&program, &taker_ata_a), 3);
+    }
+
+    // The take that completes the escrow returns the dust to it, so it must be passed
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+    let take_ix = take_without_maker_ata(&program, &taker);
+    assert_escrow_error(
+        send_signed(&mut program, &taker, take_ix),
+        EscrowError::MakerAtaRequired,
+    );
+
+    send_take_partial(&mut program, &test_data, &taker, 1).unwrap();
+    assert_eq!(token_balance(&program, &taker_ata_a), 3);
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1000000000 - 10 + 1
+    );
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_take_partial_invalid_amounts_fail() {
+    // 3 of Mint A offered for 10 of Mint B
+    let (mut program, test_data, tx) = setup_with_make_args(3, 10, ONE_WEEK, 0);
+    tx.unwrap();
+
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+
+    // Zero is rejected
+    assert_escrow_error(
+        send_take_partial(&mut program, &test_data, &taker, 0),
+        EscrowError::InvalidFillAmount,
+    );
+
+    // More than the remaining amount is rejected
+    assert_escrow_error(
+        send_take_partial(&mut program, &test_data, &taker, 11),
+        EscrowError::InvalidFillAmount,
+    );
+
+    // A fill that would round down to zero of Mint A is rejected
+    assert_escrow_error(
+        send_take_partial(&mut program, &test_data, &taker, 3),
+        EscrowError::FillTooSmall,
+    );
+
+    // The smallest fill that pays out something goes through
+    send_take_partial(&mut program, &test_data, &taker, 4).unwrap();
+    assert_eq!(token_balance(&program, &taker_ata_a), 1);
+    assert_eq!(escrow_state(&program, &test_data).remaining_b, 6);
+}
+
+#[test]
+fn test_make_zero_deposit_fails() {
+    let (_program, _test_data, tx) = setup_with_make_args(0, 10, ONE_WEEK, 0);
+    assert_escrow_error(tx, EscrowError::ZeroDeposit);
+}
+
+#[test]
+fn test_make_zero_receive_fails() {
+    let (_program, _test_data, tx) = setup_with_make_args(10, 0, ONE_WEEK, 0);
+    assert_escrow_error(tx, EscrowError::ZeroReceive);
+}
+
+#[test]
+fn test_make_same_mint_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    let mint_a = test_data.mint_a;
+    assert_escrow_error(
+        send_make(&mut program, &test_data, 456, mint_a, 10, 10, None),
+        EscrowError::SameMint,
+    );
+}
+
+#[test]
+fn test_make_insufficient_balance_fails() {
+    // The maker only holds 1,000 tokens of Mint A
+    let (_program, _test_data, tx) = setup_with_make_args(1000000001, 10, ONE_WEEK, 0);
+    assert_escrow_error(tx, EscrowError::InsufficientMakerBalance);
+}
+
+#[test]
+fn test_make_duplicate_seed_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    // The escrow PDA for seed 123 already exists
+    program.expire_blockhash();
+    let mint_b = test_data.mint_b;
+    let result = send_make(&mut program, &test_data, 123, mint_b, 10, 10, None);
+    assert_eq!(
+        result.expect_err("Transaction should have failed").err,
+        TransactionError::InstructionError(0, InstructionError::Custom(0)),
+        "System program should reject re-creating the escrow account"
+    );
+}
+
+#[test]
+fn test_take_insufficient_balance_fails() {
+    // The taker only holds 1,000 tokens of Mint B
+    let (mut program, test_data, tx) = setup_with_make_args(10, 1000000001, ONE_WEEK, 0);
+    tx.unwrap();
+
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+    assert_escrow_error(
+        send_take(&mut program, &test_data, &taker),
+        EscrowError::InsufficientTakerBalance,
+    );
+}
+
+#[test]
+fn test_take_wrong_mint_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+
+    // The taker offers Mint C instead of Mint B
+    let mint_c = CreateMint::new(&mut program, &test_data.payer)
+        .decimals(6)
+        .authority(&test_data.maker)
+        .send()
+        .unwrap();
+    let taker_ata_c = CreateAssociatedTokenAccount::new(&mut program, &taker, &mint_c)
+        .owner(&taker.pubkey())
+        .send()
+        .unwrap();
+    MintTo::new(
+        &mut program,
+        &test_data.payer,
+        &mint_c,
+        &taker_ata_c,
+        1000000000,
+    )
+    .send()
+    .unwrap();
+
+    let wrong_mint = TestData {
+        payer: test_data.payer.insecure_clone(),
+        mint_b: mint_c,
+        maker_ata_b: associated_token::get_associated_token_address(&test_data.maker, &mint_c),
+        ..test_data
+    };
+
+    assert_escrow_error(
+        send_take(&mut program, &wrong_mint, &taker),
+        EscrowError::InvalidMint,
+    );
+}
+
+#[test]
+fn test_take_already_taken_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+
+    send_take(&mut program, &test_data, &taker).unwrap();
+
+    // A second taker finds the escrow account closed
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+    assert_anchor_error(
+        send_take(&mut program, &test_data, &taker),
+        ErrorCode::AccountNotInitialized,
+    );
+}
+
+#[test]
+fn test_take_receive_overflow_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+
+    // Push the maker's Mint B balance right up to the u64 limit
+    let mut maker_ata_b_account = program.get_account(&test_data.maker_ata_b).unwrap();
+    let mut maker_ata_b_data = Account::unpack(&maker_ata_b_account.data).unwrap();
+    maker_ata_b_data.amount = u64::MAX - 5;
+    Account::pack(maker_ata_b_data, &mut maker_ata_b_account.data).unwrap();
+    program
+        .set_account(test_data.maker_ata_b, maker_ata_b_account)
+        .unwrap();
+
+    assert_escrow_error(
+        send_take(&mut program, &test_data, &taker),
+        EscrowError::ReceiveOverflow,
+    );
+}
+
+#[test]
+fn test_refund_wrong_mint_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    // Mint B is passed off as the escrowed mint
+    let maker_ata_b = test_data.maker_ata_b;
+    let wrong_mint = TestData {
+        payer: test_data.payer.insecure_clone(),
+        mint_a: test_data.mint_b,
+        maker_ata_a: maker_ata_b,
+        ..test_data
+    };
+
+    // The real vault is passed, since the one derived for Mint B does not exist
+    let refund_ix = refund_ix(&program, &wrong_mint);
+
+    assert_escrow_error(
+        send_signed(&mut program, &wrong_mint.payer, refund_ix),
+        EscrowError::InvalidMint,
+    );
+}
+
+#[test]
+fn test_refund_by_non_maker_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    // Another signer poses as the maker of an escrow it does not own
+    let impostor = Keypair::new();
+    program
+        .airdrop(&impostor.pubkey(), LAMPORTS_PER_SOL)
+        .expect("Failed to airdrop SOL to impostor");
+    let impostor_ata_a =
+        CreateAssociatedTokenAccount::new(&mut program, &impostor, &test_data.mint_a)
+            .owner(&impostor.pubkey())
+            .send()
+            .unwrap();
+
+    let impostor_data = TestData {
+        maker: impostor.pubkey(),
+        maker_ata_a: impostor_ata_a,
+        payer: impostor,
+        ..test_data
+    };
+
+    // The escrow PDA does not derive from the impostor's key
+    let refund_ix = refund_ix(&program, &impostor_data);
+
+    assert_anchor_error(
+        send_signed(&mut program, &impostor_data.payer, refund_ix),
+        ErrorCode::ConstraintSeeds,
+    );
+}
+
+#[test]
+fn test_make_emits_event() {
+    let (program, test_data, tx) = setup_with_make();
+
+    let events = decode_events::<EscrowCreated>(&tx);
+    assert_eq!(events.len(), 1);
+
+    let clock = program.get_sysvar::<Clock>();
+    let event = &events[0];
+    assert_eq!(event.seed, 123);
+    assert_eq!(event.maker, test_data.maker);
+    assert_eq!(event.mint_a, test_data.mint_a);
+    assert_eq!(event.mint_b, test_data.mint_b);
+    assert_eq!(event.deposit, 10);
+    assert_eq!(event.receive, 10);
+    assert_eq!(event.expiry, clock.unix_timestamp + ONE_WEEK);
+    assert_eq!(event.unlock_at, clock.unix_timestamp);
+    assert_eq!(event.slot, clock.slot);
+    assert_eq!(event.timestamp, clock.unix_timestamp);
+}
+
+#[test]
+fn test_take_emits_event() {
+    let (mut program, test_data, tx) = setup_with_make_args(1000, 400, ONE_WEEK, 0);
+    tx.unwrap();
+
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+    let clock = program.get_sysvar::<Clock>();
+
+    // A partial take reports what is left on the order
+    let tx = send_take_partial(&mut program, &test_data, &taker, 100).unwrap();
+
+    let events = decode_events::<EscrowTaken>(&tx);
+    assert_eq!(events.len(), 1);
+
+    let event = &events[0];
+    assert_eq!(event.seed, 123);
+    assert_eq!(event.maker, test_data.maker);
+    assert_eq!(event.taker, taker.pubkey());
+    assert_eq!(event.mint_a, test_data.mint_a);
+    assert_eq!(event.mint_b, test_data.mint_b);
+    assert_eq!(event.amount_a, 250);
+    assert_eq!(event.amount_b, 100);
+    assert_eq!(event.fee, 0);
+    assert_eq!(event.remaining_a, 750);
+    assert_eq!(event.remaining_b, 300);
+    assert_eq!(event.slot, clock.slot);
+    assert_eq!(event.timestamp, clock.unix_timestamp);
+
+    // Taking the rest closes the escrow and reports nothing remaining
+    let tx = send_take(&mut program, &test_data, &taker).unwrap();
+
+    let events = decode_events::<EscrowTaken>(&tx);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].amount_a, 750);
+    assert_eq!(events[0].amount_b, 300);
+    assert_eq!(events[0].remaining_a, 0);
+    assert_eq!(events[0].remaining_b, 0);
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_refund_emits_event() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    let mut clock = program.get_sysvar::<Clock>();
+    clock.slot += 100;
+    clock.unix_timestamp += ONE_DAY;
+    program.set_sysvar::<Clock>(&clock);
+
+    let tx = send_refund(&mut program, &test_data).unwrap();
+
+    // Only the refund event is emitted
+    assert!(decode_events::<EscrowCreated>(&tx).is_empty());
+    assert!(decode_events::<EscrowTaken>(&tx).is_empty());
+
+    let events = decode_events::<EscrowRefunded>(&tx);
+    assert_eq!(events.len(), 1);
+
+    let event = &events[0];
+    assert_eq!(event.seed, 123);
+    assert_eq!(event.maker, test_data.maker);
+    assert_eq!(event.mint_a, test_data.mint_a);
+    assert_eq!(event.mint_b, test_data.mint_b);
+    assert_eq!(event.amount_a, 10);
+    assert_eq!(event.slot, clock.slot);
+    assert_eq!(event.timestamp, clock.unix_timestamp);
+}
+
+#[test]
+fn test_take_restricted_to_allowed_taker() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    let (allowed, allowed_ata_a, _) = setup_taker(&mut program, &test_data);
+    let (intruder, intruder_ata_a, _) = setup_taker(&mut program, &test_data);
+
+    // A private escrow that only `allowed` can take
+    let mint_b = test_data.mint_b;
+    let tx = send_make(
+        &mut program,
+        &test_data,
+        456,
+        mint_b,
+        10,
+        10,
+        Some(allowed.pubkey()),
+    )
+    .unwrap();
+    assert_eq!(
+        decode_events::<EscrowCreated>(&tx)[0].allowed_taker,
+        Some(allowed.pubkey())
+    );
+
+    let private = with_seed(&test_data, 456);
+    assert_eq!(
+        escrow_state(&program, &private).allowed_taker,
+        Some(allowed.pubkey())
+    );
+
+    // Anyone else is turned away, for full and partial takes alike
+    assert_escrow_error(
+        send_take(&mut program, &private, &intruder),
+        EscrowError::UnauthorizedTaker,
+    );
+    assert_escrow_error(
+        send_take_partial(&mut program, &private, &intruder, 5),
+        EscrowError::UnauthorizedTaker,
+    );
+
+    // The named taker can fill it
+    send_take(&mut program, &private, &allowed).unwrap();
+
+    assert_eq!(token_balance(&program, &allowed_ata_a), 10);
+    assert_eq!(token_balance(&program, &intruder_ata_a), 0);
+    assert_closed(&program, &private.escrow);
+
+    // The open escrow with seed 123 is still available to anyone
+    assert_eq!(escrow_state(&program, &test_data).allowed_taker, None);
+    send_take(&mut program, &test_data, &intruder).unwrap();
+    assert_eq!(token_balance(&program, &intruder_ata_a), 10);
+}
@@ -0,0 +1,296 @@
+use super::*;
+
+const FUZZ_MAKERS: usize = 3;
+const FUZZ_TAKERS: usize = 2;
+const FUZZ_SEEDS: u64 = 3;
+const FUZZ_BALANCE: u64 = 1_000_000_000;
+
+// One step of a fuzzed sequence, addressing escrows by maker index and seed
+#[derive(Clone, Debug)]
+enum FuzzOp {
+    Make {
+        maker: usize,
+        seed: u64,
+        deposit: u64,
+        receive: u64,
+    },
+    Take {
+        taker: usize,
+        maker: usize,
+        seed: u64,
+    },
+    Refund {
+        maker: usize,
+        seed: u64,
+    },
+    // The same take sent twice in a row
+    DuplicateTake {
+        taker: usize,
+        maker: usize,
+        seed: u64,
+    },
+    // A taker signs a refund posing as the maker
+    WrongSigner {
+        taker: usize,
+        maker: usize,
+        seed: u64,
+    },
+    // A take that offers Mint A instead of Mint B
+    WrongMint {
+        taker: usize,
+        maker: usize,
+        seed: u64,
+    },
+}
+
+// Amounts up to 60% of a balance, so a second make or take can run out of funds
+fn fuzz_op() -> impl Strategy<Value = FuzzOp> {
+    let amount = 1..=FUZZ_BALANCE * 6 / 10;
+    let (taker, maker, seed) = (0..FUZZ_TAKERS, 0..FUZZ_MAKERS, 0..FUZZ_SEEDS);
+
+    prop_oneof![
+        4 => (maker.clone(), seed.clone(), amount.clone(), amount).prop_map(
+            |(maker, seed, deposit, receive)| FuzzOp::Make {
+                maker,
+                seed,
+                deposit,
+                receive,
+            }
+        ),
+        3 => (taker.clone(), maker.clone(), seed.clone())
+            .prop_map(|(taker, maker, seed)| FuzzOp::Take { taker, maker, seed }),
+        2 => (maker.clone(), seed.clone())
+            .prop_map(|(maker, seed)| FuzzOp::Refund { maker, seed }),
+        1 => (taker.clone(), maker.clone(), seed.clone())
+            .prop_map(|(taker, maker, seed)| FuzzOp::DuplicateTake { taker, maker, seed }),
+        1 => (taker.clone(), maker.clone(), seed.clone())
+            .prop_map(|(taker, maker, seed)| FuzzOp::WrongSigner { taker, maker, seed }),
+        1 => (taker, maker, seed)
+            .prop_map(|(taker, maker, seed)| FuzzOp::WrongMint { taker, maker, seed }),
+    ]
+}
+
+// Reference model: Mint A and Mint B held by every actor, and the open escrows
+// as (deposit, receive) keyed by maker index and seed
+#[derive(Clone, Debug, PartialEq)]
+struct FuzzModel {
+    maker_a: Vec<u64>,
+    maker_b: Vec<u64>,
+    taker_a: Vec<u64>,
+    taker_b: Vec<u64>,
+    escrows: BTreeMap<(usize, u64), (u64, u64)>,
+}
+
+impl FuzzModel {
+    fn new() -> Self {
+        Self {
+            maker_a: vec![FUZZ_BALANCE; FUZZ_MAKERS],
+            maker_b: vec![0; FUZZ_MAKERS],
+            taker_a: vec![0; FUZZ_TAKERS],
+            taker_b: vec![FUZZ_BALANCE; FUZZ_TAKERS],
+            escrows: BTreeMap::new(),
+        }
+    }
+
+    // Read the same state back from the chain
+    fn observe(scenario: &Scenario) -> Self {
+        let balances = |actors: &[Actor], ata: fn(&Actor) -> Pubkey| {
+            actors
+                .iter()
+                .map(|actor| scenario.balance(&ata(actor)))
+                .collect()
+        };
+
+        let mut escrows = BTreeMap::new();
+        for maker in 0..FUZZ_MAKERS {
+            for seed in 0..FUZZ_SEEDS {
+                let test_data = with_seed(&scenario.escrow(maker), seed);
+                let Some(account) = scenario.program.get_account(&test_data.escrow) else {
+                    continue;
+                };
+                if account.lamports > 0 {
+                    let escrow = escrow_state(&scenario.program, &test_data);
+                    escrows.insert((maker, seed), (escrow.deposit, escrow.receive));
+                }
+            }
+        }
+
+        Self {
+            maker_a: balances(&scenario.makers, |actor| actor.ata_a),
+            maker_b: balances(&scenario.makers, |actor| actor.ata_b),
+            taker_a: balances(&scenario.takers, |actor| actor.ata_a),
+            taker_b: balances(&scenario.takers, |actor| actor.ata_b),
+            escrows,
+        }
+    }
+
+    // Apply a full take if the model allows it, returning whether it should succeed
+    fn take(&mut self, taker: usize, maker: usize, seed: u64) -> bool {
+        match self.escrows.get(&(maker, seed)) {
+            Some(&(deposit, receive)) if self.taker_b[taker] >= receive => {
+                self.escrows.remove(&(maker, seed));
+                self.taker_a[taker] += deposit;
+                self.taker_b[taker] -= receive;
+                self.maker_b[maker] += receive;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Apply `op` to the model, returning whether every transaction it sends should succeed
+    fn apply(&mut self, op: &FuzzOp) -> Vec<bool> {
+        match *op {
+            FuzzOp::Make {
+                maker,
+                seed,
+                deposit,
+                receive,
+            } => {
+                let ok =
+                    !self.escrows.contains_key(&(maker, seed)) && self.maker_a[maker] >= deposit;
+                if ok {
+                    self.escrows.insert((maker, seed), (deposit, receive));
+                    self.maker_a[maker] -= deposit;
+                }
+                vec![ok]
+            }
+            FuzzOp::Take { taker, maker, seed } => vec![self.take(taker, maker, seed)],
+            FuzzOp::Refund { maker, seed } => match self.escrows.remove(&(maker, seed)) {
+                Some((deposit, _)) => {
+                    self.maker_a[maker] += deposit;
+                    vec![true]
+                }
+                None => vec![false],
+            },
+            FuzzOp::DuplicateTake { taker, maker, seed } => {
+                vec![self.take(taker, maker, seed), false]
+            }
+            FuzzOp::WrongSigner { .. } | FuzzOp::WrongMint { .. } => vec![false],
+        }
+    }
+}
+
+// Send `ix` with a fresh blockhash, so a repeated step is not rejected as already processed
+fn send_fresh(program: &mut LiteSVM, signer: &Keypair, ix: Instruction) -> TransactionResult {
+    program.expire_blockhash();
+    send_signed(program, signer, ix)
+}
+
+// Send the transactions of `op` against the escrows of `scenario`
+fn send_fuzz_op(scenario: &mut Scenario, op: &FuzzOp) -> Vec<TransactionResult> {
+    match *op {
+        FuzzOp::Make {
+            maker,
+            seed,
+            deposit,
+            receive,
+        } => {
+            let test_data = scenario.escrow(maker);
+            let make_ix = make_builder(&scenario.program, &test_data, seed, test_data.mint_b)
+                .deposit(deposit)
+                .receive(receive)
+                .instruction();
+            vec![send_fresh(&mut scenario.program, &test_data.payer, make_ix)]
+        }
+        FuzzOp::Take { taker, maker, seed } => {
+            let test_data = with_seed(&scenario.escrow(maker), seed);
+            let taker = scenario.takers[taker].keypair.insecure_clone();
+            let take_ix = take_ix(&scenario.program, &test_data, &taker, None);
+            vec![send_fresh(&mut scenario.program, &taker, take_ix)]
+        }
+        FuzzOp::Refund { maker, seed } => {
+            let test_data = with_seed(&scenario.escrow(maker), seed);
+            let refund_ix = refund_ix(&scenario.program, &test_data);
+            vec![send_fresh(
+                &mut scenario.program,
+                &test_data.payer,
+                refund_ix,
+            )]
+        }
+        FuzzOp::DuplicateTake { taker, maker, seed } => {
+            let test_data = with_seed(&scenario.escrow(maker), seed);
+            let taker = scenario.takers[taker].keypair.insecure_clone();
+            let take_ix = take_ix(&scenario.program, &test_data, &taker, None);
+            vec![
+                send_fresh(&mut scenario.program, &taker, take_ix.clone()),
+                send_fresh(&mut scenario.program, &taker, take_ix),
+            ]
+        }
+        FuzzOp::WrongSigner { taker, maker, seed } => {
+            let test_data = with_seed(&scenario.escrow(maker), seed);
+            let impostor = &scenario.takers[taker];
+            let impostor_data = TestData {
+                payer: impostor.keypair.insecure_clone(),
+                maker: impostor.pubkey(),
+                maker_ata_a: impostor.ata_a,
+                ..test_data
+            };
+            let refund_ix = refund_ix(&scenario.program, &impostor_data);
+            vec![send_fresh(
+                &mut scenario.program,
+                &impostor_data.payer,
+                refund_ix,
+            )]
+        }
+        FuzzOp::WrongMint { taker, maker, seed } => {
+            let test_data = with_seed(&scenario.escrow(maker), seed);
+            let wrong_mint = TestData {
+                payer: test_data.payer.insecure_clone(),
+                mint_b: test_data.mint_a,
+                maker_ata_b: test_data.maker_ata_a,
+                ..test_data
+            };
+            let taker = scenario.takers[taker].keypair.insecure_clone();
+            let take_ix = take_ix(&scenario.program, &wrong_mint, &taker, None);
+            vec![send_fresh(&mut scenario.program, &taker, take_ix)]
+        }
+    }
+}
+
+// Run `ops` against a fresh scenario and the reference model, checking both agree after
+// every step. Proptest shrinks a failing sequence to the shortest one that still fails
+fn run_fuzz(ops: &[FuzzOp]) -> Result<(), TestCaseError> {
+    let mut scenario = EscrowScenario::new()
+        .makers(FUZZ_MAKERS)
+        .takers(FUZZ_TAKERS)
+        .maker_balance(FUZZ_BALANCE)
+        .taker_balance(FUZZ_BALANCE)
+        .make(false)
+        .build();
+    let mut model = FuzzModel::new();
+
+    for (step, op) in ops.iter().enumerate() {
+        let expected = model.apply(op);
+        let results = send_fuzz_op(&mut scenario, op);
+
+        for (expected, result) in expected.into_iter().zip(results) {
+            prop_assert_eq!(
+                result.is_ok(),
+                expected,
+                "step {} {:?} returned {:?}",
+                step,
+                op,
+                result.map(|_| ()).map_err(|failed| failed.err)
+            );
+        }
+        prop_assert_eq!(
+            &FuzzModel::observe(&scenario),
+            &model,
+            "after step {} {:?}",
+            step,
+            op
+        );
+    }
+
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_fuzz_make_take_refund(ops in prop::collection::vec(fuzz_op(), 1..32)) {
+        run_fuzz(&ops)?;
+    }
+}
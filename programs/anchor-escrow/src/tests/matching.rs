@@ -0,0 +1,296 @@
+use super::*;
+
+// Test data for `maker` offering the opposite side of the pair in `test_data`
+fn counterparty(test_data: &TestData, maker: &Keypair, seed: u64) -> TestData {
+    let counterparty = TestData {
+        payer: maker.insecure_clone(),
+        maker: maker.pubkey(),
+        mint_a: test_data.mint_b,
+        mint_b: test_data.mint_a,
+        maker_ata_a: basket_ata(test_data, &maker.pubkey(), &test_data.mint_b),
+        maker_ata_b: basket_ata(test_data, &maker.pubkey(), &test_data.mint_a),
+        ..with_seed(test_data, seed)
+    };
+
+    with_seed(&counterparty, seed)
+}
+
+// Build and send the "MatchEscrows" instruction settling escrow `x` against escrow `y`
+fn send_match_escrows(
+    program: &mut LiteSVM,
+    x: &TestData,
+    y: &TestData,
+    matcher: &Keypair,
+) -> TransactionResult {
+    let match_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MatchEscrows {
+            matcher: matcher.pubkey(),
+            maker_x: x.maker,
+            maker_y: y.maker,
+            mint_a: x.mint_a,
+            mint_b: x.mint_b,
+            maker_x_ata_a: x.maker_ata_a,
+            maker_x_ata_b: x.maker_ata_b,
+            maker_y_ata_a: y.maker_ata_b,
+            maker_y_ata_b: y.maker_ata_a,
+            matcher_ata_a: basket_ata(x, &matcher.pubkey(), &x.mint_a),
+            matcher_ata_b: basket_ata(x, &matcher.pubkey(), &x.mint_b),
+            config: config_pda(),
+            fee_recipient: x.fee_recipient,
+            fee_recipient_ata_a: basket_ata(x, &x.fee_recipient, &x.mint_a),
+            fee_recipient_ata_b: basket_ata(x, &x.fee_recipient, &x.mint_b),
+            escrow_x: x.escrow,
+            vault_x: x.vault,
+            escrow_y: y.escrow,
+            vault_y: y.vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: x.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MatchEscrows {}.data(),
+    };
+
+    let message = Message::new(&[match_ix], Some(&matcher.pubkey()));
+    let transaction = Transaction::new(&[matcher], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Maker X offers 100 A for 200 B, and the taker from `setup_taker` becomes maker Y
+// offering `deposit_y` B for `receive_y` A
+fn setup_match(deposit_y: u64, receive_y: u64) -> (LiteSVM, TestData, TestData, Keypair) {
+    setup_match_with_fee(0, deposit_y, receive_y)
+}
+
+// Same as `setup_match`, but both escrows are made while the protocol charges `fee_bps`
+fn setup_match_with_fee(
+    fee_bps: u16,
+    deposit_y: u64,
+    receive_y: u64,
+) -> (LiteSVM, TestData, TestData, Keypair) {
+    let (mut program, test_data) = setup_with_fee(fee_bps);
+    let (x, y, matcher) = make_match(&mut program, &test_data, deposit_y, receive_y);
+
+    (program, x, y, matcher)
+}
+
+// Make the escrows of `setup_match` on the mints of `test_data`, and fund a matcher
+fn make_match(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    deposit_y: u64,
+    receive_y: u64,
+) -> (TestData, TestData, Keypair) {
+    let (maker_y, _, _) = setup_taker(program, test_data);
+
+    let x = with_seed(test_data, 1);
+    send_make(program, &x, 1, x.mint_b, 100, 200, None).unwrap();
+
+    let y = counterparty(test_data, &maker_y, 2);
+    send_make(program, &y, 2, y.mint_b, deposit_y, receive_y, None).unwrap();
+
+    let matcher = funded_keypair(program);
+
+    (x, y, matcher)
+}
+
+#[test]
+fn test_match_escrows_exact() {
+    let (mut program, x, y, matcher) = setup_match(200, 100);
+    let maker_x_a = token_balance(&program, &x.maker_ata_a);
+
+    let tx = send_match_escrows(&mut program, &x, &y, &matcher).unwrap();
+
+    assert_eq!(token_balance(&program, &x.maker_ata_b), 200);
+    assert_eq!(token_balance(&program, &y.maker_ata_b), 100);
+    assert_eq!(token_balance(&program, &x.maker_ata_a), maker_x_a);
+    assert_eq!(
+        token_balance(&program, &basket_ata(&x, &matcher.pubkey(), &x.mint_a)),
+        0
+    );
+    assert_eq!(
+        token_balance(&program, &basket_ata(&x, &matcher.pubkey(), &x.mint_b)),
+        0
+    );
+    assert_closed(&program, &x.escrow);
+    assert_closed(&program, &x.vault);
+    assert_closed(&program, &y.escrow);
+    assert_closed(&program, &y.vault);
+
+    let events = decode_events::<EscrowsMatched>(&tx);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].matcher, matcher.pubkey());
+    assert_eq!(events[0].maker_x, x.maker);
+    assert_eq!(events[0].maker_y, y.maker);
+    assert_eq!(events[0].amount_a, 100);
+    assert_eq!(events[0].amount_b, 200);
+    assert_eq!(events[0].surplus_a, 0);
+    assert_eq!(events[0].surplus_b, 0);
+}
+
+#[test]
+fn test_match_escrows_partial() {
+    // Y pays 2.5 B per A for 40 A, so it fills 40 of X's 100 A and leaves a surplus
+    let (mut program, x, y, matcher) = setup_match(100, 40);
+
+    let tx = send_match_escrows(&mut program, &x, &y, &matcher).unwrap();
+
+    assert_eq!(token_balance(&program, &x.maker_ata_b), 80);
+    assert_eq!(token_balance(&program, &y.maker_ata_b), 40);
+    assert_eq!(
+        token_balance(&program, &basket_ata(&x, &matcher.pubkey(), &x.mint_b)),
+        20
+    );
+    assert_closed(&program, &y.escrow);
+    assert_closed(&program, &y.vault);
+
+    let escrow = escrow_state(&program, &x);
+    assert_eq!(escrow.remaining_a, 60);
+    assert_eq!(escrow.remaining_b, 120);
+    assert_eq!(token_balance(&program, &x.vault), 60);
+
+    let events = decode_events::<EscrowsMatched>(&tx);
+    assert_eq!(events[0].amount_a, 40);
+    assert_eq!(events[0].amount_b, 100);
+    assert_eq!(events[0].surplus_b, 20);
+
+    // The rest of X can still be taken the usual way
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &x);
+    send_take(&mut program, &x, &taker).unwrap();
+    assert_eq!(token_balance(&program, &taker_ata_a), 60);
+    assert_closed(&program, &x.escrow);
+}
+
+#[test]
+fn test_match_escrows_leaves_larger_side_open() {
+    // Y wants 200 A for 500 B, more than X holds
+    let (mut program, x, y, matcher) = setup_match(500, 200);
+
+    send_match_escrows(&mut program, &x, &y, &matcher).unwrap();
+
+    assert_closed(&program, &x.escrow);
+    assert_eq!(token_balance(&program, &y.maker_ata_b), 100);
+    assert_eq!(
+        token_balance(&program, &basket_ata(&x, &matcher.pubkey(), &x.mint_b)),
+        50
+    );
+
+    let escrow = escrow_state(&program, &y);
+    assert_eq!(escrow.remaining_a, 250);
+    assert_eq!(escrow.remaining_b, 100);
+}
+
+#[test]
+fn test_match_escrows_not_crossing_fails() {
+    // Y only pays 1.5 B per A while X asks 2
+    let (mut program, x, y, matcher) = setup_match(150, 100);
+
+    let tx = send_match_escrows(&mut program, &x, &y, &matcher);
+    assert_escrow_error(tx, EscrowError::EscrowsDoNotCross);
+
+    assert_eq!(escrow_state(&program, &x).remaining_a, 100);
+    assert_eq!(escrow_state(&program, &y).remaining_a, 150);
+}
+
+#[test]
+fn test_match_escrows_with_fee() {
+    // Both makers pay the 1% fee in force when they made their escrows
+    let (mut program, x, y, matcher) = setup_match_with_fee(100, 200, 100);
+
+    let tx = send_match_escrows(&mut program, &x, &y, &matcher).unwrap();
+
+    assert_eq!(token_balance(&program, &x.maker_ata_b), 198);
+    assert_eq!(token_balance(&program, &y.maker_ata_b), 99);
+    assert_eq!(
+        token_balance(&program, &basket_ata(&x, &x.fee_recipient, &x.mint_a)),
+        1
+    );
+    assert_eq!(
+        token_balance(&program, &basket_ata(&x, &x.fee_recipient, &x.mint_b)),
+        2
+    );
+    assert_eq!(
+        token_balance(&program, &basket_ata(&x, &matcher.pubkey(), &x.mint_a)),
+        0
+    );
+    assert_closed(&program, &x.escrow);
+    assert_closed(&program, &y.escrow);
+
+    let events = decode_events::<EscrowsMatched>(&tx);
+    assert_eq!(events[0].fee_a, 1);
+    assert_eq!(events[0].fee_b, 2);
+    assert_eq!(events[0].surplus_a, 0);
+    assert_eq!(events[0].surplus_b, 0);
+}
+
+#[test]
+fn test_match_escrows_transfer_fee_mint() {
+    // Mint B charges a 1% transfer fee, which the matcher pays out of the surplus
+    let (mut program, test_data) = setup_token_2022(
+        &[],
+        &[MintExtension::TransferFee {
+            basis_points: 100,
+            maximum_fee: u64::MAX,
+        }],
+    );
+
+    // An exact cross leaves nothing to pay the transfer fee of maker X's 200 B with
+    let (x, y, matcher) = make_match(&mut program, &test_data, 200, 100);
+    assert_escrow_error(
+        send_match_escrows(&mut program, &x, &y, &matcher),
+        EscrowError::SpreadBelowTransferFee,
+    );
+
+    // Y pays 210 B for the same 100 A, enough to cover it
+    let y = counterparty(&test_data, &y.payer, 3);
+    send_make(&mut program, &y, 3, y.mint_b, 210, 100, None).unwrap();
+
+    let tx = send_match_escrows(&mut program, &x, &y, &matcher).unwrap();
+
+    assert_eq!(token_balance(&program, &x.maker_ata_b), 200);
+    assert_eq!(token_balance(&program, &y.maker_ata_b), 100);
+    assert_closed(&program, &x.escrow);
+    assert_closed(&program, &y.escrow);
+
+    let events = decode_events::<EscrowsMatched>(&tx);
+    assert_eq!(events[0].amount_b, 210);
+    assert_eq!(events[0].surplus_b, 7);
+    assert_eq!(
+        token_balance(&program, &basket_ata(&x, &matcher.pubkey(), &x.mint_b)),
+        6
+    );
+}
+
+#[test]
+fn test_match_escrows_nothing_fillable_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (maker_y, _, _) = setup_taker(&mut program, &test_data);
+
+    // X sells 100 A for 10 B, so the 5 A that Y wants is worth less than one unit of B
+    let x = with_seed(&test_data, 1);
+    send_make(&mut program, &x, 1, x.mint_b, 100, 10, None).unwrap();
+    let y = counterparty(&test_data, &maker_y, 2);
+    send_make(&mut program, &y, 2, y.mint_b, 1, 5, None).unwrap();
+
+    let matcher = funded_keypair(&mut program);
+    assert_escrow_error(
+        send_match_escrows(&mut program, &x, &y, &matcher),
+        EscrowError::EscrowsDoNotCross,
+    );
+}
+
+#[test]
+fn test_match_escrows_same_side_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let x = with_seed(&test_data, 1);
+    send_make(&mut program, &x, 1, x.mint_b, 100, 200, None).unwrap();
+
+    // Two escrows offering the same mint cannot be matched
+    let matcher = funded_keypair(&mut program);
+    let tx = send_match_escrows(&mut program, &x, &test_data, &matcher);
+    assert!(tx.is_err());
+}
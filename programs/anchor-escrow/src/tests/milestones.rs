@@ -0,0 +1,460 @@
+use super::*;
+
+// Derive the milestone escrow PDA and its vault for the maker's `seed`
+fn milestone_pdas(test_data: &TestData, seed: u64) -> (Pubkey, Pubkey) {
+    let escrow = Pubkey::find_program_address(
+        &[b"milestone", test_data.maker.as_ref(), &seed.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0;
+    let vault = associated_token::get_associated_token_address_with_program_id(
+        &escrow,
+        &test_data.mint_a,
+        &test_data.token_program,
+    );
+
+    (escrow, vault)
+}
+
+fn milestone_state(program: &LiteSVM, escrow: &Pubkey) -> crate::state::MilestoneEscrow {
+    let account = program.get_account(escrow).unwrap();
+    crate::state::MilestoneEscrow::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+// Build and send the "MakeMilestones" instruction for tranches of Mint A
+fn send_make_milestones(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    taker: Pubkey,
+    milestones: Vec<MilestoneTerms>,
+) -> TransactionResult {
+    let (escrow, vault) = milestone_pdas(test_data, seed);
+
+    let mut make_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MakeMilestones {
+            maker: test_data.maker,
+            mint: test_data.mint_a,
+            maker_ata: test_data.maker_ata_a,
+            escrow,
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MakeMilestones {
+            seed,
+            taker,
+            milestones,
+        }
+        .data(),
+    };
+    make_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[make_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_release_milestone(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    signer: &Keypair,
+    taker: Pubkey,
+    index: u8,
+) -> TransactionResult {
+    let (escrow, vault) = milestone_pdas(test_data, seed);
+    let taker_ata = associated_token::get_associated_token_address_with_program_id(
+        &taker,
+        &test_data.mint_a,
+        &test_data.token_program,
+    );
+
+    let mut release_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::ReleaseMilestone {
+            signer: signer.pubkey(),
+            maker: test_data.maker,
+            taker,
+            mint: test_data.mint_a,
+            taker_ata,
+            escrow,
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::ReleaseMilestone { index }.data(),
+    };
+    release_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[release_ix], Some(&signer.pubkey()));
+    let transaction = Transaction::new(&[signer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_refund_milestones(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    escrow: Pubkey,
+    taker: Pubkey,
+) -> TransactionResult {
+    let vault = associated_token::get_associated_token_address_with_program_id(
+        &escrow,
+        &test_data.mint_a,
+        &test_data.token_program,
+    );
+    let taker_ata = associated_token::get_associated_token_address_with_program_id(
+        &taker,
+        &test_data.mint_a,
+        &test_data.token_program,
+    );
+
+    let mut refund_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::RefundMilestones {
+            maker: test_data.maker,
+            taker,
+            mint: test_data.mint_a,
+            maker_ata: test_data.maker_ata_a,
+            taker_ata,
+            escrow,
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::RefundMilestones {}.data(),
+    };
+    refund_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[refund_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Milestone escrow under seed 9 paying 100, then 200 after a day, then 300 after a week
+// The first tranche has no unlock time, so only the maker can release it
+fn setup_milestones() -> (LiteSVM, TestData, Keypair) {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let taker = funded_keypair(&mut program);
+
+    let now = now(&program);
+    let milestones = vec![
+        MilestoneTerms {
+            amount: 100,
+            unlock_at: None,
+        },
+        MilestoneTerms {
+            amount: 200,
+            unlock_at: Some(now + ONE_DAY),
+        },
+        MilestoneTerms {
+            amount: 300,
+            unlock_at: Some(now + ONE_WEEK),
+        },
+    ];
+
+    let tx = send_make_milestones(&mut program, &test_data, 9, taker.pubkey(), milestones).unwrap();
+
+    let events = decode_events::<MilestoneEscrowCreated>(&tx);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].amount, 600);
+    assert_eq!(events[0].milestones, 3);
+
+    (program, test_data, taker)
+}
+
+#[test]
+fn test_milestones_full_lifecycle() {
+    let (mut program, test_data, taker) = setup_milestones();
+    let (escrow, vault) = milestone_pdas(&test_data, 9);
+    let taker_ata =
+        associated_token::get_associated_token_address(&taker.pubkey(), &test_data.mint_a);
+
+    assert_eq!(token_balance(&program, &vault), 600);
+    let escrow_data = milestone_state(&program, &escrow);
+    assert_eq!(escrow_data.taker, taker.pubkey());
+    assert_eq!(escrow_data.milestones.len(), 3);
+    assert!(escrow_data.milestones.iter().all(|m| !m.released));
+
+    // Milestone 0 has no unlock time: the taker cannot claim it, the maker approves it
+    assert_escrow_error(
+        send_release_milestone(&mut program, &test_data, 9, &taker, taker.pubkey(), 0),
+        EscrowError::MilestoneLocked,
+    );
+    let tx = send_release_milestone(
+        &mut program,
+        &test_data,
+        9,
+        &test_data.payer,
+        taker.pubkey(),
+        0,
+    )
+    .unwrap();
+    assert_eq!(token_balance(&program, &taker_ata), 100);
+    assert_eq!(token_balance(&program, &vault), 500);
+
+    let events = decode_events::<MilestoneReleased>(&tx);
+    assert_eq!(events[0].index, 0);
+    assert_eq!(events[0].amount, 100);
+    assert_eq!(events[0].released_by, test_data.maker);
+
+    // Milestone 1 unlocks after a day, then the taker can claim it without the maker
+    assert_escrow_error(
+        send_release_milestone(&mut program, &test_data, 9, &taker, taker.pubkey(), 1),
+        EscrowError::MilestoneLocked,
+    );
+    let later = now(&program) + ONE_DAY;
+    warp_to(&mut program, later);
+
+    send_release_milestone(&mut program, &test_data, 9, &taker, taker.pubkey(), 1).unwrap();
+    assert_eq!(token_balance(&program, &taker_ata), 300);
+    assert_eq!(token_balance(&program, &vault), 300);
+
+    // A tranche is only paid once
+    program.expire_blockhash();
+    assert_escrow_error(
+        send_release_milestone(&mut program, &test_data, 9, &taker, taker.pubkey(), 1),
+        EscrowError::MilestoneReleased,
+    );
+    assert_escrow_error(
+        send_release_milestone(&mut program, &test_data, 9, &taker, taker.pubkey(), 3),
+        EscrowError::InvalidMilestone,
+    );
+
+    let escrow_data = milestone_state(&program, &escrow);
+    assert!(escrow_data.milestones[0].released);
+    assert!(escrow_data.milestones[1].released);
+    assert!(!escrow_data.milestones[2].released);
+
+    // The maker approves the last milestone early, which closes the escrow
+    send_release_milestone(
+        &mut program,
+        &test_data,
+        9,
+        &test_data.payer,
+        taker.pubkey(),
+        2,
+    )
+    .unwrap();
+    assert_eq!(token_balance(&program, &taker_ata), 600);
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+}
+
+#[test]
+fn test_milestones_last_release_sweeps_excess() {
+    let (mut program, test_data, taker) = setup_milestones();
+    let (escrow, vault) = milestone_pdas(&test_data, 9);
+    let taker_ata =
+        associated_token::get_associated_token_address(&taker.pubkey(), &test_data.mint_a);
+
+    // Tokens sent straight to the vault would otherwise block closing it
+    mint_to(&mut program, &test_data, test_data.mint_a, vault, 7);
+
+    for index in 0..3 {
+        send_release_milestone(
+            &mut program,
+            &test_data,
+            9,
+            &test_data.payer,
+            taker.pubkey(),
+            index,
+        )
+        .unwrap();
+    }
+
+    assert_eq!(token_balance(&program, &taker_ata), 607);
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+}
+
+#[test]
+fn test_milestones_refund_unreleased() {
+    let (mut program, test_data, taker) = setup_milestones();
+    let (escrow, vault) = milestone_pdas(&test_data, 9);
+
+    send_release_milestone(
+        &mut program,
+        &test_data,
+        9,
+        &test_data.payer,
+        taker.pubkey(),
+        0,
+    )
+    .unwrap();
+
+    let maker_balance = token_balance(&program, &test_data.maker_ata_a);
+
+    // The two unreleased tranches are still locked, so both go back to the maker
+    let tx = send_refund_milestones(&mut program, &test_data, escrow, taker.pubkey()).unwrap();
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        maker_balance + 500
+    );
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+
+    let events = decode_events::<MilestonesRefunded>(&tx);
+    assert_eq!(events[0].amount, 500);
+    assert_eq!(events[0].released, 0);
+}
+
+#[test]
+fn test_milestones_refund_pays_unlocked_to_taker() {
+    let (mut program, test_data, taker) = setup_milestones();
+    let (escrow, vault) = milestone_pdas(&test_data, 9);
+    let taker_ata =
+        associated_token::get_associated_token_address(&taker.pubkey(), &test_data.mint_a);
+
+    // A day in, the second tranche has unlocked but nobody has released it yet
+    let later = now(&program) + ONE_DAY;
+    warp_to(&mut program, later);
+
+    let maker_balance = token_balance(&program, &test_data.maker_ata_a);
+
+    // The maker cannot claw it back: it goes to the taker, the rest to the maker
+    let tx = send_refund_milestones(&mut program, &test_data, escrow, taker.pubkey()).unwrap();
+    assert_eq!(token_balance(&program, &taker_ata), 200);
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        maker_balance + 400
+    );
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+
+    let events = decode_events::<MilestonesRefunded>(&tx);
+    assert_eq!(events[0].amount, 400);
+    assert_eq!(events[0].released, 200);
+}
+
+#[test]
+fn test_milestones_refund_by_non_maker_fails() {
+    let (mut program, test_data, taker) = setup_milestones();
+    let (escrow, _vault) = milestone_pdas(&test_data, 9);
+
+    let impostor = funded_keypair(&mut program);
+    let impostor_ata_a =
+        CreateAssociatedTokenAccount::new(&mut program, &impostor, &test_data.mint_a)
+            .owner(&impostor.pubkey())
+            .send()
+            .unwrap();
+
+    let impostor_data = TestData {
+        maker: impostor.pubkey(),
+        maker_ata_a: impostor_ata_a,
+        payer: impostor,
+        ..test_data
+    };
+
+    // The impostor cannot sign for the maker's escrow
+    assert_anchor_error(
+        send_refund_milestones(&mut program, &impostor_data, escrow, taker.pubkey()),
+        ErrorCode::ConstraintSeeds,
+    );
+}
+
+#[test]
+fn test_milestones_release_to_wrong_taker_fails() {
+    let (mut program, test_data, _taker) = setup_milestones();
+
+    assert_escrow_error(
+        send_release_milestone(
+            &mut program,
+            &test_data,
+            9,
+            &test_data.payer,
+            Pubkey::new_unique(),
+            0,
+        ),
+        EscrowError::InvalidTaker,
+    );
+}
+
+#[test]
+fn test_milestones_invalid_terms_fail() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let taker = Pubkey::new_unique();
+    let tranche = MilestoneTerms {
+        amount: 10,
+        unlock_at: None,
+    };
+
+    assert_escrow_error(
+        send_make_milestones(&mut program, &test_data, 9, taker, vec![]),
+        EscrowError::InvalidMilestoneCount,
+    );
+    assert_escrow_error(
+        send_make_milestones(&mut program, &test_data, 9, taker, vec![tranche; 9]),
+        EscrowError::InvalidMilestoneCount,
+    );
+    assert_escrow_error(
+        send_make_milestones(
+            &mut program,
+            &test_data,
+            9,
+            taker,
+            vec![
+                tranche,
+                MilestoneTerms {
+                    amount: 0,
+                    ..tranche
+                },
+            ],
+        ),
+        EscrowError::ZeroDeposit,
+    );
+    assert_escrow_error(
+        send_make_milestones(
+            &mut program,
+            &test_data,
+            9,
+            taker,
+            vec![
+                tranche,
+                MilestoneTerms {
+                    amount: u64::MAX,
+                    ..tranche
+                },
+            ],
+        ),
+        EscrowError::DepositOverflow,
+    );
+    assert_escrow_error(
+        send_make_milestones(
+            &mut program,
+            &test_data,
+            9,
+            taker,
+            vec![MilestoneTerms {
+                amount: 2_000_000_000,
+                ..tranche
+            }],
+        ),
+        EscrowError::InsufficientMakerBalance,
+    );
+
+    // The full eight tranches fit in the account
+    send_make_milestones(&mut program, &test_data, 9, taker, vec![tranche; 8]).unwrap();
+}
@@ -1,526 +1,1267 @@
-#[cfg(test)]
-mod tests {
-
-    use {
-        anchor_lang::{
-            prelude::{msg, Clock},
-            solana_program::program_pack::Pack,
-            AccountDeserialize, InstructionData, ToAccountMetas,
+#![allow(clippy::result_large_err)]
+
+use {
+    anchor_lang::{
+        event::EVENT_IX_TAG_LE,
+        prelude::{msg, Clock},
+        solana_program::{
+            bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+            hash::hashv,
+            program_pack::Pack,
         },
-        anchor_spl::{
-            associated_token::{self, spl_associated_token_account},
-            token::spl_token,
-            token::spl_token::state::Account,
+        AccountDeserialize, AnchorDeserialize, AnchorSerialize, Discriminator, InstructionData,
+        ToAccountMetas,
+    },
+    anchor_spl::{
+        associated_token::{self, spl_associated_token_account},
+        token::spl_token,
+        token::spl_token::state::Account,
+    },
+    escrow_client::{MakeBuilder, RawAccount, RefundBuilder, TakeBuilder},
+    litesvm::{
+        types::{TransactionMetadata, TransactionResult},
+        LiteSVM,
+    },
+    litesvm_token::{
+        spl_token::ID as TOKEN_PROGRAM_ID, CreateAssociatedTokenAccount, CreateMint, FreezeAccount,
+        MintTo,
+    },
+    proptest::prelude::*,
+    solana_address_lookup_table_interface::state::{AddressLookupTable, LookupTableMeta},
+    solana_compute_budget_interface::ComputeBudgetInstruction,
+    solana_instruction::{error::InstructionError, AccountMeta, Instruction},
+    solana_keypair::{keypair_from_seed, Keypair},
+    solana_message::{v0, AddressLookupTableAccount, Message, VersionedMessage},
+    solana_native_token::LAMPORTS_PER_SOL,
+    solana_packet::PACKET_DATA_SIZE,
+    solana_pubkey::Pubkey,
+    solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
+    solana_signer::Signer,
+    solana_transaction::{versioned::VersionedTransaction, Transaction},
+    solana_transaction_error::TransactionError,
+    spl_token_2022::extension::{
+        transfer_hook::TransferHook, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    std::{collections::BTreeMap, path::PathBuf},
+};
+
+use {
+    crate::{
+        error::EscrowError,
+        events::{
+            ArbitratedEscrowCreated, BasketCreated, BasketRefunded, BasketTaken, BidCancelled,
+            BidCreated, BidFilled, EscrowCranked, EscrowCreated, EscrowDisputed, EscrowRefunded,
+            EscrowResolved, EscrowTaken, EscrowUpdated, EscrowsMatched, MilestoneEscrowCreated,
+            MilestoneReleased, MilestonesRefunded, NftEscrowCreated, NftEscrowRefunded,
+            NftEscrowTaken, OracleEscrowCreated, OracleEscrowRefunded, OracleEscrowTaken,
+            VestedClaimed, VestingEscrowCreated,
         },
-        litesvm::{types::TransactionMetadata, LiteSVM},
-        litesvm_token::{
-            spl_token::ID as TOKEN_PROGRAM_ID, CreateAssociatedTokenAccount, CreateMint, MintTo,
-        },
-        solana_instruction::Instruction,
-        solana_keypair::Keypair,
-        solana_message::Message,
-        solana_native_token::LAMPORTS_PER_SOL,
-        solana_pubkey::Pubkey,
-        solana_sdk_ids::system_program::ID as SYSTEM_PROGRAM_ID,
-        solana_signer::Signer,
-        solana_transaction::Transaction,
-        std::{future, path::PathBuf, str::FromStr},
-    };
+        metadata::METADATA_PROGRAM_ID,
+        oracle::{PriceFeed, PRICE_FEED_MAGIC},
+        state::{ArbitrationStatus, BasketLeg, DutchAuction, MilestoneTerms},
+    },
+    anchor_lang::error::ErrorCode,
+};
+
+mod arbitrated;
+mod auction;
+mod basket;
+mod bid;
+mod config;
+mod crank;
+mod escrow;
+mod fuzz;
+mod matching;
+mod milestones;
+mod native;
+mod nft;
+mod oracle;
+mod scenario;
+mod token_2022;
+mod update;
+mod vesting;
+
+static PROGRAM_ID: Pubkey = crate::ID;
+
+// The program that owns the price feeds written by `set_oracle_price`
+const ORACLE_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0x0a; 32]);
+
+const ONE_DAY: i64 = 24 * 60 * 60;
+const ONE_WEEK: i64 = 7 * ONE_DAY;
+
+pub struct TestData {
+    payer: Keypair,
+    maker: Pubkey,
+    seed: u64,
+    vault: Pubkey,
+    escrow: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    maker_ata_a: Pubkey,
+    maker_ata_b: Pubkey,
+    fee_recipient: Pubkey,
+    token_program: Pubkey,
+}
 
-    static PROGRAM_ID: Pubkey = crate::ID;
-
-    pub struct TestData {
-        payer: Keypair,
-        maker: Pubkey,
-        vault: Pubkey,
-        escrow: Pubkey,
-        mint_a: Pubkey,
-        mint_b: Pubkey,
-        maker_ata_a: Pubkey,
-        maker_ata_b: Pubkey,
-    }
+// Setup function to initialize LiteSVM and create a payer keypair
+// Also loads an account from devnet into the LiteSVM environment (for testing purposes)
+fn setup() -> (LiteSVM, Keypair) {
+    // Initialize LiteSVM and payer
+    let mut program = load_program();
+    let payer = Keypair::new();
+    set_upgrade_authority(&mut program, &payer.pubkey());
+
+    // Airdrop some SOL to the payer keypair
+    program
+        .airdrop(
+            &payer.pubkey(),
+            10u64.checked_mul(LAMPORTS_PER_SOL).unwrap(),
+        )
+        .expect("Failed to airdrop SOL to payer");
+
+    // => Remove this section because it's replacing the payer account with devnet account data
+    // => This causes an issue because the lamports loaded from devnet is significantly less than the lamports airdropped to the payer
+    // => This results in insufficient funds for transactions
+    // Example on how to Load an account from devnet
+    // LiteSVM does not have access to real Solana network data since it does not have network access,
+    // so we use an RPC client to fetch account data from devnet
+    // let rpc_client = RpcClient::new("https://api.devnet.solana.com");
+    // let account_address =
+    //     Address::from_str("DRYvf71cbF2s5wgaJQvAGkghMkRcp5arvsK2w97vXhi2").unwrap();
+    // let fetched_account = rpc_client
+    //     .get_account(&account_address)
+    //     .expect("Failed to fetch account from devnet");
+
+    // // Set the fetched account in the LiteSVM environment
+    // // This allows us to simulate interactions with this account during testing
+    // program
+    //     .set_account(
+    //         payer.pubkey(),
+    //         Account {
+    //             lamports: fetched_account.lamports,
+    //             data: fetched_account.data,
+    //             owner: Pubkey::from(fetched_account.owner.to_bytes()),
+    //             executable: fetched_account.executable,
+    //             rent_epoch: fetched_account.rent_epoch,
+    //         },
+    //     )
+    //     .unwrap();
+
+    // msg!("Lamports of fetched account: {}", fetched_account.lamports);
+
+    // Return the LiteSVM instance and payer keypair
+    (program, payer)
+}
 
-    // Setup function to initialize LiteSVM and create a payer keypair
-    // Also loads an account from devnet into the LiteSVM environment (for testing purposes)
-    fn setup() -> (LiteSVM, Keypair) {
-        // Initialize LiteSVM and payer
-        let mut program = LiteSVM::new();
-        let payer = Keypair::new();
-
-        // Airdrop some SOL to the payer keypair
-        program
-            .airdrop(
-                &payer.pubkey(),
-                10u64.checked_mul(LAMPORTS_PER_SOL).unwrap(),
-            )
-            .expect("Failed to airdrop SOL to payer");
-
-        // Load program SO file
-        let so_path =
-            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/anchor_escrow.so");
-
-        let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
-
-        program.add_program(PROGRAM_ID, &program_data);
-
-        // => Remove this section because it's replacing the payer account with devnet account data
-        // => This causes an issue because the lamports loaded from devnet is significantly less than the lamports airdropped to the payer
-        // => This results in insufficient funds for transactions
-        // Example on how to Load an account from devnet
-        // LiteSVM does not have access to real Solana network data since it does not have network access,
-        // so we use an RPC client to fetch account data from devnet
-        // let rpc_client = RpcClient::new("https://api.devnet.solana.com");
-        // let account_address =
-        //     Address::from_str("DRYvf71cbF2s5wgaJQvAGkghMkRcp5arvsK2w97vXhi2").unwrap();
-        // let fetched_account = rpc_client
-        //     .get_account(&account_address)
-        //     .expect("Failed to fetch account from devnet");
-
-        // // Set the fetched account in the LiteSVM environment
-        // // This allows us to simulate interactions with this account during testing
-        // program
-        //     .set_account(
-        //         payer.pubkey(),
-        //         Account {
-        //             lamports: fetched_account.lamports,
-        //             data: fetched_account.data,
-        //             owner: Pubkey::from(fetched_account.owner.to_bytes()),
-        //             executable: fetched_account.executable,
-        //             rent_epoch: fetched_account.rent_epoch,
-        //         },
-        //     )
-        //     .unwrap();
-
-        // msg!("Lamports of fetched account: {}", fetched_account.lamports);
-
-        // Return the LiteSVM instance and payer keypair
-        (program, payer)
-    }
+// Initialize LiteSVM with the escrow program loaded
+fn load_program() -> LiteSVM {
+    let mut program = LiteSVM::new();
 
-    fn setup_with_make() -> (LiteSVM, TestData, TransactionMetadata) {
-        // Setup the test environment using the `setup` function
-        let (mut program, payer) = setup();
+    // Load program SO file
+    let so_path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy/anchor_escrow.so");
 
-        // Get the maker's public key from the payer keypair
-        let maker = payer.pubkey();
+    let program_data = std::fs::read(so_path).expect("Failed to read program SO file");
 
-        // Create two mints (Mint A and Mint B) with 6 decimal places
-        // Make the `maker` as the authority
-        // This done using litesvm-token's CreateMint utility which creates the mint in the LiteSVM environment
-        let mint_a = CreateMint::new(&mut program, &payer)
-            .decimals(6)
-            .authority(&maker)
-            .send()
-            .unwrap();
-        // msg!("Mint A: {}", mint_a);
+    program.add_program(PROGRAM_ID, &program_data);
 
-        let mint_b = CreateMint::new(&mut program, &payer)
-            .decimals(6)
-            .authority(&maker)
-            .send()
-            .unwrap();
-        // msg!("Mint B: {}", mint_b);
+    program
+}
 
-        // Create the maker's associated token account for Mint A and Mint B
-        // This is done using litesvm-token's CreateAssociatedTokenAccount utility
-        let maker_ata_a = CreateAssociatedTokenAccount::new(&mut program, &payer, &mint_a)
-            .owner(&maker)
-            .send()
-            .unwrap();
-        // msg!("Maker ATA A: {}\n", maker_ata_a);
+// Record `authority` as the upgrade authority of the escrow program
+// `add_program` deploys without a ProgramData account, so write the one the loader would
+fn set_upgrade_authority(program: &mut LiteSVM, authority: &Pubkey) {
+    let data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 0,
+        upgrade_authority_address: Some(*authority),
+    })
+    .unwrap();
+
+    program
+        .set_account(
+            program_data_pda(),
+            solana_account::Account {
+                lamports: program.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: bpf_loader_upgradeable::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+}
 
-        let maker_ata_b = CreateAssociatedTokenAccount::new(&mut program, &payer, &mint_b)
-            .owner(&maker)
-            .send()
-            .unwrap();
-        // msg!("Maker ATA B: {}\n", maker_ata_b);
+fn setup_with_make() -> (LiteSVM, TestData, TransactionMetadata) {
+    // Escrow that can be taken for a week and refunded at any time
+    let (program, test_data, tx) = setup_with_timed_make(ONE_WEEK, 0);
+
+    (program, test_data, tx.unwrap())
+}
+
+// Same as `setup_with_make`, but the escrow expires `expires_in` seconds from now
+// and cannot be refunded until `lock_period` seconds from now
+fn setup_with_timed_make(
+    expires_in: i64,
+    lock_period: i64,
+) -> (LiteSVM, TestData, TransactionResult) {
+    setup_with_make_args(10, 10, expires_in, lock_period)
+}
+
+// Same as `setup_with_timed_make`, but with custom deposit and receive amounts
+fn setup_with_make_args(
+    deposit: u64,
+    receive: u64,
+    expires_in: i64,
+    lock_period: i64,
+) -> (LiteSVM, TestData, TransactionResult) {
+    // Mints, token accounts and config of the default scenario, without the escrow
+    let mut scenario = EscrowScenario::new()
+        .deposit(deposit)
+        .receive(receive)
+        .expires_in(expires_in)
+        .lock_period(lock_period)
+        .make(false)
+        .build();
+
+    // Send the "Make" instruction and capture the result
+    let tx = scenario.send_make(0);
+    let test_data = scenario.escrow(0);
+
+    (scenario.program, test_data, tx)
+}
 
-        // Derive the PDA for the escrow account using the maker's public key and a seed value
-        let escrow = Pubkey::find_program_address(
-            &[b"escrow", maker.as_ref(), &123u64.to_le_bytes()],
-            &PROGRAM_ID,
+// Same as `setup_with_make`, but the escrow is made while the protocol charges `fee_bps`
+fn setup_with_fee(fee_bps: u16) -> (LiteSVM, TestData) {
+    let scenario = EscrowScenario::new().fee_bps(fee_bps).build();
+    let test_data = scenario.escrow(0);
+
+    (scenario.program, test_data)
+}
+
+// Keypair derived from the scenario seed and a name, identical on every run
+fn seeded_keypair(seed: u64, name: &str) -> Keypair {
+    let entropy = hashv(&[&seed.to_le_bytes(), name.as_bytes()]);
+    keypair_from_seed(entropy.as_ref()).unwrap()
+}
+
+// Create a mint with `decimals` at the address of `mint`, owned by `token_program`
+fn create_mint(
+    program: &mut LiteSVM,
+    authority: &Keypair,
+    mint: &Keypair,
+    decimals: u8,
+    token_program: &Pubkey,
+) -> Pubkey {
+    let space = spl_token::state::Mint::LEN;
+    let instructions = [
+        solana_system_interface::instruction::create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            program.minimum_balance_for_rent_exemption(space),
+            space as u64,
+            token_program,
+        ),
+        spl_token_2022::instruction::initialize_mint2(
+            token_program,
+            &mint.pubkey(),
+            &authority.pubkey(),
+            None,
+            decimals,
         )
-        .0;
-        // msg!("Escrow PDA: {}\n", escrow);
+        .unwrap(),
+    ];
+
+    let message = Message::new(&instructions, Some(&authority.pubkey()));
+    let transaction = Transaction::new(&[authority, mint], message, program.latest_blockhash());
+    program.send_transaction(transaction).unwrap();
 
-        // Derive the PDA for the vault associated token account using the escrow PDA and Mint A
-        let vault = associated_token::get_associated_token_address(&escrow, &mint_a);
-        // msg!("Vault PDA: {}\n", vault);
+    mint.pubkey()
+}
 
-        // Define program IDs for associated token program, token program, and system program
-        let associated_token_program = spl_associated_token_account::ID;
-        let token_program = TOKEN_PROGRAM_ID;
-        let system_program = SYSTEM_PROGRAM_ID;
+// A named participant of a scenario with its token accounts for both mints
+struct Actor {
+    name: String,
+    keypair: Keypair,
+    ata_a: Pubkey,
+    ata_b: Pubkey,
+}
 
-        // Mint 1,000 tokens (with 6 decimal places) of Mint A to the maker's associated token account
-        MintTo::new(&mut program, &payer, &mint_a, &maker_ata_a, 1000000000)
-            .send()
-            .unwrap();
+impl Actor {
+    fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+}
 
-        // Create the "Make" instruction to deposit tokens into the escrow
-        let make_ix = Instruction {
-            program_id: PROGRAM_ID,
-            accounts: crate::accounts::Make {
-                maker: maker,
-                mint_a: mint_a,
-                mint_b: mint_b,
-                maker_ata_a: maker_ata_a,
-                escrow: escrow,
-                vault: vault,
-                associated_token_program: associated_token_program,
-                token_program: token_program,
-                system_program: system_program,
-            }
-            .to_account_metas(None),
-            data: crate::instruction::Make {
-                deposit: 10,
-                seed: 123u64,
-                receive: 10,
-            }
-            .data(),
-        };
+// Fixture builder for escrow tests
+// Every keypair is derived from `keypair_seed` and the name of its owner, so a failing
+// scenario is reproduced with the same addresses by running it with the same seed
+#[derive(Clone)]
+struct EscrowScenario {
+    keypair_seed: u64,
+    makers: usize,
+    takers: usize,
+    maker_balance: u64,
+    taker_balance: u64,
+    escrow_seed: u64,
+    deposit: u64,
+    receive: u64,
+    expires_in: i64,
+    lock_period: i64,
+    decimals_a: u8,
+    decimals_b: u8,
+    token_program: Pubkey,
+    fee_bps: u16,
+    bounty: u64,
+    make: bool,
+}
 
-        // Create and send the transaction containing the "Make" instruction
-        let message = Message::new(&[make_ix], Some(&payer.pubkey()));
-        let recent_blockhash = program.latest_blockhash();
+impl EscrowScenario {
+    // One maker holding 1,000 tokens of Mint A who escrows 10 for 10 of Mint B,
+    // takeable for a week and refundable at any time
+    fn new() -> Self {
+        Self {
+            keypair_seed: 0,
+            makers: 1,
+            takers: 0,
+            maker_balance: 1_000_000_000,
+            taker_balance: 1_000_000_000,
+            escrow_seed: 123,
+            deposit: 10,
+            receive: 10,
+            expires_in: ONE_WEEK,
+            lock_period: 0,
+            decimals_a: 6,
+            decimals_b: 6,
+            token_program: TOKEN_PROGRAM_ID,
+            fee_bps: 0,
+            bounty: 0,
+            make: true,
+        }
+    }
 
-        let transaction = Transaction::new(&[&payer], message, recent_blockhash);
+    fn keypair_seed(mut self, keypair_seed: u64) -> Self {
+        self.keypair_seed = keypair_seed;
+        self
+    }
 
-        // Send the transaction and capture the result
-        let tx = program.send_transaction(transaction).unwrap();
+    fn makers(mut self, makers: usize) -> Self {
+        self.makers = makers;
+        self
+    }
 
-        let test_data = TestData {
-            payer,
-            maker,
-            vault,
-            escrow,
-            mint_a,
-            mint_b,
-            maker_ata_a,
-            maker_ata_b,
-        };
+    fn takers(mut self, takers: usize) -> Self {
+        self.takers = takers;
+        self
+    }
 
-        (program, test_data, tx)
+    // Mint A held by each maker before making the escrow
+    fn maker_balance(mut self, maker_balance: u64) -> Self {
+        self.maker_balance = maker_balance;
+        self
     }
 
-    #[test]
-    fn test_make() {
-        // Setup the maker and create an escrow using the "Make" instruction
-        let (program, test_data, tx) = setup_with_make();
-
-        // Extract relevant data from the test setup
-        let vault = test_data.vault;
-        let escrow = test_data.escrow;
-        let mint_a = test_data.mint_a;
-        let maker = test_data.maker;
-        let mint_b = test_data.mint_b;
-
-        // Log transaction details
-        msg!("\nMake transaction sucessful");
-        msg!("CUs Consumed: {}", tx.compute_units_consumed);
-        msg!("Tx Signature: {}", tx.signature);
-
-        // Verify the vault account and escrow account data after the "Make" instruction
-        let vault_account = program.get_account(&vault).unwrap();
-        let vault_data = spl_token::state::Account::unpack(&vault_account.data).unwrap();
-        assert_eq!(vault_data.amount, 10);
-        assert_eq!(vault_data.owner, escrow);
-        assert_eq!(vault_data.mint, mint_a);
-
-        let escrow_account = program.get_account(&escrow).unwrap();
-        let escrow_data =
-            crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap();
-        assert_eq!(escrow_data.seed, 123u64);
-        assert_eq!(escrow_data.maker, maker);
-        assert_eq!(escrow_data.mint_a, mint_a);
-        assert_eq!(escrow_data.mint_b, mint_b);
-        assert_eq!(escrow_data.receive, 10);
+    // Mint B held by each taker
+    fn taker_balance(mut self, taker_balance: u64) -> Self {
+        self.taker_balance = taker_balance;
+        self
     }
 
-    #[test]
-    fn test_take() {
-        // Setup the maker and create an escrow using the "Make" instruction
-        let (mut program, test_data, _tx) = setup_with_make();
-
-        // Extract relevant data from the test setup
-        let payer = test_data.payer;
-        let vault = test_data.vault;
-        let escrow = test_data.escrow;
-        let mint_a = test_data.mint_a;
-        let maker = test_data.maker;
-        let mint_b = test_data.mint_b;
-        let maker_ata_b = test_data.maker_ata_b;
-
-        // Create a taker keypair and airdrop some SOL to the taker for transaction fees
-        let taker = Keypair::new();
-
-        program
-            .airdrop(
-                &taker.pubkey(),
-                10u64.checked_mul(LAMPORTS_PER_SOL).unwrap(),
-            )
-            .expect("Failed to airdrop SOL to taker");
+    fn escrow_seed(mut self, escrow_seed: u64) -> Self {
+        self.escrow_seed = escrow_seed;
+        self
+    }
 
-        // Create the taker's associated token accounts for Mint A and Mint B
-        let taker_ata_a = CreateAssociatedTokenAccount::new(&mut program, &taker, &mint_a)
-            .owner(&taker.pubkey())
-            .send()
-            .unwrap();
-        // msg!(">>> Taker ATA A: {}\n\n", taker_ata_a);
+    fn deposit(mut self, deposit: u64) -> Self {
+        self.deposit = deposit;
+        self
+    }
 
-        let taker_ata_b = CreateAssociatedTokenAccount::new(&mut program, &taker, &mint_b)
-            .owner(&taker.pubkey())
-            .send()
-            .unwrap();
-        // msg!(">>> Taker ATA B: {}", taker_ata_b);
+    fn receive(mut self, receive: u64) -> Self {
+        self.receive = receive;
+        self
+    }
+
+    fn expires_in(mut self, expires_in: i64) -> Self {
+        self.expires_in = expires_in;
+        self
+    }
+
+    fn lock_period(mut self, lock_period: i64) -> Self {
+        self.lock_period = lock_period;
+        self
+    }
+
+    fn decimals_a(mut self, decimals_a: u8) -> Self {
+        self.decimals_a = decimals_a;
+        self
+    }
 
-        // Define program IDs for associated token program, token program, and system program
-        let associated_token_program = spl_associated_token_account::ID;
-        let token_program = TOKEN_PROGRAM_ID;
-        let system_program = SYSTEM_PROGRAM_ID;
+    fn decimals_b(mut self, decimals_b: u8) -> Self {
+        self.decimals_b = decimals_b;
+        self
+    }
+
+    // Token program owning both mints
+    fn token_program(mut self, token_program: Pubkey) -> Self {
+        self.token_program = token_program;
+        self
+    }
+
+    fn fee_bps(mut self, fee_bps: u16) -> Self {
+        self.fee_bps = fee_bps;
+        self
+    }
+
+    // Lamports each maker posts for whoever cranks its escrow once expired
+    fn bounty(mut self, bounty: u64) -> Self {
+        self.bounty = bounty;
+        self
+    }
 
-        // Mint 1,000 tokens (with 6 decimal places) of Mint B to the taker's associated token account
-        MintTo::new(&mut program, &payer, &mint_b, &taker_ata_b, 1000000000)
-            .send()
+    // Whether every maker makes its escrow while building
+    fn make(mut self, make: bool) -> Self {
+        self.make = make;
+        self
+    }
+
+    // Create the actors, mints, token accounts and config, then make the escrows
+    // The first maker also administers the config and both mints, like the payer of `setup`
+    fn build(self) -> Scenario {
+        msg!("Escrow scenario keypair seed: {}", self.keypair_seed);
+
+        let mut program = load_program();
+        let keypair = |name: &str| seeded_keypair(self.keypair_seed, name);
+
+        let makers = (0..self.makers)
+            .map(|i| keypair(&format!("maker {i}")))
+            .collect::<Vec<_>>();
+        let takers = (0..self.takers)
+            .map(|i| keypair(&format!("taker {i}")))
+            .collect::<Vec<_>>();
+        for actor in makers.iter().chain(&takers) {
+            program
+                .airdrop(
+                    &actor.pubkey(),
+                    10u64.checked_mul(LAMPORTS_PER_SOL).unwrap(),
+                )
+                .expect("Failed to airdrop SOL to actor");
+        }
+
+        let admin = makers[0].insecure_clone();
+        set_upgrade_authority(&mut program, &admin.pubkey());
+        let fee_recipient = keypair("fee recipient").pubkey();
+        send_initialize_config(&mut program, &admin, self.fee_bps, fee_recipient).unwrap();
+
+        let mint_a = create_mint(
+            &mut program,
+            &admin,
+            &keypair("mint a"),
+            self.decimals_a,
+            &self.token_program,
+        );
+        let mint_b = create_mint(
+            &mut program,
+            &admin,
+            &keypair("mint b"),
+            self.decimals_b,
+            &self.token_program,
+        );
+
+        let mut actor = |keypair: Keypair, name: String, mint: Pubkey, balance: u64| {
+            let [ata_a, ata_b] = [mint_a, mint_b].map(|ata_mint| {
+                CreateAssociatedTokenAccount::new(&mut program, &keypair, &ata_mint)
+                    .token_program_id(&self.token_program)
+                    .send()
+                    .unwrap()
+            });
+            let ata = if mint == mint_a { ata_a } else { ata_b };
+            let mint_ix = spl_token_2022::instruction::mint_to(
+                &self.token_program,
+                &mint,
+                &ata,
+                &admin.pubkey(),
+                &[],
+                balance,
+            )
             .unwrap();
+            send_signed(&mut program, &admin, mint_ix).unwrap();
 
-        // Create the "Take" instruction to accept and send tokens
-        let take_ix = Instruction {
-            program_id: PROGRAM_ID,
-            accounts: crate::accounts::Take {
-                taker: taker.pubkey(),
-                maker: maker,
-                mint_a: mint_a,
-                mint_b: mint_b,
-                taker_ata_a: taker_ata_a,
-                taker_ata_b: taker_ata_b,
-                maker_ata_b: maker_ata_b,
-                escrow: escrow,
-                vault: vault,
-                associated_token_program: associated_token_program,
-                token_program: token_program,
-                system_program: system_program,
+            Actor {
+                name,
+                keypair,
+                ata_a,
+                ata_b,
             }
-            .to_account_metas(None),
-            data: crate::instruction::Take {}.data(),
         };
 
-        // Create and send the transaction containing the "Take" instruction
-        let message = Message::new(&[take_ix], Some(&taker.pubkey()));
-        let recent_blockhash = program.latest_blockhash();
-
-        let transaction = Transaction::new(&[&taker], message, recent_blockhash);
-
-        // Send the transaction and capture the result
-        let tx = program.send_transaction(transaction).unwrap();
-
-        // Log transaction details
-        msg!("\n\nTake transaction successful");
-        msg!("CUs Consumed: {}", tx.compute_units_consumed);
-        msg!("Tx Signature: {}", tx.signature);
-
-        // Verify the escrow account has been closed
-        let escrow_account = program.get_account(&escrow);
-        if let Some(account) = escrow_account {
-            assert_eq!(
-                account.lamports, 0,
-                "Escrow account should have 0 lamports after closure"
-            );
-            assert!(
-                account.data.is_empty() || account.data.iter().all(|&b| b == 0),
-                "Escrow account data should be empty or zeroed"
-            );
+        let makers = makers
+            .into_iter()
+            .enumerate()
+            .map(|(i, maker)| actor(maker, format!("maker {i}"), mint_a, self.maker_balance))
+            .collect::<Vec<_>>();
+        let takers = takers
+            .into_iter()
+            .enumerate()
+            .map(|(i, taker)| actor(taker, format!("taker {i}"), mint_b, self.taker_balance))
+            .collect::<Vec<_>>();
+
+        let mut scenario = Scenario {
+            program,
+            makers,
+            takers,
+            mint_a,
+            mint_b,
+            fee_recipient,
+            config: self,
+        };
+
+        if scenario.config.make {
+            for maker in 0..scenario.makers.len() {
+                scenario.send_make(maker).unwrap();
+            }
         }
 
-        // Verify the vault account has been closed
-        let vault_account = program.get_account(&vault);
-        if let Some(account) = vault_account {
-            assert_eq!(
-                account.lamports, 0,
-                "Vault account should have 0 lamports after closure"
-            );
+        scenario
+    }
+}
+
+// Everything an `EscrowScenario` created, with makers and takers in creation order
+struct Scenario {
+    program: LiteSVM,
+    makers: Vec<Actor>,
+    takers: Vec<Actor>,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    fee_recipient: Pubkey,
+    config: EscrowScenario,
+}
+
+impl Scenario {
+    // Accounts of the escrow made by maker `maker`, for the helpers taking `TestData`
+    fn escrow(&self, maker: usize) -> TestData {
+        let actor = &self.makers[maker];
+        let escrow = escrow_pda(&actor.pubkey(), self.config.escrow_seed);
+
+        TestData {
+            payer: actor.keypair.insecure_clone(),
+            maker: actor.pubkey(),
+            seed: self.config.escrow_seed,
+            vault: associated_token::get_associated_token_address_with_program_id(
+                &escrow,
+                &self.mint_a,
+                &self.config.token_program,
+            ),
+            escrow,
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            maker_ata_a: actor.ata_a,
+            maker_ata_b: actor.ata_b,
+            fee_recipient: self.fee_recipient,
+            token_program: self.config.token_program,
         }
+    }
 
-        // Verify taker received the escrowed tokens (mint_a)
-        let taker_ata_a_account = program.get_account(&taker_ata_a).unwrap();
-        let taker_ata_a_data = Account::unpack(&taker_ata_a_account.data).unwrap();
-        assert_eq!(
-            taker_ata_a_data.amount, 10,
-            "Taker should have received 10 tokens of mint_a"
-        );
-        assert_eq!(taker_ata_a_data.owner, taker.pubkey());
-        assert_eq!(taker_ata_a_data.mint, mint_a);
+    // Build and send the "Make" instruction of maker `maker` with the scenario terms
+    fn send_make(&mut self, maker: usize) -> TransactionResult {
+        let test_data = self.escrow(maker);
+        let make_ix = make_builder(&self.program, &test_data, test_data.seed, self.mint_b)
+            .deposit(self.config.deposit)
+            .receive(self.config.receive)
+            .expiry(now(&self.program) + self.config.expires_in)
+            .lock_period(self.config.lock_period)
+            .bounty(self.config.bounty)
+            .instruction();
+        send_signed(&mut self.program, &self.makers[maker].keypair, make_ix)
     }
 
-    #[test]
-    fn test_refund() {
-        let (mut program, test_data, _tx) = setup_with_make();
-
-        let payer = test_data.payer;
-        let maker = test_data.maker;
-        let vault = test_data.vault;
-        let escrow = test_data.escrow;
-        let mint_a = test_data.mint_a;
-        let maker_ata_a = test_data.maker_ata_a;
-
-        // Define program IDs for associated token program, token program, and system program
-        let token_program = TOKEN_PROGRAM_ID;
-        let system_program = SYSTEM_PROGRAM_ID;
-
-        // Create the "Refund" instruction to accept and send tokens
-        let refund_ix = Instruction {
-            program_id: PROGRAM_ID,
-            accounts: crate::accounts::Refund {
-                maker: maker,
-                mint_a: mint_a,
-                maker_ata_a: maker_ata_a,
-                escrow: escrow,
-                vault: vault,
-                token_program: token_program,
-                system_program: system_program,
-            }
-            .to_account_metas(None),
-            data: crate::instruction::Refund {}.data(),
-        };
+    // Build and send the "Take" instruction from taker `taker` for the escrow of maker `maker`
+    fn send_take(&mut self, taker: usize, maker: usize) -> TransactionResult {
+        let test_data = self.escrow(maker);
+        send_take(&mut self.program, &test_data, &self.takers[taker].keypair)
+    }
 
-        // Create and send the transaction containing the "Refund" instruction
-        let message = Message::new(&[refund_ix], Some(&maker));
-        let recent_blockhash = program.latest_blockhash();
+    fn balance(&self, token_account: &Pubkey) -> u64 {
+        token_balance(&self.program, token_account)
+    }
+}
 
-        let transaction = Transaction::new(&[&payer], message, recent_blockhash);
+// Derive the PDA that signs the self-CPI used by `emit_cpi!`
+fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &PROGRAM_ID).0
+}
 
-        // Send the transaction and capture the result
-        let tx = program.send_transaction(transaction).unwrap();
+// Derive the PDA of the global protocol config
+fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &PROGRAM_ID).0
+}
 
-        // Log transaction details
-        msg!("\n\nRefund transaction successful");
-        msg!("CUs Consumed: {}", tx.compute_units_consumed);
-        msg!("Tx Signature: {}", tx.signature);
+// Derive the escrow PDA of `maker` for `seed`
+fn escrow_pda(maker: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"escrow", maker.as_ref(), &seed.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0
+}
 
-        // 1. Verify maker received their tokens back
-        let maker_ata_a_account = program.get_account(&maker_ata_a).unwrap();
-        let maker_ata_a_data =
-            spl_token::state::Account::unpack(&maker_ata_a_account.data).unwrap();
-        assert_eq!(
-            maker_ata_a_data.amount, 1000000000,
-            "Maker should have all their tokens back (original 1B)"
-        );
+// Derive the ProgramData account holding the upgrade authority of the escrow program
+fn program_data_pda() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
 
-        // 2. Verify escrow account has been closed
-        let escrow_account = program.get_account(&escrow);
-        if let Some(account) = escrow_account {
-            assert_eq!(
-                account.lamports, 0,
-                "Escrow account should have 0 lamports after refund"
-            );
-            assert!(
-                account.data.is_empty() || account.data.iter().all(|&b| b == 0),
-                "Escrow account data should be empty or zeroed"
-            );
+// Fetched account of `mint`, as the builders take it to pick its token program
+fn mint_account(program: &LiteSVM, mint: &Pubkey) -> solana_account::Account {
+    program
+        .get_account(mint)
+        .expect("mint account should exist")
+}
+
+fn raw_account<'a>(address: &Pubkey, account: &'a solana_account::Account) -> RawAccount<'a> {
+    RawAccount {
+        address: *address,
+        owner: account.owner,
+        lamports: account.lamports,
+        data: &account.data,
+    }
+}
+
+// Builder of the "Make" instruction depositing Mint A of `test_data` for `mint_b`,
+// expiring in a week, with the transfer hook accounts of Mint A
+fn make_builder(program: &LiteSVM, test_data: &TestData, seed: u64, mint_b: Pubkey) -> MakeBuilder {
+    let mint_a_account = mint_account(program, &test_data.mint_a);
+    let mint_b_account = mint_account(program, &mint_b);
+
+    MakeBuilder::new(
+        test_data.maker,
+        seed,
+        raw_account(&test_data.mint_a, &mint_a_account),
+        raw_account(&mint_b, &mint_b_account),
+    )
+    .unwrap()
+    .expiry(now(program) + ONE_WEEK)
+    .remaining_accounts(transfer_hook_accounts(program, &[test_data.mint_a]))
+}
+
+// Build and send the "InitializeConfig" instruction with `admin` as the config admin
+fn send_initialize_config(
+    program: &mut LiteSVM,
+    admin: &Keypair,
+    fee_bps: u16,
+    fee_recipient: Pubkey,
+) -> TransactionResult {
+    let initialize_config_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::InitializeConfig {
+            admin: admin.pubkey(),
+            program_data: program_data_pda(),
+            config: config_pda(),
+            system_program: SYSTEM_PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::InitializeConfig {
+            fee_bps,
+            fee_recipient,
+            oracle_program: ORACLE_PROGRAM_ID,
         }
+        .data(),
+    };
 
-        // 3. Verify vault account has been closed
-        let vault_account = program.get_account(&vault);
-        if let Some(account) = vault_account {
-            assert_eq!(
-                account.lamports, 0,
-                "Vault account should have 0 lamports after refund"
-            );
+    let message = Message::new(&[initialize_config_ix], Some(&admin.pubkey()));
+    let transaction = Transaction::new(&[admin], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "UpdateConfig" instruction signed by `admin`
+fn send_update_config(
+    program: &mut LiteSVM,
+    admin: &Keypair,
+    fee_bps: u16,
+    fee_recipient: Pubkey,
+    new_admin: Pubkey,
+) -> TransactionResult {
+    send_update_config_with_oracle(
+        program,
+        admin,
+        fee_bps,
+        fee_recipient,
+        ORACLE_PROGRAM_ID,
+        new_admin,
+    )
+}
+
+// Same as `send_update_config`, but also allow-listing `oracle_program`
+fn send_update_config_with_oracle(
+    program: &mut LiteSVM,
+    admin: &Keypair,
+    fee_bps: u16,
+    fee_recipient: Pubkey,
+    oracle_program: Pubkey,
+    new_admin: Pubkey,
+) -> TransactionResult {
+    let update_config_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::UpdateConfig {
+            admin: admin.pubkey(),
+            config: config_pda(),
+        }
+        .to_account_metas(None),
+        data: crate::instruction::UpdateConfig {
+            fee_bps,
+            fee_recipient,
+            oracle_program,
+            admin: new_admin,
         }
+        .data(),
+    };
+
+    let message = Message::new(&[update_config_ix], Some(&admin.pubkey()));
+    let transaction = Transaction::new(&[admin], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Current on-chain unix timestamp of the LiteSVM instance
+fn now(program: &LiteSVM) -> i64 {
+    program.get_sysvar::<Clock>().unix_timestamp
+}
+
+// Set the Clock sysvar to `timestamp` and expire the blockhash so that
+// an identical transaction can be sent again afterwards
+fn warp_to(program: &mut LiteSVM, timestamp: i64) {
+    let mut clock = program.get_sysvar::<Clock>();
+    clock.unix_timestamp = timestamp;
+    program.set_sysvar::<Clock>(&clock);
+    program.expire_blockhash();
+}
+
+// Create a funded taker holding 1,000 tokens of Mint B
+fn setup_taker(program: &mut LiteSVM, test_data: &TestData) -> (Keypair, Pubkey, Pubkey) {
+    let taker = Keypair::new();
+
+    program
+        .airdrop(
+            &taker.pubkey(),
+            10u64.checked_mul(LAMPORTS_PER_SOL).unwrap(),
+        )
+        .expect("Failed to airdrop SOL to taker");
+
+    let taker_ata_a = CreateAssociatedTokenAccount::new(program, &taker, &test_data.mint_a)
+        .owner(&taker.pubkey())
+        .token_program_id(&test_data.token_program)
+        .send()
+        .unwrap();
+
+    let taker_ata_b = CreateAssociatedTokenAccount::new(program, &taker, &test_data.mint_b)
+        .owner(&taker.pubkey())
+        .token_program_id(&test_data.token_program)
+        .send()
+        .unwrap();
+
+    mint_to(
+        program,
+        test_data,
+        test_data.mint_b,
+        taker_ata_b,
+        1000000000,
+    );
+
+    (taker, taker_ata_a, taker_ata_b)
+}
+
+// Builder of the "Take" instruction from `taker` for the escrow in `test_data`,
+// with the transfer hook accounts of both mints
+// A native SOL leg is passed as `None`, which builds "TakeNative" or "TakeForNative" instead
+fn take_builder(
+    program: &LiteSVM,
+    test_data: &TestData,
+    taker: &Keypair,
+    native_a: bool,
+    native_b: bool,
+) -> TakeBuilder {
+    let mint_a_account = mint_account(program, &test_data.mint_a);
+    let mint_b_account = mint_account(program, &test_data.mint_b);
+
+    TakeBuilder::new(
+        taker.pubkey(),
+        test_data.maker,
+        test_data.seed,
+        (!native_a).then(|| raw_account(&test_data.mint_a, &mint_a_account)),
+        (!native_b).then(|| raw_account(&test_data.mint_b, &mint_b_account)),
+        test_data.fee_recipient,
+    )
+    .unwrap()
+    .fills_escrow(true)
+    .remaining_accounts(transfer_hook_accounts(
+        program,
+        &[test_data.mint_a, test_data.mint_b],
+    ))
+}
+
+// Build the "Take" instruction from `taker` for the escrow in `test_data`,
+// or "TakePartial" for `amount_b` of Mint B
+fn take_ix(
+    program: &LiteSVM,
+    test_data: &TestData,
+    taker: &Keypair,
+    amount_b: Option<u64>,
+) -> Instruction {
+    let take = take_builder(program, test_data, taker, false, false);
+
+    match amount_b {
+        Some(amount_b) => take.amount_b(amount_b).instruction(),
+        None => take.instruction(),
+    }
+}
+
+// Build and send the "Take" instruction for the escrow in `test_data`
+fn send_take(program: &mut LiteSVM, test_data: &TestData, taker: &Keypair) -> TransactionResult {
+    let take_ix = take_ix(program, test_data, taker, None);
+    send_signed(program, taker, take_ix)
+}
+
+// Build and send the "TakePartial" instruction for `amount_b` of Mint B
+fn send_take_partial(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    taker: &Keypair,
+    amount_b: u64,
+) -> TransactionResult {
+    let take_ix = take_ix(program, test_data, taker, Some(amount_b));
+    send_signed(program, taker, take_ix)
+}
+
+// Builder of the "Refund" instruction for the escrow and vault in `test_data` as given,
+// so that tests can point either elsewhere, with the transfer hook accounts of Mint A
+// An escrow holding native SOL is refunded with "RefundNative" instead
+fn refund_builder(program: &LiteSVM, test_data: &TestData, native: bool) -> RefundBuilder {
+    let mint_a_account = mint_account(program, &test_data.mint_a);
+
+    RefundBuilder::new(
+        test_data.maker,
+        test_data.seed,
+        (!native).then(|| raw_account(&test_data.mint_a, &mint_a_account)),
+    )
+    .unwrap()
+    .with_escrow(test_data.escrow)
+    .with_vault(test_data.vault)
+    .remaining_accounts(transfer_hook_accounts(program, &[test_data.mint_a]))
+}
+
+// Build the "Refund" instruction for the escrow in `test_data`
+fn refund_ix(program: &LiteSVM, test_data: &TestData) -> Instruction {
+    refund_builder(program, test_data, false).instruction()
+}
+
+// Build and send the "Refund" instruction for the escrow in `test_data`
+fn send_refund(program: &mut LiteSVM, test_data: &TestData) -> TransactionResult {
+    let refund_ix = refund_ix(program, test_data);
+    send_signed(program, &test_data.payer, refund_ix)
+}
+
+// Send `ix` in its own transaction, signed and paid for by `signer`
+fn send_signed(program: &mut LiteSVM, signer: &Keypair, ix: Instruction) -> TransactionResult {
+    let message = Message::new(&[ix], Some(&signer.pubkey()));
+    let transaction = Transaction::new(&[signer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Assert that the transaction failed with the given custom program error
+fn assert_escrow_error(result: TransactionResult, expected: EscrowError) {
+    assert_custom_error(result, expected.into());
+}
+
+// Assert that the transaction failed with the given Anchor framework error
+fn assert_anchor_error(result: TransactionResult, expected: ErrorCode) {
+    assert_custom_error(result, expected.into());
+}
+
+fn assert_custom_error(result: TransactionResult, code: u32) {
+    assert_custom_error_at(result, 0, code);
+}
+
+// Same as `assert_custom_error`, for the instruction at `index` in the transaction
+fn assert_custom_error_at(result: TransactionResult, index: u8, code: u32) {
+    let err = result.expect_err("Transaction should have failed").err;
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(index, InstructionError::Custom(code)),
+    );
+}
+
+// Read the escrow account state
+fn escrow_state(program: &LiteSVM, test_data: &TestData) -> crate::state::Escrow {
+    let escrow_account = program.get_account(&test_data.escrow).unwrap();
+    crate::state::Escrow::try_deserialize(&mut escrow_account.data.as_ref()).unwrap()
+}
+
+// Read the token balance of an SPL Token account
+fn token_balance(program: &LiteSVM, token_account: &Pubkey) -> u64 {
+    let account = program.get_account(token_account).unwrap();
+    StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+// Assert that an account has been closed (or never existed)
+fn assert_closed(program: &LiteSVM, address: &Pubkey) {
+    if let Some(account) = program.get_account(address) {
+        assert_eq!(account.lamports, 0, "Account {} should be closed", address);
+    }
+}
+
+// Point `test_data` at the escrow and vault of another seed from the same maker
+fn with_seed(test_data: &TestData, seed: u64) -> TestData {
+    let escrow = escrow_pda(&test_data.maker, seed);
+
+    TestData {
+        payer: test_data.payer.insecure_clone(),
+        seed,
+        escrow,
+        vault: basket_ata(test_data, &escrow, &test_data.mint_a),
+        ..*test_data
     }
+}
 
-    #[test]
-    fn test_refund_after_delay() {
-        let (mut program, test_data, _tx) = setup_with_make();
-
-        let payer = test_data.payer;
-        let maker = test_data.maker;
-        let vault = test_data.vault;
-        let escrow = test_data.escrow;
-        let mint_a = test_data.mint_a;
-        let maker_ata_a = test_data.maker_ata_a;
-
-        // Define program IDs for associated token program, token program, and system program
-        let token_program = TOKEN_PROGRAM_ID;
-        let system_program = SYSTEM_PROGRAM_ID;
-
-        // Time travel BEFORE attempting refund
-        let mut clock = program.get_sysvar::<Clock>();
-        let current_timestamp = clock.unix_timestamp;
-        let five_days = 5i64
-            .checked_mul(24)
-            .unwrap()
-            .checked_mul(60)
-            .unwrap()
-            .checked_mul(60)
+// Build and send a second "Make" instruction from the maker in `test_data`
+fn send_make(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    mint_b: Pubkey,
+    deposit: u64,
+    receive: u64,
+    allowed_taker: Option<Pubkey>,
+) -> TransactionResult {
+    let make_ix = make_builder(program, test_data, seed, mint_b)
+        .deposit(deposit)
+        .receive(receive)
+        .allowed_taker(allowed_taker)
+        .instruction();
+
+    send_signed(program, &test_data.payer, make_ix)
+}
+
+// Decode every event of type `T` emitted through `emit_cpi!` in the transaction
+fn decode_events<T: AnchorDeserialize + Discriminator>(tx: &TransactionMetadata) -> Vec<T> {
+    tx.inner_instructions
+        .iter()
+        .flatten()
+        .filter_map(|inner| {
+            let data = inner.instruction.data.strip_prefix(EVENT_IX_TAG_LE)?;
+            let mut data = data.strip_prefix(T::DISCRIMINATOR)?;
+            T::deserialize(&mut data).ok()
+        })
+        .collect()
+}
+
+// Read the protocol config state
+fn config_state(program: &LiteSVM) -> crate::state::Config {
+    let config_account = program.get_account(&config_pda()).unwrap();
+    crate::state::Config::try_deserialize(&mut config_account.data.as_ref()).unwrap()
+}
+
+// Lamport balance of an account, zero if it does not exist
+fn lamports(program: &LiteSVM, address: &Pubkey) -> u64 {
+    program
+        .get_account(address)
+        .map_or(0, |account| account.lamports)
+}
+
+// Rent-exempt minimum of an escrow account
+fn escrow_rent(program: &LiteSVM) -> u64 {
+    program.minimum_balance_for_rent_exemption(
+        8 + <crate::state::Escrow as anchor_lang::Space>::INIT_SPACE,
+    )
+}
+
+// Mint `amount` to `token_account` with the payer as mint authority
+// Unlike litesvm-token's `MintTo`, this also works for mints with Token-2022 extensions
+fn mint_to(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    mint: Pubkey,
+    token_account: Pubkey,
+    amount: u64,
+) {
+    let mint_ix = spl_token_2022::instruction::mint_to(
+        &test_data.token_program,
+        &mint,
+        &token_account,
+        &test_data.payer.pubkey(),
+        &[],
+        amount,
+    )
+    .unwrap();
+
+    let message = Message::new(&[mint_ix], Some(&test_data.payer.pubkey()));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction).unwrap();
+}
+
+// Token-2022 mint extensions used by the test matrix
+enum MintExtension {
+    TransferFee { basis_points: u16, maximum_fee: u64 },
+    InterestBearing { rate: i16 },
+    NonTransferable,
+    TransferHook,
+}
+
+// Create a Token-2022 mint with 6 decimals and the given extensions, owned by the payer
+fn create_mint_2022(
+    program: &mut LiteSVM,
+    payer: &Keypair,
+    extensions: &[MintExtension],
+) -> Pubkey {
+    let mint = Keypair::new();
+    let token_program = spl_token_2022::ID;
+
+    let extension_types = extensions
+        .iter()
+        .map(|extension| match extension {
+            MintExtension::TransferFee { .. } => ExtensionType::TransferFeeConfig,
+            MintExtension::InterestBearing { .. } => ExtensionType::InterestBearingConfig,
+            MintExtension::NonTransferable => ExtensionType::NonTransferable,
+            MintExtension::TransferHook => ExtensionType::TransferHook,
+        })
+        .collect::<Vec<_>>();
+    let space =
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&extension_types)
             .unwrap();
-        let time_jump = current_timestamp.checked_add(five_days).unwrap();
-
-        // Update timestamp to 5 days in the future
-        clock.unix_timestamp = time_jump;
-        program.set_sysvar::<Clock>(&clock);
-
-        msg!("\n\nTime travelled {} seconds into the future", five_days);
-
-        // Create the "Refund" instruction
-        let refund_ix = Instruction {
-            program_id: PROGRAM_ID,
-            accounts: crate::accounts::Refund {
-                maker: maker,
-                mint_a: mint_a,
-                maker_ata_a: maker_ata_a,
-                escrow: escrow,
-                vault: vault,
-                token_program: token_program,
-                system_program: system_program,
+
+    let mut instructions = vec![solana_system_interface::instruction::create_account(
+        &payer.pubkey(),
+        &mint.pubkey(),
+        program.minimum_balance_for_rent_exemption(space),
+        space as u64,
+        &token_program,
+    )];
+
+    for extension in extensions {
+        instructions.push(match *extension {
+            MintExtension::TransferFee {
+                basis_points,
+                maximum_fee,
+            } => spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config(
+                &token_program,
+                &mint.pubkey(),
+                None,
+                None,
+                basis_points,
+                maximum_fee,
+            )
+            .unwrap(),
+            MintExtension::InterestBearing { rate } => {
+                spl_token_2022::extension::interest_bearing_mint::instruction::initialize(
+                    &token_program,
+                    &mint.pubkey(),
+                    None,
+                    rate,
+                )
+                .unwrap()
             }
-            .to_account_metas(None),
-            data: crate::instruction::Refund {}.data(),
-        };
+            MintExtension::NonTransferable => {
+                spl_token_2022::instruction::initialize_non_transferable_mint(
+                    &token_program,
+                    &mint.pubkey(),
+                )
+                .unwrap()
+            }
+            MintExtension::TransferHook => {
+                spl_token_2022::extension::transfer_hook::instruction::initialize(
+                    &token_program,
+                    &mint.pubkey(),
+                    None,
+                    Some(transfer_hook_counter::ID),
+                )
+                .unwrap()
+            }
+        });
+    }
 
-        // Create and send the transaction containing the "Refund" instruction
-        let message = Message::new(&[refund_ix], Some(&maker));
-        let recent_blockhash = program.latest_blockhash();
+    instructions.push(
+        spl_token_2022::instruction::initialize_mint2(
+            &token_program,
+            &mint.pubkey(),
+            &payer.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+    );
+
+    let message = Message::new(&instructions, Some(&payer.pubkey()));
+    let transaction = Transaction::new(&[payer, &mint], message, program.latest_blockhash());
+    program.send_transaction(transaction).unwrap();
+
+    if extensions
+        .iter()
+        .any(|extension| matches!(extension, MintExtension::TransferHook))
+    {
+        initialize_transfer_hook(program, payer, mint.pubkey());
+    }
 
-        let transaction = Transaction::new(&[&payer], message, recent_blockhash);
+    mint.pubkey()
+}
 
-        // Send the transaction and capture the result
-        let tx = program.send_transaction(transaction).unwrap();
+// Load the transfer hook test program from `programs/transfer-hook-counter`
+fn load_transfer_hook(program: &mut LiteSVM) {
+    let so_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../../target/deploy/transfer_hook_counter.so");
 
-        // Log transaction details
-        msg!("\n\nRefund after delay transaction successful");
-        msg!("CUs Consumed: {}", tx.compute_units_consumed);
-        msg!("Tx Signature: {}", tx.signature);
+    let program_data = std::fs::read(so_path).expect("Failed to read transfer hook SO file");
 
-        // 1. Verify maker received their tokens back
-        let maker_ata_a_account = program.get_account(&maker_ata_a).unwrap();
-        let maker_ata_a_data =
-            spl_token::state::Account::unpack(&maker_ata_a_account.data).unwrap();
-        assert_eq!(
-            maker_ata_a_data.amount, 1000000000,
-            "Maker should have all their tokens back after time-locked refund"
-        );
+    program.add_program(transfer_hook_counter::ID, &program_data);
+}
+
+// Transfer hook PDAs of `mint`: the extra account metas list and the invocation counter
+fn transfer_hook_pdas(mint: &Pubkey) -> (Pubkey, Pubkey) {
+    let extra_account_meta_list = Pubkey::find_program_address(
+        &[b"extra-account-metas", mint.as_ref()],
+        &transfer_hook_counter::ID,
+    )
+    .0;
+    let counter =
+        Pubkey::find_program_address(&[b"counter", mint.as_ref()], &transfer_hook_counter::ID).0;
+
+    (extra_account_meta_list, counter)
+}
 
-        // 2. Verify escrow account has been closed
-        let escrow_account = program.get_account(&escrow);
-        if let Some(account) = escrow_account {
-            assert_eq!(
-                account.lamports, 0,
-                "Escrow account should have 0 lamports after time-locked refund"
-            );
-            assert!(
-                account.data.is_empty() || account.data.iter().all(|&b| b == 0),
-                "Escrow account data should be empty or zeroed after time-locked refund"
-            );
+// Load the hook program if needed and create the hook accounts for `mint`
+fn initialize_transfer_hook(program: &mut LiteSVM, payer: &Keypair, mint: Pubkey) {
+    if program.get_account(&transfer_hook_counter::ID).is_none() {
+        load_transfer_hook(program);
+    }
+
+    let (extra_account_meta_list, counter) = transfer_hook_pdas(&mint);
+
+    let initialize_ix = Instruction {
+        program_id: transfer_hook_counter::ID,
+        accounts: transfer_hook_counter::accounts::InitializeExtraAccountMetaList {
+            payer: payer.pubkey(),
+            extra_account_meta_list,
+            mint,
+            counter,
+            system_program: SYSTEM_PROGRAM_ID,
         }
+        .to_account_metas(None),
+        data: transfer_hook_counter::instruction::InitializeExtraAccountMetaList {}.data(),
+    };
+
+    let message = Message::new(&[initialize_ix], Some(&payer.pubkey()));
+    let transaction = Transaction::new(&[payer], message, program.latest_blockhash());
+    program.send_transaction(transaction).unwrap();
+}
+
+// Remaining accounts a transfer of any of `mints` needs for their transfer hooks
+fn transfer_hook_accounts(program: &LiteSVM, mints: &[Pubkey]) -> Vec<AccountMeta> {
+    let mut accounts = vec![];
 
-        // 3. Verify vault account has been closed
-        let vault_account = program.get_account(&vault);
-        if let Some(account) = vault_account {
-            assert_eq!(
-                account.lamports, 0,
-                "Vault account should have 0 lamports after time-locked refund"
-            );
+    for mint in mints {
+        let Some(account) = program.get_account(mint) else {
+            continue;
+        };
+        let Ok(state) = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&account.data)
+        else {
+            continue;
+        };
+        if state.get_extension::<TransferHook>().is_err() {
+            continue;
         }
+
+        let (extra_account_meta_list, counter) = transfer_hook_pdas(mint);
+        accounts.extend([
+            AccountMeta::new_readonly(transfer_hook_counter::ID, false),
+            AccountMeta::new_readonly(extra_account_meta_list, false),
+            AccountMeta::new(counter, false),
+        ]);
     }
+
+    accounts
+}
+
+// Number of times the transfer hook has run for `mint`
+fn transfer_hook_count(program: &LiteSVM, mint: &Pubkey) -> u64 {
+    let counter = transfer_hook_pdas(mint).1;
+    let account = program.get_account(&counter).unwrap();
+    transfer_hook_counter::Counter::try_deserialize(&mut account.data.as_ref())
+        .unwrap()
+        .count
+}
+
+// Like `setup_with_make`, but with Token-2022 mints and without making the escrow
+// The maker holds 1,000 tokens of Mint A and seed 123 is free for `send_make`
+fn setup_token_2022(
+    mint_a_extensions: &[MintExtension],
+    mint_b_extensions: &[MintExtension],
+) -> (LiteSVM, TestData) {
+    let (mut program, payer) = setup();
+    let maker = payer.pubkey();
+    let token_program = spl_token_2022::ID;
+
+    let fee_recipient = Pubkey::new_unique();
+    send_initialize_config(&mut program, &payer, 0, fee_recipient).unwrap();
+
+    let mint_a = create_mint_2022(&mut program, &payer, mint_a_extensions);
+    let mint_b = create_mint_2022(&mut program, &payer, mint_b_extensions);
+
+    let maker_ata_a = CreateAssociatedTokenAccount::new(&mut program, &payer, &mint_a)
+        .owner(&maker)
+        .token_program_id(&token_program)
+        .send()
+        .unwrap();
+
+    let maker_ata_b = CreateAssociatedTokenAccount::new(&mut program, &payer, &mint_b)
+        .owner(&maker)
+        .token_program_id(&token_program)
+        .send()
+        .unwrap();
+
+    let test_data = with_seed(
+        &TestData {
+            payer,
+            maker,
+            seed: 0,
+            vault: Pubkey::default(),
+            escrow: Pubkey::default(),
+            mint_a,
+            mint_b,
+            maker_ata_a,
+            maker_ata_b,
+            fee_recipient,
+            token_program,
+        },
+        123,
+    );
+
+    mint_to(
+        &mut program,
+        &test_data,
+        mint_a,
+        test_data.maker_ata_a,
+        1000000000,
+    );
+
+    (program, test_data)
+}
+
+// Funded keypair for a taker or arbiter
+fn funded_keypair(program: &mut LiteSVM) -> Keypair {
+    let keypair = Keypair::new();
+    program
+        .airdrop(&keypair.pubkey(), LAMPORTS_PER_SOL)
+        .expect("Failed to airdrop SOL");
+
+    keypair
+}
+
+fn basket_ata(test_data: &TestData, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    associated_token::get_associated_token_address_with_program_id(
+        owner,
+        mint,
+        &test_data.token_program,
+    )
 }
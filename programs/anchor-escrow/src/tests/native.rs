@@ -0,0 +1,364 @@
+use super::*;
+
+// Build and send the "MakeNative" instruction, offering `deposit` lamports for Mint B
+// and posting `bounty` lamports for whoever cranks the escrow once expired
+pub(super) fn send_make_native(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    deposit: u64,
+    receive: u64,
+    bounty: u64,
+) -> TransactionResult {
+    let escrow = with_seed(test_data, seed).escrow;
+
+    let make_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MakeNative {
+            maker: test_data.maker,
+            mint_b: test_data.mint_b,
+            config: config_pda(),
+            escrow,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MakeNative {
+            seed,
+            deposit,
+            receive,
+            expiry: now(program) + ONE_WEEK,
+            lock_period: 0,
+            allowed_taker: None,
+            bounty,
+        }
+        .data(),
+    };
+
+    let message = Message::new(&[make_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "TakeNative" instruction for `amount_b` of Mint B
+fn send_take_native(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    taker: &Keypair,
+    amount_b: u64,
+) -> TransactionResult {
+    let take_ix = take_builder(program, test_data, taker, true, false)
+        .amount_b(amount_b)
+        .instruction();
+
+    let message = Message::new(&[take_ix], Some(&taker.pubkey()));
+    let transaction = Transaction::new(&[taker], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "RefundNative" instruction for the escrow in `test_data`
+fn send_refund_native(program: &mut LiteSVM, test_data: &TestData) -> TransactionResult {
+    let refund_ix = refund_builder(program, test_data, true).instruction();
+
+    let message = Message::new(&[refund_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "MakeForNative" instruction, offering `deposit` of Mint A for lamports
+fn send_make_for_native(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    deposit: u64,
+    receive: u64,
+) -> TransactionResult {
+    let seeded = with_seed(test_data, seed);
+
+    let make_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MakeForNative {
+            maker: test_data.maker,
+            mint_a: test_data.mint_a,
+            maker_ata_a: test_data.maker_ata_a,
+            config: config_pda(),
+            escrow: seeded.escrow,
+            vault: seeded.vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MakeForNative {
+            seed,
+            deposit,
+            receive,
+            expiry: now(program) + ONE_WEEK,
+            lock_period: 0,
+            allowed_taker: None,
+            bounty: 0,
+        }
+        .data(),
+    };
+
+    let message = Message::new(&[make_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "TakeForNative" instruction, paying `amount_b` lamports
+fn send_take_for_native(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    taker: &Keypair,
+    amount_b: u64,
+) -> TransactionResult {
+    let take_ix = take_builder(program, test_data, taker, false, true)
+        .amount_b(amount_b)
+        .instruction();
+
+    let message = Message::new(&[take_ix], Some(&taker.pubkey()));
+    let transaction = Transaction::new(&[taker], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+#[test]
+fn test_native_sol_for_token() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _taker_ata_a, _taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    // The maker offers 2 SOL for 500 of Mint B
+    send_make_native(&mut program, &test_data, 456, 2 * LAMPORTS_PER_SOL, 500, 0).unwrap();
+    let native = with_seed(&test_data, 456);
+
+    // The escrow PDA itself holds the lamports on top of its rent, and there is no vault
+    let escrow_data = escrow_state(&program, &native);
+    assert_eq!(escrow_data.mint_a, crate::state::Escrow::NATIVE_SOL);
+    assert_eq!(escrow_data.mint_b, test_data.mint_b);
+    assert_eq!(
+        lamports(&program, &native.escrow),
+        escrow_rent(&program) + 2 * LAMPORTS_PER_SOL
+    );
+    assert!(program.get_account(&native.vault).is_none());
+
+    let maker_lamports = lamports(&program, &test_data.maker);
+    let taker_lamports = lamports(&program, &taker.pubkey());
+
+    send_take_native(&mut program, &native, &taker, 500).unwrap();
+
+    // The taker receives 2 SOL, less its transaction fee and the fee account it created
+    let fee_account_rent = program.minimum_balance_for_rent_exemption(Account::LEN);
+    assert_eq!(
+        lamports(&program, &taker.pubkey()),
+        taker_lamports + 2 * LAMPORTS_PER_SOL - 5000 - fee_account_rent
+    );
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 500);
+
+    // The maker gets the escrow rent back
+    assert_closed(&program, &native.escrow);
+    assert_eq!(
+        lamports(&program, &test_data.maker),
+        maker_lamports + escrow_rent(&program)
+    );
+}
+
+#[test]
+fn test_native_sol_partial_takes_keep_escrow_rent_exempt() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _taker_ata_a, _taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    // 10 lamports for 3 of Mint B does not divide evenly
+    send_make_native(&mut program, &test_data, 456, 10, 3, 0).unwrap();
+    let native = with_seed(&test_data, 456);
+    let rent = escrow_rent(&program);
+
+    // Each fill pays out 3 lamports and never touches the escrow's rent
+    send_take_native(&mut program, &native, &taker, 1).unwrap();
+    assert_eq!(lamports(&program, &native.escrow), rent + 7);
+
+    program.expire_blockhash();
+    send_take_native(&mut program, &native, &taker, 1).unwrap();
+    assert_eq!(lamports(&program, &native.escrow), rent + 4);
+
+    // The final fill closes the escrow, returning rent and the 1 lamport of dust to the maker
+    let maker_lamports = lamports(&program, &test_data.maker);
+    program.expire_blockhash();
+    send_take_native(&mut program, &native, &taker, 1).unwrap();
+
+    assert_closed(&program, &native.escrow);
+    assert_eq!(
+        lamports(&program, &test_data.maker),
+        maker_lamports + rent + 1
+    );
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 3);
+}
+
+#[test]
+fn test_refund_native() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    let maker_lamports = lamports(&program, &test_data.maker);
+    send_make_native(&mut program, &test_data, 456, LAMPORTS_PER_SOL, 500, 0).unwrap();
+    let native = with_seed(&test_data, 456);
+
+    // Refunding returns the deposit and the rent, leaving only the two transaction fees spent
+    let tx = send_refund_native(&mut program, &native).unwrap();
+
+    assert_closed(&program, &native.escrow);
+    assert_eq!(
+        lamports(&program, &test_data.maker),
+        maker_lamports - 2 * 5000
+    );
+    assert_eq!(
+        decode_events::<EscrowRefunded>(&tx)[0].amount_a,
+        LAMPORTS_PER_SOL
+    );
+
+    // A token escrow cannot be closed through the native refund, which would strand its vault
+    assert_escrow_error(
+        send_refund_native(&mut program, &test_data),
+        EscrowError::InvalidMint,
+    );
+}
+
+#[test]
+fn test_make_native_insufficient_balance_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    assert_escrow_error(
+        send_make_native(
+            &mut program,
+            &test_data,
+            456,
+            100 * LAMPORTS_PER_SOL,
+            500,
+            0,
+        ),
+        EscrowError::InsufficientMakerBalance,
+    );
+}
+
+#[test]
+fn test_take_native_on_token_escrow_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _taker_ata_a, _taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    // Seed 123 holds Mint A in a vault, not lamports
+    assert_escrow_error(
+        send_take_native(&mut program, &test_data, &taker, 10),
+        EscrowError::InvalidMint,
+    );
+}
+
+#[test]
+fn test_token_for_native_sol() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, taker_ata_a, _taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    // The maker offers 100 of Mint A for 1 SOL
+    send_make_for_native(&mut program, &test_data, 456, 100, LAMPORTS_PER_SOL).unwrap();
+    let for_native = with_seed(&test_data, 456);
+
+    let escrow_data = escrow_state(&program, &for_native);
+    assert_eq!(escrow_data.mint_a, test_data.mint_a);
+    assert_eq!(escrow_data.mint_b, crate::state::Escrow::NATIVE_SOL);
+    assert_eq!(token_balance(&program, &for_native.vault), 100);
+
+    let maker_lamports = lamports(&program, &test_data.maker);
+    let vault_rent = lamports(&program, &for_native.vault);
+
+    let tx = send_take_for_native(&mut program, &for_native, &taker, LAMPORTS_PER_SOL).unwrap();
+
+    // The maker is paid 1 SOL directly and gets the vault and escrow rent back
+    assert_eq!(token_balance(&program, &taker_ata_a), 100);
+    assert_eq!(
+        lamports(&program, &test_data.maker),
+        maker_lamports + LAMPORTS_PER_SOL + vault_rent + escrow_rent(&program)
+    );
+    assert_closed(&program, &for_native.vault);
+    assert_closed(&program, &for_native.escrow);
+
+    let events = decode_events::<EscrowTaken>(&tx);
+    assert_eq!(events[0].amount_a, 100);
+    assert_eq!(events[0].amount_b, LAMPORTS_PER_SOL);
+    assert_eq!(events[0].mint_b, crate::state::Escrow::NATIVE_SOL);
+}
+
+#[test]
+fn test_token_for_native_sol_with_fee() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, taker_ata_a, _taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    send_update_config(
+        &mut program,
+        &test_data.payer,
+        250,
+        test_data.fee_recipient,
+        test_data.maker,
+    )
+    .unwrap();
+
+    send_make_for_native(&mut program, &test_data, 456, 100, LAMPORTS_PER_SOL).unwrap();
+    let for_native = with_seed(&test_data, 456);
+
+    // A partial fill of 0.4 SOL pays a 2.5% fee to the fee recipient in lamports
+    let maker_lamports = lamports(&program, &test_data.maker);
+    send_take_for_native(&mut program, &for_native, &taker, LAMPORTS_PER_SOL * 4 / 10).unwrap();
+
+    assert_eq!(token_balance(&program, &taker_ata_a), 40);
+    assert_eq!(lamports(&program, &test_data.fee_recipient), 10_000_000);
+    assert_eq!(
+        lamports(&program, &test_data.maker),
+        maker_lamports + 390_000_000
+    );
+    assert_eq!(escrow_state(&program, &for_native).remaining_b, 600_000_000);
+}
+
+#[test]
+fn test_take_for_native_rent_exemption() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _taker_ata_a, _taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    send_update_config(
+        &mut program,
+        &test_data.payer,
+        250,
+        test_data.fee_recipient,
+        test_data.maker,
+    )
+    .unwrap();
+
+    // 100 of Mint A for just 1,000 lamports
+    send_make_for_native(&mut program, &test_data, 456, 100, 1000).unwrap();
+    let for_native = with_seed(&test_data, 456);
+
+    // A 25 lamport fee cannot fund the empty fee recipient account
+    assert_eq!(lamports(&program, &test_data.fee_recipient), 0);
+    assert_escrow_error(
+        send_take_for_native(&mut program, &for_native, &taker, 1000),
+        EscrowError::RecipientNotRentExempt,
+    );
+
+    // Once the fee recipient is funded the fee is accepted
+    program
+        .airdrop(&test_data.fee_recipient, LAMPORTS_PER_SOL)
+        .unwrap();
+
+    // Nor can 500 lamports fund a maker whose wallet has been emptied
+    let mut maker_account = program.get_account(&test_data.maker).unwrap();
+    maker_account.lamports = 0;
+    program.set_account(test_data.maker, maker_account).unwrap();
+
+    assert_escrow_error(
+        send_take_for_native(&mut program, &for_native, &taker, 500),
+        EscrowError::RecipientNotRentExempt,
+    );
+}
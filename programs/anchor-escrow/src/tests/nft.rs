@@ -0,0 +1,719 @@
+use super::*;
+
+fn nft_pda(test_data: &TestData, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"nft", test_data.maker.as_ref(), &seed.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+fn metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+fn master_edition_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            b"edition",
+        ],
+        &METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+// Create an NFT held by `owner`, with the mint authority locked in its master edition
+// The maker stays the freeze authority
+fn create_nft(program: &mut LiteSVM, test_data: &TestData, owner: &Pubkey) -> Pubkey {
+    let mint = create_nft_mint(program, test_data, owner);
+    set_mint_authority(program, test_data, mint, Some(&master_edition_pda(&mint)));
+
+    mint
+}
+
+// Create a 0-decimal mint with its single token held by `owner`
+// The maker is both the mint and the freeze authority
+fn create_nft_mint(program: &mut LiteSVM, test_data: &TestData, owner: &Pubkey) -> Pubkey {
+    let mint = Keypair::new();
+    let space = spl_token_2022::state::Mint::LEN;
+
+    let instructions = [
+        solana_system_interface::instruction::create_account(
+            &test_data.payer.pubkey(),
+            &mint.pubkey(),
+            program.minimum_balance_for_rent_exemption(space),
+            space as u64,
+            &test_data.token_program,
+        ),
+        spl_token_2022::instruction::initialize_mint2(
+            &test_data.token_program,
+            &mint.pubkey(),
+            &test_data.maker,
+            Some(&test_data.maker),
+            0,
+        )
+        .unwrap(),
+    ];
+
+    let message = Message::new(&instructions, Some(&test_data.payer.pubkey()));
+    let transaction = Transaction::new(
+        &[&test_data.payer, &mint],
+        message,
+        program.latest_blockhash(),
+    );
+    program.send_transaction(transaction).unwrap();
+
+    let mint = mint.pubkey();
+    let ata = CreateAssociatedTokenAccount::new(program, &test_data.payer, &mint)
+        .owner(owner)
+        .token_program_id(&test_data.token_program)
+        .send()
+        .unwrap();
+    mint_to(program, test_data, mint, ata, 1);
+
+    mint
+}
+
+// Hand the mint authority of `mint` from the maker to `new_authority`
+fn set_mint_authority(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    mint: Pubkey,
+    new_authority: Option<&Pubkey>,
+) {
+    let set_authority_ix = spl_token_2022::instruction::set_authority(
+        &test_data.token_program,
+        &mint,
+        new_authority,
+        spl_token_2022::instruction::AuthorityType::MintTokens,
+        &test_data.payer.pubkey(),
+        &[],
+    )
+    .unwrap();
+
+    let message = Message::new(&[set_authority_ix], Some(&test_data.payer.pubkey()));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction).unwrap();
+}
+
+// Write a Metaplex metadata account for `mint` at `address`, owned by the metadata program
+// Only the leading fields read by the escrow are laid out, followed by zeroed padding
+fn write_metadata(
+    program: &mut LiteSVM,
+    address: Pubkey,
+    mint: &Pubkey,
+    seller_fee_basis_points: u16,
+    creators: &[(Pubkey, u8)],
+) {
+    let mut data = vec![4];
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(mint.as_ref());
+    for field in ["Escrow NFT", "ENFT", "https://example.com/nft.json"] {
+        data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    data.extend_from_slice(&seller_fee_basis_points.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(&(creators.len() as u32).to_le_bytes());
+    for (creator, share) in creators {
+        data.extend_from_slice(creator.as_ref());
+        data.push(1);
+        data.push(*share);
+    }
+    data.extend_from_slice(&[0; 32]);
+
+    program
+        .set_account(
+            address,
+            solana_account::Account {
+                lamports: program.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: METADATA_PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+}
+
+// Build and send the "MakeNft" instruction offering the NFT `mint_a` for `receive` of `mint_b`
+// `metadata` is the metadata account passed for `mint_a`, normally its `metadata_pda`
+fn send_make_nft(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    receive: u64,
+    metadata: Pubkey,
+) -> TransactionResult {
+    let escrow = nft_pda(test_data, seed);
+
+    let make_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MakeNft {
+            maker: test_data.maker,
+            mint_a,
+            mint_b,
+            maker_ata_a: basket_ata(test_data, &test_data.maker, &mint_a),
+            metadata,
+            config: config_pda(),
+            escrow,
+            vault: basket_ata(test_data, &escrow, &mint_a),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MakeNft { seed, receive }.data(),
+    };
+
+    let message = Message::new(&[make_ix], Some(&test_data.payer.pubkey()));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "TakeNft" instruction, passing `[creator, creator_ata]` for each creator
+fn send_take_nft(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    (mint_a, mint_b): (Pubkey, Pubkey),
+    taker: &Keypair,
+    creators: &[Pubkey],
+) -> TransactionResult {
+    let escrow = nft_pda(test_data, seed);
+
+    let mut take_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::TakeNft {
+            taker: taker.pubkey(),
+            maker: test_data.maker,
+            mint_a,
+            mint_b,
+            taker_ata_a: basket_ata(test_data, &taker.pubkey(), &mint_a),
+            taker_ata_b: basket_ata(test_data, &taker.pubkey(), &mint_b),
+            maker_ata_b: basket_ata(test_data, &test_data.maker, &mint_b),
+            config: config_pda(),
+            fee_recipient: test_data.fee_recipient,
+            fee_recipient_ata_b: basket_ata(test_data, &test_data.fee_recipient, &mint_b),
+            escrow,
+            vault: basket_ata(test_data, &escrow, &mint_a),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::TakeNft {}.data(),
+    };
+    for creator in creators {
+        take_ix
+            .accounts
+            .push(AccountMeta::new_readonly(*creator, false));
+        take_ix.accounts.push(AccountMeta::new(
+            basket_ata(test_data, creator, &mint_b),
+            false,
+        ));
+    }
+
+    let message = Message::new(&[take_ix], Some(&taker.pubkey()));
+    let transaction = Transaction::new(&[taker], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Build and send the "RefundNft" instruction for the NFT escrow at `seed`
+fn send_refund_nft(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    mint_a: Pubkey,
+) -> TransactionResult {
+    let escrow = nft_pda(test_data, seed);
+
+    let refund_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::RefundNft {
+            maker: test_data.maker,
+            mint_a,
+            maker_ata_a: basket_ata(test_data, &test_data.maker, &mint_a),
+            escrow,
+            vault: basket_ata(test_data, &escrow, &mint_a),
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::RefundNft {}.data(),
+    };
+
+    let message = Message::new(&[refund_ix], Some(&test_data.payer.pubkey()));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+#[test]
+fn test_nft_for_fungible_with_royalties() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let nft = create_nft(&mut program, &test_data, &test_data.maker);
+
+    // 5% royalty split 60/40, plus a 1% protocol fee
+    let creators = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let metadata = metadata_pda(&nft);
+    write_metadata(
+        &mut program,
+        metadata,
+        &nft,
+        500,
+        &[(creators[0], 60), (creators[1], 40)],
+    );
+    send_update_config(
+        &mut program,
+        &test_data.payer,
+        100,
+        test_data.fee_recipient,
+        test_data.maker,
+    )
+    .unwrap();
+
+    let tx = send_make_nft(
+        &mut program,
+        &test_data,
+        7,
+        nft,
+        test_data.mint_b,
+        1000,
+        metadata,
+    )
+    .unwrap();
+
+    let escrow = nft_pda(&test_data, 7);
+    let vault = basket_ata(&test_data, &escrow, &nft);
+    assert_eq!(token_balance(&program, &vault), 1);
+    assert_eq!(
+        token_balance(&program, &basket_ata(&test_data, &test_data.maker, &nft)),
+        0
+    );
+
+    let events = decode_events::<NftEscrowCreated>(&tx);
+    assert_eq!(events[0].receive, 1000);
+    assert_eq!(events[0].royalty_bps, 500);
+
+    let (taker, _, taker_ata_b) = setup_taker(&mut program, &test_data);
+    let tx = send_take_nft(
+        &mut program,
+        &test_data,
+        7,
+        (nft, test_data.mint_b),
+        &taker,
+        &creators,
+    )
+    .unwrap();
+
+    // 1000 paid: 10 fee, 30 and 20 royalty, 940 to the maker
+    assert_eq!(token_balance(&program, &taker_ata_b), 1000000000 - 1000);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 940);
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &test_data.fee_recipient, &test_data.mint_b)
+        ),
+        10
+    );
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &creators[0], &test_data.mint_b)
+        ),
+        30
+    );
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &creators[1], &test_data.mint_b)
+        ),
+        20
+    );
+    assert_eq!(
+        token_balance(&program, &basket_ata(&test_data, &taker.pubkey(), &nft)),
+        1
+    );
+    assert_closed(&program, &escrow);
+    assert_closed(&program, &vault);
+
+    let events = decode_events::<NftEscrowTaken>(&tx);
+    assert_eq!(events[0].taker, taker.pubkey());
+    assert_eq!(events[0].amount_b, 1000);
+    assert_eq!(events[0].fee, 10);
+    assert_eq!(events[0].royalty, 50);
+}
+
+#[test]
+fn test_nft_for_nft() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let taker = funded_keypair(&mut program);
+    let nft_a = create_nft(&mut program, &test_data, &test_data.maker);
+    let nft_b = create_nft(&mut program, &test_data, &taker.pubkey());
+
+    // An NFT is priced at its single token
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            nft_a,
+            nft_b,
+            2,
+            metadata_pda(&nft_a),
+        ),
+        EscrowError::InvalidNftPrice,
+    );
+
+    send_make_nft(
+        &mut program,
+        &test_data,
+        7,
+        nft_a,
+        nft_b,
+        1,
+        metadata_pda(&nft_a),
+    )
+    .unwrap();
+    send_take_nft(&mut program, &test_data, 7, (nft_a, nft_b), &taker, &[]).unwrap();
+
+    assert_eq!(
+        token_balance(&program, &basket_ata(&test_data, &taker.pubkey(), &nft_a)),
+        1
+    );
+    assert_eq!(
+        token_balance(&program, &basket_ata(&test_data, &test_data.maker, &nft_b)),
+        1
+    );
+    assert_eq!(
+        token_balance(&program, &basket_ata(&test_data, &taker.pubkey(), &nft_b)),
+        0
+    );
+    assert_closed(&program, &nft_pda(&test_data, 7));
+}
+
+#[test]
+fn test_token_2022_nft_for_token() {
+    let (mut program, test_data) = setup_token_2022(&[], &[]);
+    let nft = create_nft(&mut program, &test_data, &test_data.maker);
+
+    send_make_nft(
+        &mut program,
+        &test_data,
+        7,
+        nft,
+        test_data.mint_b,
+        500,
+        metadata_pda(&nft),
+    )
+    .unwrap();
+
+    let (taker, _, taker_ata_b) = setup_taker(&mut program, &test_data);
+    send_take_nft(
+        &mut program,
+        &test_data,
+        7,
+        (nft, test_data.mint_b),
+        &taker,
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(token_balance(&program, &taker_ata_b), 1000000000 - 500);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 500);
+    assert_eq!(
+        token_balance(&program, &basket_ata(&test_data, &taker.pubkey(), &nft)),
+        1
+    );
+    assert_closed(&program, &nft_pda(&test_data, 7));
+}
+
+#[test]
+fn test_refund_nft() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let nft = create_nft(&mut program, &test_data, &test_data.maker);
+
+    send_make_nft(
+        &mut program,
+        &test_data,
+        7,
+        nft,
+        test_data.mint_b,
+        1000,
+        metadata_pda(&nft),
+    )
+    .unwrap();
+    let tx = send_refund_nft(&mut program, &test_data, 7, nft).unwrap();
+
+    let escrow = nft_pda(&test_data, 7);
+    assert_eq!(
+        token_balance(&program, &basket_ata(&test_data, &test_data.maker, &nft)),
+        1
+    );
+    assert_closed(&program, &escrow);
+    assert_closed(&program, &basket_ata(&test_data, &escrow, &nft));
+
+    let events = decode_events::<NftEscrowRefunded>(&tx);
+    assert_eq!(events[0].mint_a, nft);
+}
+
+#[test]
+fn test_make_nft_non_nft_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    // A fungible mint with decimals
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            test_data.mint_a,
+            test_data.mint_b,
+            1000,
+            metadata_pda(&test_data.mint_a),
+        ),
+        EscrowError::NotAnNft,
+    );
+
+    // A 0-decimal mint with more than one token in supply
+    let edition = create_nft_mint(&mut program, &test_data, &test_data.maker);
+    program.expire_blockhash();
+    mint_to(
+        &mut program,
+        &test_data,
+        edition,
+        basket_ata(&test_data, &test_data.maker, &edition),
+        1,
+    );
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            edition,
+            test_data.mint_b,
+            1000,
+            metadata_pda(&edition),
+        ),
+        EscrowError::NotAnNft,
+    );
+}
+
+#[test]
+fn test_make_nft_mint_authority() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    // A single token whose mint authority can still mint more is not an NFT
+    let mintable = create_nft_mint(&mut program, &test_data, &test_data.maker);
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            mintable,
+            test_data.mint_b,
+            1000,
+            metadata_pda(&mintable),
+        ),
+        EscrowError::NotAnNft,
+    );
+
+    // Nor is one whose authority was handed to an account other than the master edition
+    set_mint_authority(
+        &mut program,
+        &test_data,
+        mintable,
+        Some(&Pubkey::new_unique()),
+    );
+    program.expire_blockhash();
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            mintable,
+            test_data.mint_b,
+            1000,
+            metadata_pda(&mintable),
+        ),
+        EscrowError::NotAnNft,
+    );
+
+    // Once the mint authority is revoked the supply is fixed
+    let revoked = create_nft_mint(&mut program, &test_data, &test_data.maker);
+    set_mint_authority(&mut program, &test_data, revoked, None);
+    program.expire_blockhash();
+    send_make_nft(
+        &mut program,
+        &test_data,
+        7,
+        revoked,
+        test_data.mint_b,
+        1000,
+        metadata_pda(&revoked),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_make_nft_frozen_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let nft = create_nft(&mut program, &test_data, &test_data.maker);
+
+    FreezeAccount::new(&mut program, &test_data.payer, &nft)
+        .send()
+        .unwrap();
+
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            nft,
+            test_data.mint_b,
+            1000,
+            metadata_pda(&nft),
+        ),
+        EscrowError::NftFrozen,
+    );
+}
+
+#[test]
+fn test_make_nft_invalid_metadata_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let nft = create_nft(&mut program, &test_data, &test_data.maker);
+    let other = create_nft(&mut program, &test_data, &test_data.maker);
+    let creators = [(Pubkey::new_unique(), 100)];
+
+    // Metadata of another mint
+    write_metadata(&mut program, metadata_pda(&other), &other, 500, &creators);
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            nft,
+            test_data.mint_b,
+            1000,
+            metadata_pda(&other),
+        ),
+        EscrowError::InvalidNftMetadata,
+    );
+
+    // Metadata at the right address that claims another mint
+    write_metadata(&mut program, metadata_pda(&nft), &other, 500, &creators);
+    program.expire_blockhash();
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            nft,
+            test_data.mint_b,
+            1000,
+            metadata_pda(&nft),
+        ),
+        EscrowError::InvalidNftMetadata,
+    );
+
+    // Creator shares that do not add up to 100
+    write_metadata(
+        &mut program,
+        metadata_pda(&nft),
+        &nft,
+        500,
+        &[(Pubkey::new_unique(), 60)],
+    );
+    program.expire_blockhash();
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            nft,
+            test_data.mint_b,
+            1000,
+            metadata_pda(&nft),
+        ),
+        EscrowError::InvalidNftMetadata,
+    );
+
+    // The royalty cannot be skipped by passing an empty account in place of the metadata
+    write_metadata(&mut program, metadata_pda(&nft), &nft, 500, &creators);
+    program.expire_blockhash();
+    assert_escrow_error(
+        send_make_nft(
+            &mut program,
+            &test_data,
+            7,
+            nft,
+            test_data.mint_b,
+            1000,
+            Pubkey::new_unique(),
+        ),
+        EscrowError::InvalidNftMetadata,
+    );
+}
+
+#[test]
+fn test_take_nft_wrong_creators_fail() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let nft = create_nft(&mut program, &test_data, &test_data.maker);
+
+    let creators = [Pubkey::new_unique(), Pubkey::new_unique()];
+    write_metadata(
+        &mut program,
+        metadata_pda(&nft),
+        &nft,
+        500,
+        &[(creators[0], 60), (creators[1], 40)],
+    );
+    send_make_nft(
+        &mut program,
+        &test_data,
+        7,
+        nft,
+        test_data.mint_b,
+        1000,
+        metadata_pda(&nft),
+    )
+    .unwrap();
+
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+    let mints = (nft, test_data.mint_b);
+
+    // Missing, reordered and substituted creators are all rejected
+    let cases = [
+        vec![creators[0]],
+        vec![creators[1], creators[0]],
+        vec![creators[0], Pubkey::new_unique()],
+    ];
+    for creators in cases {
+        program.expire_blockhash();
+        assert_escrow_error(
+            send_take_nft(&mut program, &test_data, 7, mints, &taker, &creators),
+            EscrowError::InvalidCreator,
+        );
+    }
+
+    send_take_nft(&mut program, &test_data, 7, mints, &taker, &creators).unwrap();
+}
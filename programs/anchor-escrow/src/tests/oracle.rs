@@ -0,0 +1,382 @@
+use super::*;
+
+fn oracle_escrow_pda(test_data: &TestData, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"oracle", test_data.maker.as_ref(), &seed.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0
+}
+
+// Write a price feed account at `feed`, as the oracle would publish it
+fn set_oracle_price(program: &mut LiteSVM, feed: Pubkey, price: i64, expo: i32, publish_time: i64) {
+    let mut data = vec![];
+    PriceFeed {
+        magic: PRICE_FEED_MAGIC,
+        price,
+        expo,
+        publish_time,
+    }
+    .serialize(&mut data)
+    .unwrap();
+
+    program
+        .set_account(
+            feed,
+            solana_account::Account {
+                lamports: program.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: ORACLE_PROGRAM_ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+}
+
+// Build and send the "MakeOracle" instruction depositing `deposit` of Mint A
+fn send_make_oracle(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    oracle: Pubkey,
+    deposit: u64,
+    max_slippage_bps: u16,
+    max_staleness: i64,
+) -> TransactionResult {
+    let escrow = oracle_escrow_pda(test_data, seed);
+
+    let make_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MakeOracle {
+            maker: test_data.maker,
+            mint_a: test_data.mint_a,
+            mint_b: test_data.mint_b,
+            maker_ata_a: test_data.maker_ata_a,
+            oracle,
+            config: config_pda(),
+            escrow,
+            vault: basket_ata(test_data, &escrow, &test_data.mint_a),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MakeOracle {
+            seed,
+            deposit,
+            max_slippage_bps,
+            max_staleness,
+        }
+        .data(),
+    };
+
+    let message = Message::new(&[make_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_take_oracle(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    oracle: Pubkey,
+    taker: &Keypair,
+    amount_b: u64,
+) -> TransactionResult {
+    let escrow = oracle_escrow_pda(test_data, seed);
+
+    let take_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::TakeOracle {
+            taker: taker.pubkey(),
+            maker: test_data.maker,
+            mint_a: test_data.mint_a,
+            mint_b: test_data.mint_b,
+            taker_ata_a: basket_ata(test_data, &taker.pubkey(), &test_data.mint_a),
+            taker_ata_b: basket_ata(test_data, &taker.pubkey(), &test_data.mint_b),
+            maker_ata_b: test_data.maker_ata_b,
+            config: config_pda(),
+            fee_recipient: test_data.fee_recipient,
+            fee_recipient_ata_b: basket_ata(test_data, &test_data.fee_recipient, &test_data.mint_b),
+            oracle,
+            escrow,
+            vault: basket_ata(test_data, &escrow, &test_data.mint_a),
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::TakeOracle { amount_b }.data(),
+    };
+
+    let message = Message::new(&[take_ix], Some(&taker.pubkey()));
+    let transaction = Transaction::new(&[taker], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_refund_oracle(program: &mut LiteSVM, test_data: &TestData, seed: u64) -> TransactionResult {
+    let escrow = oracle_escrow_pda(test_data, seed);
+
+    let refund_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::RefundOracle {
+            maker: test_data.maker,
+            mint_a: test_data.mint_a,
+            maker_ata_a: test_data.maker_ata_a,
+            escrow,
+            vault: basket_ata(test_data, &escrow, &test_data.mint_a),
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::RefundOracle {}.data(),
+    };
+
+    let message = Message::new(&[refund_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+// Maker sells 1 token of Mint A at the feed price, currently 2.50 Mint B, accepting up to 5% less,
+// on prices up to a minute old
+fn setup_oracle() -> (LiteSVM, TestData, Pubkey, Keypair) {
+    setup_oracle_with_fee(0)
+}
+
+// Same as `setup_oracle`, but the escrow is made while the protocol charges `fee_bps`
+fn setup_oracle_with_fee(fee_bps: u16) -> (LiteSVM, TestData, Pubkey, Keypair) {
+    let (mut program, test_data) = setup_with_fee(fee_bps);
+    let feed = Pubkey::new_unique();
+    let published = now(&program);
+    set_oracle_price(&mut program, feed, 250, -2, published);
+
+    send_make_oracle(&mut program, &test_data, 7, feed, 1_000_000, 500, 60).unwrap();
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+
+    (program, test_data, feed, taker)
+}
+
+#[test]
+fn test_oracle_make() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let feed = Pubkey::new_unique();
+    let published = now(&program);
+    set_oracle_price(&mut program, feed, 250, -2, published);
+
+    let tx = send_make_oracle(&mut program, &test_data, 7, feed, 1_000_000, 500, 60).unwrap();
+
+    let escrow = oracle_escrow_pda(&test_data, 7);
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &escrow, &test_data.mint_a)
+        ),
+        1_000_000
+    );
+
+    let events = decode_events::<OracleEscrowCreated>(&tx);
+    assert_eq!(events[0].oracle, feed);
+    assert_eq!(events[0].oracle_program, ORACLE_PROGRAM_ID);
+    assert_eq!(events[0].max_slippage_bps, 500);
+}
+
+#[test]
+fn test_oracle_take_at_feed_price() {
+    // Charge a 1% fee
+    let (mut program, test_data, feed, taker) = setup_oracle_with_fee(100);
+
+    // The price rises to 2.60 after the escrow is made, and the taker pays it in full
+    let published = now(&program);
+    set_oracle_price(&mut program, feed, 260, -2, published);
+
+    let tx = send_take_oracle(&mut program, &test_data, 7, feed, &taker, 2_600_000).unwrap();
+
+    let taker_ata_b = basket_ata(&test_data, &taker.pubkey(), &test_data.mint_b);
+    assert_eq!(
+        token_balance(&program, &taker_ata_b),
+        1000000000 - 2_600_000
+    );
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 2_574_000);
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &test_data.fee_recipient, &test_data.mint_b)
+        ),
+        26_000
+    );
+    assert_eq!(
+        token_balance(
+            &program,
+            &basket_ata(&test_data, &taker.pubkey(), &test_data.mint_a)
+        ),
+        1_000_000
+    );
+    assert_closed(&program, &oracle_escrow_pda(&test_data, 7));
+
+    let events = decode_events::<OracleEscrowTaken>(&tx);
+    assert_eq!(events[0].price, 260);
+    assert_eq!(events[0].expo, -2);
+    assert_eq!(events[0].amount_a, 1_000_000);
+    assert_eq!(events[0].amount_b, 2_600_000);
+    assert_eq!(events[0].fee, 26_000);
+}
+
+#[test]
+fn test_oracle_stale_price_fails() {
+    let (mut program, test_data, feed, taker) = setup_oracle();
+
+    let later = now(&program) + 61;
+    warp_to(&mut program, later);
+
+    assert_escrow_error(
+        send_take_oracle(&mut program, &test_data, 7, feed, &taker, 2_500_000),
+        EscrowError::StaleOraclePrice,
+    );
+
+    // Nor can a new escrow be made off the stale price
+    assert_escrow_error(
+        send_make_oracle(&mut program, &test_data, 8, feed, 1_000_000, 500, 60),
+        EscrowError::StaleOraclePrice,
+    );
+
+    // Once the oracle publishes again the escrow can be taken
+    set_oracle_price(&mut program, feed, 250, -2, later);
+    program.expire_blockhash();
+    send_take_oracle(&mut program, &test_data, 7, feed, &taker, 2_500_000).unwrap();
+}
+
+#[test]
+fn test_oracle_payment_below_slippage_fails() {
+    let (mut program, test_data, feed, taker) = setup_oracle();
+    let published = now(&program);
+
+    // The price is read at take time, however far it moved since the escrow was made
+    set_oracle_price(&mut program, feed, 500, -2, published);
+
+    let cases = [
+        (500, -2, 4_749_999, EscrowError::PaymentBelowOraclePrice),
+        (50, -1, 4_749_999, EscrowError::PaymentBelowOraclePrice),
+        (0, -2, 4_750_000, EscrowError::InvalidOraclePrice),
+        (-500, -2, 4_750_000, EscrowError::InvalidOraclePrice),
+    ];
+
+    for (price, expo, amount_b, expected) in cases {
+        set_oracle_price(&mut program, feed, price, expo, published);
+        program.expire_blockhash();
+        assert_escrow_error(
+            send_take_oracle(&mut program, &test_data, 7, feed, &taker, amount_b),
+            expected,
+        );
+    }
+
+    // 5% below 5.00 is the least the maker accepts, and the taker pays exactly what it signed
+    set_oracle_price(&mut program, feed, 500, -2, published);
+    program.expire_blockhash();
+    let tx = send_take_oracle(&mut program, &test_data, 7, feed, &taker, 4_750_000).unwrap();
+
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 4_750_000);
+    let events = decode_events::<OracleEscrowTaken>(&tx);
+    assert_eq!(events[0].price, 500);
+    assert_eq!(events[0].amount_b, 4_750_000);
+}
+
+#[test]
+fn test_oracle_wrong_feed_fails() {
+    let (mut program, test_data, _feed, taker) = setup_oracle();
+
+    // A feed other than the one pinned by the maker
+    let other = Pubkey::new_unique();
+    let published = now(&program);
+    set_oracle_price(&mut program, other, 100, -2, published);
+    assert_escrow_error(
+        send_take_oracle(&mut program, &test_data, 7, other, &taker, 1_000_000),
+        EscrowError::InvalidOracle,
+    );
+
+    // An account that is not a price feed
+    assert_escrow_error(
+        send_make_oracle(
+            &mut program,
+            &test_data,
+            8,
+            test_data.maker_ata_b,
+            1_000_000,
+            500,
+            60,
+        ),
+        EscrowError::InvalidOracle,
+    );
+}
+
+#[test]
+fn test_oracle_program_update_keeps_open_escrows() {
+    let (mut program, test_data, feed, taker) = setup_oracle();
+
+    // The admin moves to a new oracle program while the escrow is open
+    let new_oracle_program = Pubkey::new_unique();
+    send_update_config_with_oracle(
+        &mut program,
+        &test_data.payer,
+        0,
+        test_data.fee_recipient,
+        new_oracle_program,
+        test_data.payer.pubkey(),
+    )
+    .unwrap();
+
+    // New escrows only accept feeds of the new program
+    assert_escrow_error(
+        send_make_oracle(&mut program, &test_data, 8, feed, 1_000_000, 500, 60),
+        EscrowError::InvalidOracle,
+    );
+
+    // The open escrow is still priced off the feed of the program it was made with
+    send_take_oracle(&mut program, &test_data, 7, feed, &taker, 2_500_000).unwrap();
+    assert_closed(&program, &oracle_escrow_pda(&test_data, 7));
+}
+
+#[test]
+fn test_oracle_feed_from_other_program_fails() {
+    let (mut program, test_data, feed, taker) = setup_oracle();
+
+    // Same layout and magic, but written by a program other than the allow-listed oracle
+    let mut account = program.get_account(&feed).unwrap();
+    account.owner = Pubkey::new_unique();
+    program.set_account(feed, account).unwrap();
+
+    assert_escrow_error(
+        send_take_oracle(&mut program, &test_data, 7, feed, &taker, 2_500_000),
+        EscrowError::InvalidOracle,
+    );
+    assert_escrow_error(
+        send_make_oracle(&mut program, &test_data, 8, feed, 1_000_000, 500, 60),
+        EscrowError::InvalidOracle,
+    );
+}
+
+#[test]
+fn test_oracle_refund() {
+    let (mut program, test_data, _feed, _taker) = setup_oracle();
+
+    let tx = send_refund_oracle(&mut program, &test_data, 7).unwrap();
+
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1000000000 - 10
+    );
+    assert_closed(&program, &oracle_escrow_pda(&test_data, 7));
+    assert_eq!(
+        decode_events::<OracleEscrowRefunded>(&tx)[0].amount_a,
+        1_000_000
+    );
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+#[test]
+fn test_scenario_is_deterministic() {
+    let build = |seed| EscrowScenario::new().keypair_seed(seed).build();
+    let (first, again, other) = (build(7), build(7), build(8));
+
+    // The same seed yields the same actors, mints and escrow
+    assert_eq!(first.makers[0].pubkey(), again.makers[0].pubkey());
+    assert_eq!(first.mint_a, again.mint_a);
+    assert_eq!(first.mint_b, again.mint_b);
+    assert_eq!(first.escrow(0).escrow, again.escrow(0).escrow);
+
+    assert_ne!(first.makers[0].pubkey(), other.makers[0].pubkey());
+    assert_ne!(first.mint_a, other.mint_a);
+}
+
+#[test]
+fn test_scenario_multiple_makers_and_takers() {
+    let mut scenario = EscrowScenario::new()
+        .makers(2)
+        .takers(2)
+        .deposit(100)
+        .receive(50)
+        .build();
+
+    // Each taker fills the escrow of the maker with the same index
+    for i in 0..2 {
+        scenario.send_take(i, i).unwrap();
+    }
+
+    for (maker, taker) in scenario.makers.iter().zip(&scenario.takers) {
+        assert_eq!(
+            scenario.balance(&maker.ata_a),
+            1_000_000_000 - 100,
+            "{}",
+            maker.name
+        );
+        assert_eq!(scenario.balance(&maker.ata_b), 50, "{}", maker.name);
+        assert_eq!(scenario.balance(&taker.ata_a), 100, "{}", taker.name);
+        assert_eq!(
+            scenario.balance(&taker.ata_b),
+            1_000_000_000 - 50,
+            "{}",
+            taker.name
+        );
+    }
+
+    for maker in 0..2 {
+        assert_closed(&scenario.program, &scenario.escrow(maker).escrow);
+    }
+}
+
+#[test]
+fn test_scenario_decimals_and_token_program() {
+    // 2.5 of a 9-decimal Mint A for 10 of a 2-decimal Mint B, both under Token-2022
+    let mut scenario = EscrowScenario::new()
+        .takers(1)
+        .decimals_a(9)
+        .decimals_b(2)
+        .token_program(spl_token_2022::ID)
+        .maker_balance(5_000_000_000)
+        .taker_balance(1_000)
+        .deposit(2_500_000_000)
+        .receive(1_000)
+        .build();
+
+    let test_data = scenario.escrow(0);
+    let escrow = escrow_state(&scenario.program, &test_data);
+    assert_eq!(escrow.deposit, 2_500_000_000);
+    assert_eq!(escrow.receive, 1_000);
+    assert_eq!(
+        scenario
+            .program
+            .get_account(&test_data.vault)
+            .unwrap()
+            .owner,
+        spl_token_2022::ID
+    );
+
+    scenario.send_take(0, 0).unwrap();
+
+    assert_eq!(scenario.balance(&scenario.takers[0].ata_a), 2_500_000_000);
+    assert_eq!(scenario.balance(&scenario.takers[0].ata_b), 0);
+    assert_eq!(scenario.balance(&scenario.makers[0].ata_b), 1_000);
+}
+
+#[test]
+fn test_scenario_maker_balance_below_deposit_fails() {
+    let mut scenario = EscrowScenario::new()
+        .maker_balance(5)
+        .deposit(10)
+        .escrow_seed(9)
+        .fee_bps(100)
+        .make(false)
+        .build();
+
+    let tx = scenario.send_make(0);
+    assert_escrow_error(tx, EscrowError::InsufficientMakerBalance);
+    assert_closed(&scenario.program, &scenario.escrow(0).escrow);
+}
@@ -0,0 +1,405 @@
+use super::*;
+
+#[test]
+fn test_token_2022_transfer_fee_take() {
+    // 1% transfer fee on Mint A, 2% on Mint B
+    let (mut program, test_data) = setup_token_2022(
+        &[MintExtension::TransferFee {
+            basis_points: 100,
+            maximum_fee: u64::MAX,
+        }],
+        &[MintExtension::TransferFee {
+            basis_points: 200,
+            maximum_fee: u64::MAX,
+        }],
+    );
+    let (taker, taker_ata_a, taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+
+    // The maker pays the fee on top, so the vault holds the full deposit
+    // 10,102 less a 1% fee of 102 (rounded up) leaves 10,000
+    assert_eq!(token_balance(&program, &test_data.vault), 10_000);
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1_000_000_000 - 10_102
+    );
+
+    send_take(&mut program, &test_data, &taker).unwrap();
+
+    // The taker pays 5,103 so the maker nets exactly the 5,000 agreed
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 5_000);
+    assert_eq!(token_balance(&program, &taker_ata_b), 1_000_000_000 - 5_103);
+
+    // The taker bears the fee on the way out of the vault
+    assert_eq!(token_balance(&program, &taker_ata_a), 9_900);
+
+    // Withheld fees are harvested to the mint so the vault can be closed
+    assert_closed(&program, &test_data.vault);
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_token_2022_transfer_fee_with_protocol_fee() {
+    let (mut program, test_data) = setup_token_2022(
+        &[],
+        &[MintExtension::TransferFee {
+            basis_points: 200,
+            maximum_fee: u64::MAX,
+        }],
+    );
+    let (taker, _, taker_ata_b) = setup_taker(&mut program, &test_data);
+
+    send_update_config(
+        &mut program,
+        &test_data.payer,
+        250,
+        test_data.fee_recipient,
+        test_data.maker,
+    )
+    .unwrap();
+
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+    let tx = send_take(&mut program, &test_data, &taker).unwrap();
+
+    // Both legs of the 5,000 are grossed up separately: 4,975 for 4,875 and 128 for 125
+    let fee_recipient_ata_b = associated_token::get_associated_token_address_with_program_id(
+        &test_data.fee_recipient,
+        &test_data.mint_b,
+        &test_data.token_program,
+    );
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 4_875);
+    assert_eq!(token_balance(&program, &fee_recipient_ata_b), 125);
+    assert_eq!(
+        token_balance(&program, &taker_ata_b),
+        1_000_000_000 - 4_975 - 128
+    );
+
+    let events = decode_events::<EscrowTaken>(&tx);
+    assert_eq!(events[0].amount_b, 5_000);
+    assert_eq!(events[0].fee, 125);
+}
+
+#[test]
+fn test_token_2022_transfer_fee_take_insufficient_balance_fails() {
+    let (mut program, test_data) = setup_token_2022(
+        &[],
+        &[MintExtension::TransferFee {
+            basis_points: 200,
+            maximum_fee: u64::MAX,
+        }],
+    );
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+
+    // A taker holding exactly `receive` cannot also cover the transfer fee
+    let other = Keypair::new();
+    program.airdrop(&other.pubkey(), LAMPORTS_PER_SOL).unwrap();
+    let other_ata_b = CreateAssociatedTokenAccount::new(&mut program, &other, &test_data.mint_b)
+        .owner(&other.pubkey())
+        .token_program_id(&test_data.token_program)
+        .send()
+        .unwrap();
+    mint_to(
+        &mut program,
+        &test_data,
+        test_data.mint_b,
+        other_ata_b,
+        5_000,
+    );
+
+    assert_escrow_error(
+        send_take(&mut program, &test_data, &other),
+        EscrowError::InsufficientTakerBalance,
+    );
+
+    // A well-funded taker still fills it
+    send_take(&mut program, &test_data, &taker).unwrap();
+}
+
+#[test]
+fn test_token_2022_transfer_fee_maximum_fee() {
+    // A 50% fee capped at 10 tokens per transfer
+    let (mut program, test_data) = setup_token_2022(
+        &[MintExtension::TransferFee {
+            basis_points: 5_000,
+            maximum_fee: 10,
+        }],
+        &[],
+    );
+
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(token_balance(&program, &test_data.vault), 10_000);
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1_000_000_000 - 10_010
+    );
+}
+
+#[test]
+fn test_token_2022_transfer_fee_refund() {
+    let (mut program, test_data) = setup_token_2022(
+        &[MintExtension::TransferFee {
+            basis_points: 100,
+            maximum_fee: u64::MAX,
+        }],
+        &[],
+    );
+
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+    let tx = send_refund(&mut program, &test_data).unwrap();
+
+    // The refund pays the fee on the way out, and the vault still closes
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1_000_000_000 - 10_102 + 9_900
+    );
+    assert_eq!(decode_events::<EscrowRefunded>(&tx)[0].amount_a, 10_000);
+    assert_closed(&program, &test_data.vault);
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_token_2022_interest_bearing() {
+    // 5% a year on Mint A, 10% on Mint B
+    let (mut program, test_data) = setup_token_2022(
+        &[MintExtension::InterestBearing { rate: 500 }],
+        &[MintExtension::InterestBearing { rate: 1_000 }],
+    );
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+
+    // Interest only changes the UI amount, the escrow trades raw amounts
+    let later = now(&program) + 6 * ONE_DAY;
+    warp_to(&mut program, later);
+    send_take(&mut program, &test_data, &taker).unwrap();
+
+    assert_eq!(token_balance(&program, &taker_ata_a), 10_000);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 5_000);
+    assert_closed(&program, &test_data.vault);
+}
+
+#[test]
+fn test_token_2022_partial_fills() {
+    let (mut program, test_data) = setup_token_2022(
+        &[MintExtension::TransferFee {
+            basis_points: 100,
+            maximum_fee: u64::MAX,
+        }],
+        &[MintExtension::InterestBearing { rate: 500 }],
+    );
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+
+    send_take_partial(&mut program, &test_data, &taker, 2_000).unwrap();
+    assert_eq!(token_balance(&program, &test_data.vault), 6_000);
+    assert_eq!(token_balance(&program, &taker_ata_a), 3_960);
+
+    program.expire_blockhash();
+    send_take_partial(&mut program, &test_data, &taker, 3_000).unwrap();
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 5_000);
+    assert_closed(&program, &test_data.vault);
+}
+
+#[test]
+fn test_token_2022_non_transferable_fails() {
+    // A non-transferable deposit could never leave the vault
+    let (mut program, test_data) = setup_token_2022(&[MintExtension::NonTransferable], &[]);
+
+    assert_escrow_error(
+        send_make(
+            &mut program,
+            &test_data,
+            123,
+            test_data.mint_b,
+            10_000,
+            5_000,
+            None,
+        ),
+        EscrowError::UnsupportedMintExtension,
+    );
+
+    // Nor could a taker ever pay in a non-transferable mint
+    let (mut program, test_data) = setup_token_2022(&[], &[MintExtension::NonTransferable]);
+
+    assert_escrow_error(
+        send_make(
+            &mut program,
+            &test_data,
+            123,
+            test_data.mint_b,
+            10_000,
+            5_000,
+            None,
+        ),
+        EscrowError::UnsupportedMintExtension,
+    );
+}
+
+#[test]
+fn test_transfer_hook_make_and_take() {
+    let (mut program, test_data) = setup_token_2022(
+        &[MintExtension::TransferHook],
+        &[MintExtension::TransferHook],
+    );
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+
+    // Minting does not run the hook
+    assert_eq!(transfer_hook_count(&program, &test_data.mint_a), 0);
+    assert_eq!(transfer_hook_count(&program, &test_data.mint_b), 0);
+
+    // The deposit leg runs the Mint A hook
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+    assert_eq!(transfer_hook_count(&program, &test_data.mint_a), 1);
+    assert_eq!(token_balance(&program, &test_data.vault), 10_000);
+
+    // The take runs the Mint B hook for the payment and the Mint A hook for the withdrawal
+    send_take(&mut program, &test_data, &taker).unwrap();
+    assert_eq!(transfer_hook_count(&program, &test_data.mint_a), 2);
+    assert_eq!(transfer_hook_count(&program, &test_data.mint_b), 1);
+
+    assert_eq!(token_balance(&program, &taker_ata_a), 10_000);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 5_000);
+    assert_closed(&program, &test_data.vault);
+}
+
+#[test]
+fn test_transfer_hook_take_with_fee() {
+    let (mut program, test_data) = setup_token_2022(&[], &[MintExtension::TransferHook]);
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+
+    send_update_config(
+        &mut program,
+        &test_data.payer,
+        250,
+        test_data.fee_recipient,
+        test_data.maker,
+    )
+    .unwrap();
+
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+
+    // Paying the maker and the fee recipient are separate transfers, each running the hook
+    send_take_partial(&mut program, &test_data, &taker, 2_000).unwrap();
+    assert_eq!(transfer_hook_count(&program, &test_data.mint_b), 2);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 1_950);
+}
+
+#[test]
+fn test_transfer_hook_refund() {
+    let (mut program, test_data) = setup_token_2022(&[MintExtension::TransferHook], &[]);
+
+    send_make(
+        &mut program,
+        &test_data,
+        123,
+        test_data.mint_b,
+        10_000,
+        5_000,
+        None,
+    )
+    .unwrap();
+
+    // Without the hook accounts the refund cannot resolve the hook and fails
+    let mut refund_ix = refund_ix(&program, &test_data);
+    let hook_accounts = transfer_hook_accounts(&program, &[test_data.mint_a]);
+    refund_ix
+        .accounts
+        .truncate(refund_ix.accounts.len() - hook_accounts.len());
+    assert!(send_signed(&mut program, &test_data.payer, refund_ix).is_err());
+    assert_eq!(transfer_hook_count(&program, &test_data.mint_a), 1);
+
+    // With them the refund leg runs the hook
+    send_refund(&mut program, &test_data).unwrap();
+    assert_eq!(transfer_hook_count(&program, &test_data.mint_a), 2);
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1_000_000_000
+    );
+    assert_closed(&program, &test_data.vault);
+}
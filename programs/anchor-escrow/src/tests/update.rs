@@ -0,0 +1,237 @@
+use super::*;
+
+// Build and send the "Update" instruction, signed by `test_data.payer` as the maker
+pub(super) fn send_update(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    deposit: Option<u64>,
+    receive: Option<u64>,
+    expiry: Option<i64>,
+    allowed_taker: Option<Option<Pubkey>>,
+) -> TransactionResult {
+    let mut update_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::Update {
+            maker: test_data.maker,
+            mint_a: test_data.mint_a,
+            maker_ata_a: test_data.maker_ata_a,
+            escrow: test_data.escrow,
+            vault: test_data.vault,
+            token_program: test_data.token_program,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::Update {
+            deposit,
+            receive,
+            expiry,
+            allowed_taker,
+        }
+        .data(),
+    };
+    update_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[update_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+#[test]
+fn test_update_raise_deposit() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+
+    // Offer 30 of Mint A for 15 of Mint B instead of 10 for 10
+    let tx = send_update(&mut program, &test_data, Some(30), Some(15), None, None).unwrap();
+
+    assert_eq!(token_balance(&program, &test_data.vault), 30);
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1_000_000_000 - 30
+    );
+
+    let escrow_data = escrow_state(&program, &test_data);
+    assert_eq!(escrow_data.deposit, 30);
+    assert_eq!(escrow_data.receive, 15);
+    assert_eq!(escrow_data.remaining_a, 30);
+    assert_eq!(escrow_data.remaining_b, 15);
+
+    let events = decode_events::<EscrowUpdated>(&tx);
+    assert_eq!(events[0].seed, 123);
+    assert_eq!(events[0].remaining_a, 30);
+    assert_eq!(events[0].remaining_b, 15);
+
+    // Takers fill at the new terms
+    send_take(&mut program, &test_data, &taker).unwrap();
+    assert_eq!(token_balance(&program, &taker_ata_a), 30);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 15);
+    assert_closed(&program, &test_data.escrow);
+}
+
+#[test]
+fn test_update_lower_deposit() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+
+    // Withdraw 6 of the 10 deposited, keeping the same receive amount
+    send_update(&mut program, &test_data, Some(4), None, None, None).unwrap();
+
+    assert_eq!(token_balance(&program, &test_data.vault), 4);
+    assert_eq!(
+        token_balance(&program, &test_data.maker_ata_a),
+        1_000_000_000 - 4
+    );
+
+    // The escrow stays open at the same address, now asking 10 of Mint B for 4 of Mint A
+    let escrow_data = escrow_state(&program, &test_data);
+    assert_eq!(escrow_data.deposit, 4);
+    assert_eq!(escrow_data.receive, 10);
+
+    send_take_partial(&mut program, &test_data, &taker, 5).unwrap();
+    assert_eq!(token_balance(&program, &taker_ata_a), 2);
+}
+
+#[test]
+fn test_update_after_partial_fill() {
+    let (mut program, test_data, _tx) = setup_with_make_args(100, 50, ONE_WEEK, 0);
+    let (taker, taker_ata_a, _) = setup_taker(&mut program, &test_data);
+
+    send_take_partial(&mut program, &test_data, &taker, 10).unwrap();
+    assert_eq!(token_balance(&program, &taker_ata_a), 20);
+
+    // The new receive amount prices the 80 still on offer
+    send_update(&mut program, &test_data, None, Some(80), None, None).unwrap();
+
+    let escrow_data = escrow_state(&program, &test_data);
+    assert_eq!(escrow_data.remaining_a, 80);
+    assert_eq!(escrow_data.remaining_b, 80);
+
+    send_take(&mut program, &test_data, &taker).unwrap();
+    assert_eq!(token_balance(&program, &taker_ata_a), 100);
+    assert_eq!(token_balance(&program, &test_data.maker_ata_b), 90);
+    assert_closed(&program, &test_data.vault);
+}
+
+#[test]
+fn test_update_withdraw_during_lock_fails() {
+    let (mut program, test_data, tx) = setup_with_timed_make(ONE_WEEK, ONE_DAY);
+    tx.unwrap();
+
+    // Withdrawing is a partial refund and waits for the lock
+    assert_escrow_error(
+        send_update(&mut program, &test_data, Some(5), None, None, None),
+        EscrowError::RefundLocked,
+    );
+
+    // Adding to the deposit and repricing are still allowed
+    send_update(&mut program, &test_data, Some(20), Some(5), None, None).unwrap();
+    assert_eq!(token_balance(&program, &test_data.vault), 20);
+
+    let unlock_at = escrow_state(&program, &test_data).unlock_at;
+    warp_to(&mut program, unlock_at);
+    send_update(&mut program, &test_data, Some(5), None, None, None).unwrap();
+    assert_eq!(token_balance(&program, &test_data.vault), 5);
+}
+
+#[test]
+fn test_update_expiry_and_allowed_taker() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let (taker, _, _) = setup_taker(&mut program, &test_data);
+
+    // Restrict the escrow to another taker and extend it by a month
+    let expiry = now(&program) + 30 * ONE_DAY;
+    let other_taker = Pubkey::new_unique();
+    send_update(
+        &mut program,
+        &test_data,
+        None,
+        None,
+        Some(expiry),
+        Some(Some(other_taker)),
+    )
+    .unwrap();
+
+    let escrow_data = escrow_state(&program, &test_data);
+    assert_eq!(escrow_data.expiry, expiry);
+    assert_eq!(escrow_data.allowed_taker, Some(other_taker));
+    assert_eq!(escrow_data.deposit, 10);
+    assert_eq!(escrow_data.receive, 10);
+
+    assert_escrow_error(
+        send_take(&mut program, &test_data, &taker),
+        EscrowError::UnauthorizedTaker,
+    );
+
+    // Past the original expiry, with the restriction lifted, anyone can take
+    let later = now(&program) + 2 * ONE_WEEK;
+    warp_to(&mut program, later);
+    send_update(&mut program, &test_data, None, None, None, Some(None)).unwrap();
+    send_take(&mut program, &test_data, &taker).unwrap();
+}
+
+#[test]
+fn test_update_invalid_terms_fail() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    assert_escrow_error(
+        send_update(&mut program, &test_data, Some(0), None, None, None),
+        EscrowError::ZeroDeposit,
+    );
+    assert_escrow_error(
+        send_update(&mut program, &test_data, None, Some(0), None, None),
+        EscrowError::ZeroReceive,
+    );
+
+    let past = now(&program) - 1;
+    assert_escrow_error(
+        send_update(&mut program, &test_data, None, None, Some(past), None),
+        EscrowError::InvalidExpiry,
+    );
+    assert_escrow_error(
+        send_update(
+            &mut program,
+            &test_data,
+            Some(2_000_000_000),
+            None,
+            None,
+            None,
+        ),
+        EscrowError::InsufficientMakerBalance,
+    );
+}
+
+#[test]
+fn test_update_non_maker_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+
+    let impostor = Keypair::new();
+    program
+        .airdrop(&impostor.pubkey(), LAMPORTS_PER_SOL)
+        .expect("Failed to airdrop SOL to impostor");
+    let impostor_ata_a =
+        CreateAssociatedTokenAccount::new(&mut program, &impostor, &test_data.mint_a)
+            .owner(&impostor.pubkey())
+            .send()
+            .unwrap();
+
+    let impostor_data = TestData {
+        maker: impostor.pubkey(),
+        maker_ata_a: impostor_ata_a,
+        payer: impostor,
+        ..test_data
+    };
+
+    // The impostor can neither drain the vault nor reprice the offer
+    assert_anchor_error(
+        send_update(&mut program, &impostor_data, Some(1), None, None, None),
+        ErrorCode::ConstraintSeeds,
+    );
+    assert_anchor_error(
+        send_update(&mut program, &impostor_data, None, Some(1), None, None),
+        ErrorCode::ConstraintSeeds,
+    );
+}
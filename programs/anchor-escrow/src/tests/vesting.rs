@@ -0,0 +1,295 @@
+use super::*;
+
+// Derive the vesting escrow PDA and its vault for the maker's `seed`
+fn vesting_pdas(test_data: &TestData, seed: u64) -> (Pubkey, Pubkey) {
+    let escrow = Pubkey::find_program_address(
+        &[b"vesting", test_data.maker.as_ref(), &seed.to_le_bytes()],
+        &PROGRAM_ID,
+    )
+    .0;
+    let vault = associated_token::get_associated_token_address_with_program_id(
+        &escrow,
+        &test_data.mint_a,
+        &test_data.token_program,
+    );
+
+    (escrow, vault)
+}
+
+fn vesting_state(program: &LiteSVM, escrow: &Pubkey) -> crate::state::VestingEscrow {
+    let account = program.get_account(escrow).unwrap();
+    crate::state::VestingEscrow::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+// Build and send the "MakeVesting" instruction locking `amount` of Mint A
+#[allow(clippy::too_many_arguments)]
+fn send_make_vesting(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    beneficiary: Pubkey,
+    amount: u64,
+    start: i64,
+    cliff: i64,
+    end: i64,
+) -> TransactionResult {
+    let (escrow, vault) = vesting_pdas(test_data, seed);
+
+    let mut make_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::MakeVesting {
+            maker: test_data.maker,
+            mint: test_data.mint_a,
+            maker_ata: test_data.maker_ata_a,
+            escrow,
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::MakeVesting {
+            seed,
+            beneficiary,
+            amount,
+            start,
+            cliff,
+            end,
+        }
+        .data(),
+    };
+    make_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[make_ix], Some(&test_data.maker));
+    let transaction = Transaction::new(&[&test_data.payer], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+fn send_claim_vested(
+    program: &mut LiteSVM,
+    test_data: &TestData,
+    seed: u64,
+    beneficiary: &Keypair,
+) -> TransactionResult {
+    let (escrow, vault) = vesting_pdas(test_data, seed);
+    let beneficiary_ata = associated_token::get_associated_token_address_with_program_id(
+        &beneficiary.pubkey(),
+        &test_data.mint_a,
+        &test_data.token_program,
+    );
+
+    let mut claim_ix = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: crate::accounts::ClaimVested {
+            beneficiary: beneficiary.pubkey(),
+            maker: test_data.maker,
+            mint: test_data.mint_a,
+            beneficiary_ata,
+            escrow,
+            vault,
+            associated_token_program: spl_associated_token_account::ID,
+            token_program: test_data.token_program,
+            system_program: SYSTEM_PROGRAM_ID,
+            event_authority: event_authority(),
+            program: PROGRAM_ID,
+        }
+        .to_account_metas(None),
+        data: crate::instruction::ClaimVested {}.data(),
+    };
+    claim_ix
+        .accounts
+        .extend(transfer_hook_accounts(program, &[test_data.mint_a]));
+
+    let message = Message::new(&[claim_ix], Some(&beneficiary.pubkey()));
+    let transaction = Transaction::new(&[beneficiary], message, program.latest_blockhash());
+
+    program.send_transaction(transaction)
+}
+
+#[test]
+fn test_vesting_linear_schedule_with_cliff() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let beneficiary = funded_keypair(&mut program);
+    let (escrow, vault) = vesting_pdas(&test_data, 11);
+    let beneficiary_ata =
+        associated_token::get_associated_token_address(&beneficiary.pubkey(), &test_data.mint_a);
+
+    // 1,000 vesting linearly over four days, with a one day cliff
+    let start = now(&program);
+    let tx = send_make_vesting(
+        &mut program,
+        &test_data,
+        11,
+        beneficiary.pubkey(),
+        1_000,
+        start,
+        start + ONE_DAY,
+        start + 4 * ONE_DAY,
+    )
+    .unwrap();
+
+    assert_eq!(token_balance(&program, &vault), 1_000);
+    let events = decode_events::<VestingEscrowCreated>(&tx);
+    assert_eq!(events[0].beneficiary, beneficiary.pubkey());
+    assert_eq!(events[0].cliff, start + ONE_DAY);
+
+    // Nothing can be claimed before the cliff, even though time has passed since the start
+    warp_to(&mut program, start + ONE_DAY - 1);
+    assert_escrow_error(
+        send_claim_vested(&mut program, &test_data, 11, &beneficiary),
+        EscrowError::NothingToClaim,
+    );
+
+    // At the cliff, the first quarter has vested
+    warp_to(&mut program, start + ONE_DAY);
+    let tx = send_claim_vested(&mut program, &test_data, 11, &beneficiary).unwrap();
+    assert_eq!(token_balance(&program, &beneficiary_ata), 250);
+    assert_eq!(token_balance(&program, &vault), 750);
+
+    let events = decode_events::<VestedClaimed>(&tx);
+    assert_eq!(events[0].amount, 250);
+    assert_eq!(events[0].claimed, 250);
+
+    // Claiming again at the same time yields nothing new
+    program.expire_blockhash();
+    assert_escrow_error(
+        send_claim_vested(&mut program, &test_data, 11, &beneficiary),
+        EscrowError::NothingToClaim,
+    );
+
+    // Halfway between the third and fourth day, 625 has vested in total
+    warp_to(&mut program, start + 2 * ONE_DAY + ONE_DAY / 2);
+    send_claim_vested(&mut program, &test_data, 11, &beneficiary).unwrap();
+    assert_eq!(token_balance(&program, &beneficiary_ata), 625);
+    assert_eq!(vesting_state(&program, &escrow).claimed, 625);
+
+    // Past the end, the rest is claimable and the escrow is closed
+    warp_to(&mut program, start + 5 * ONE_DAY);
+    let tx = send_claim_vested(&mut program, &test_data, 11, &beneficiary).unwrap();
+    assert_eq!(token_balance(&program, &beneficiary_ata), 1_000);
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+
+    let events = decode_events::<VestedClaimed>(&tx);
+    assert_eq!(events[0].amount, 375);
+    assert_eq!(events[0].claimed, 1_000);
+}
+
+#[test]
+fn test_vesting_final_claim_sweeps_excess() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let beneficiary = funded_keypair(&mut program);
+    let (escrow, vault) = vesting_pdas(&test_data, 11);
+    let beneficiary_ata =
+        associated_token::get_associated_token_address(&beneficiary.pubkey(), &test_data.mint_a);
+
+    let start = now(&program);
+    send_make_vesting(
+        &mut program,
+        &test_data,
+        11,
+        beneficiary.pubkey(),
+        1_000,
+        start,
+        start,
+        start + ONE_DAY,
+    )
+    .unwrap();
+
+    // Tokens sent straight to the vault would otherwise block closing it
+    mint_to(&mut program, &test_data, test_data.mint_a, vault, 5);
+
+    warp_to(&mut program, start + ONE_DAY);
+    let tx = send_claim_vested(&mut program, &test_data, 11, &beneficiary).unwrap();
+    assert_eq!(token_balance(&program, &beneficiary_ata), 1_005);
+    assert_closed(&program, &vault);
+    assert_closed(&program, &escrow);
+
+    // Only the schedule counts as claimed
+    let events = decode_events::<VestedClaimed>(&tx);
+    assert_eq!(events[0].amount, 1_000);
+}
+
+#[test]
+fn test_vesting_claim_by_non_beneficiary_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let beneficiary = Pubkey::new_unique();
+
+    let start = now(&program);
+    send_make_vesting(
+        &mut program,
+        &test_data,
+        11,
+        beneficiary,
+        1_000,
+        start,
+        start,
+        start + ONE_DAY,
+    )
+    .unwrap();
+
+    warp_to(&mut program, start + ONE_DAY);
+
+    // Neither an outsider nor the maker can claim on the beneficiary's behalf
+    let outsider = funded_keypair(&mut program);
+    for claimer in [&outsider, &test_data.payer] {
+        assert_escrow_error(
+            send_claim_vested(&mut program, &test_data, 11, claimer),
+            EscrowError::InvalidBeneficiary,
+        );
+    }
+}
+
+#[test]
+fn test_vesting_invalid_schedule_fails() {
+    let (mut program, test_data, _tx) = setup_with_make();
+    let beneficiary = Pubkey::new_unique();
+    let start = now(&program);
+
+    // (amount, start, cliff, end, expected error)
+    let cases = [
+        (0, start, start, start + ONE_DAY, EscrowError::ZeroDeposit),
+        (
+            1_000,
+            start,
+            start - 1,
+            start + ONE_DAY,
+            EscrowError::InvalidVestingSchedule,
+        ),
+        (
+            1_000,
+            start,
+            start + 2 * ONE_DAY,
+            start + ONE_DAY,
+            EscrowError::InvalidVestingSchedule,
+        ),
+        (
+            1_000,
+            start,
+            start,
+            start,
+            EscrowError::InvalidVestingSchedule,
+        ),
+    ];
+
+    for (amount, start, cliff, end, expected) in cases {
+        assert_escrow_error(
+            send_make_vesting(
+                &mut program,
+                &test_data,
+                11,
+                beneficiary,
+                amount,
+                start,
+                cliff,
+                end,
+            ),
+            expected,
+        );
+    }
+}